@@ -0,0 +1,350 @@
+//! configurable behavior of the test service.
+//!
+//! by default, the test service responds immediately with a `200 OK`, echoing the request body.
+//! latency, errors, and the size and pace of response bodies may be injected, see [`Behavior`].
+
+use {
+    super::{faults::Faults, Error},
+    futures::{stream, StreamExt},
+    http::StatusCode,
+    http_body_util::{combinators::BoxBody, BodyExt, Full, StreamBody},
+    hyper::body::{Bytes, Frame},
+    rand::Rng,
+    std::{fmt, str::FromStr, time::Duration},
+};
+
+/// the body of a test service response.
+pub(super) type Body = BoxBody<Bytes, hyper::Error>;
+
+/// how the test service responds to requests.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct Behavior {
+    /// how long to wait before responding.
+    pub(super) latency: Latency,
+    /// the percentage of requests that receive an error response, from 0 to 100.
+    pub(super) error_rate: f64,
+    /// the status codes of error responses.
+    ///
+    /// each error response is given one of these at random.
+    pub(super) error_statuses: Vec<StatusCode>,
+    /// the size of each response body, in bytes.
+    ///
+    /// if this is `None`, the request body is echoed back to the client.
+    pub(super) body_size: Option<usize>,
+    /// if set, the response body is sent in chunks, with a delay between each.
+    pub(super) drip: Option<Drip>,
+    /// protocol-level faults to inject.
+    pub(super) faults: Faults,
+}
+
+/// a distribution of response latencies.
+///
+/// latencies may be parsed from a string:
+/// * `fixed:10ms`, always waits for 10 milliseconds.
+/// * `uniform:5ms..50ms`, waits between 5 and 50 milliseconds.
+/// * `normal:20ms,5ms`, waits for a mean of 20 milliseconds, with a standard deviation of 5.
+/// * `pareto:5ms,1.5`, waits at least 5 milliseconds, with a long tail of shape 1.5.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Latency {
+    /// respond immediately.
+    #[default]
+    None,
+    /// always wait for the same amount of time.
+    Fixed(Duration),
+    /// wait for an amount of time chosen uniformly from `min` to `max`.
+    Uniform {
+        /// the shortest wait.
+        min: Duration,
+        /// the longest wait.
+        max: Duration,
+    },
+    /// wait for a normally distributed amount of time.
+    ///
+    /// negative samples are clamped to zero.
+    Normal {
+        /// the mean wait.
+        mean: Duration,
+        /// the standard deviation of the wait.
+        stddev: Duration,
+    },
+    /// wait for an amount of time following a pareto distribution.
+    ///
+    /// most waits are close to `min`, but a long tail of waits are much longer. a smaller `shape`
+    /// makes for a longer tail.
+    Pareto {
+        /// the shortest wait.
+        min: Duration,
+        /// the shape of the distribution, which must be positive.
+        shape: f64,
+    },
+}
+
+/// sends response bodies in chunks, with a delay between each.
+///
+/// drips may be parsed from a string written as `SIZE,INTERVAL`, e.g. `64,10ms`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Drip {
+    /// the size of each chunk, in bytes.
+    pub chunk_size: usize,
+    /// how long to wait before sending each chunk after the first.
+    pub interval: Duration,
+}
+
+// === impl Behavior ===
+
+impl Behavior {
+    /// the status code of error responses, if none were given.
+    pub(super) const DEFAULT_ERROR_STATUS: StatusCode = StatusCode::SERVICE_UNAVAILABLE;
+
+    /// returns the status code that the next response should have.
+    pub(super) fn status(&self, rng: &mut impl Rng) -> StatusCode {
+        if self.error_rate <= 0.0 || rng.gen::<f64>() * 100.0 >= self.error_rate {
+            return StatusCode::OK;
+        }
+        match self.error_statuses.as_slice() {
+            [] => Self::DEFAULT_ERROR_STATUS,
+            statuses => statuses[rng.gen_range(0..statuses.len())],
+        }
+    }
+
+    /// returns a response body, given the body of the request.
+    pub(super) async fn body(&self, request: hyper::body::Incoming) -> Result<Body, hyper::Error> {
+        let body = match (self.body_size, self.drip) {
+            // echo the request body, streaming it back to the client.
+            (None, None) => return Ok(request.boxed()),
+            // echo the request body, which must be read before it is split into chunks.
+            (None, Some(_)) => request.collect().await?.to_bytes(),
+            (Some(size), _) => Bytes::from(vec![b'a'; size]),
+        };
+
+        let Some(Drip {
+            chunk_size,
+            interval,
+        }) = self.drip
+        else {
+            return Ok(Full::new(body).map_err(|never| match never {}).boxed());
+        };
+
+        // send each chunk after waiting for the interval, sending the first immediately.
+        let chunks = (0..body.len())
+            .step_by(chunk_size.max(1))
+            .map(move |start| body.slice(start..body.len().min(start + chunk_size.max(1))))
+            .enumerate()
+            .collect::<Vec<_>>();
+        let frames = stream::iter(chunks).then(move |(i, chunk)| async move {
+            if i > 0 {
+                tokio::time::sleep(interval).await;
+            }
+            Ok(Frame::data(chunk))
+        });
+        Ok(BodyExt::boxed(StreamBody::new(frames)))
+    }
+}
+
+// === impl Latency ===
+
+impl Latency {
+    /// returns how long to wait before responding.
+    pub fn sample(&self, rng: &mut impl Rng) -> Duration {
+        match *self {
+            Self::None => Duration::ZERO,
+            Self::Fixed(latency) => latency,
+            Self::Uniform { min, max } if min >= max => min,
+            Self::Uniform { min, max } => rng.gen_range(min..=max),
+            Self::Normal { mean, stddev } => {
+                // use the box-muller transform to sample a standard normal distribution.
+                let (u1, u2) = (1.0 - rng.gen::<f64>(), rng.gen::<f64>());
+                let z = (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+                Duration::from_secs_f64((mean.as_secs_f64() + z * stddev.as_secs_f64()).max(0.0))
+            }
+            Self::Pareto { min, shape } => {
+                // use inverse transform sampling, bounding samples to avoid overflowing.
+                let u = 1.0 - rng.gen::<f64>();
+                let factor = u.powf(-1.0 / shape).min(1e6);
+                min.mul_f64(factor)
+            }
+        }
+    }
+}
+
+impl FromStr for Latency {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let duration = |s: &str| humantime::parse_duration(s.trim());
+        let (kind, params) = s
+            .split_once(':')
+            .ok_or("latency must be written as `KIND:PARAMS`, e.g. `fixed:10ms`")?;
+        let pair = |sep| {
+            params
+                .split_once(sep)
+                .ok_or_else(|| format!("`{kind}` latency must be written as `{kind}:A{sep}B`"))
+        };
+
+        match kind.trim() {
+            "fixed" => Ok(Self::Fixed(duration(params)?)),
+            "uniform" => {
+                let (min, max) = pair("..")?;
+                let (min, max) = (duration(min)?, duration(max)?);
+                if min > max {
+                    return Err("uniform latency minimum must not exceed its maximum".into());
+                }
+                Ok(Self::Uniform { min, max })
+            }
+            "normal" => {
+                let (mean, stddev) = pair(",")?;
+                Ok(Self::Normal {
+                    mean: duration(mean)?,
+                    stddev: duration(stddev)?,
+                })
+            }
+            "pareto" => {
+                let (min, shape) = pair(",")?;
+                let shape = shape.trim().parse::<f64>()?;
+                if !(shape.is_finite() && shape > 0.0) {
+                    return Err("pareto latency shape must be positive".into());
+                }
+                Ok(Self::Pareto {
+                    min: duration(min)?,
+                    shape,
+                })
+            }
+            other => Err(format!(
+                "unknown latency `{other}`, expected `fixed`, `uniform`, `normal`, or `pareto`"
+            )
+            .into()),
+        }
+    }
+}
+
+impl fmt::Display for Latency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use humantime::format_duration as d;
+        match *self {
+            Self::None => f.write_str("none"),
+            Self::Fixed(latency) => write!(f, "fixed:{}", d(latency)),
+            Self::Uniform { min, max } => write!(f, "uniform:{}..{}", d(min), d(max)),
+            Self::Normal { mean, stddev } => write!(f, "normal:{},{}", d(mean), d(stddev)),
+            Self::Pareto { min, shape } => write!(f, "pareto:{},{shape}", d(min)),
+        }
+    }
+}
+
+// === impl Drip ===
+
+impl FromStr for Drip {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (chunk_size, interval) = s
+            .split_once(',')
+            .ok_or("drip must be written as `SIZE,INTERVAL`, e.g. `64,10ms`")?;
+        let chunk_size = chunk_size.trim().parse::<usize>()?;
+        if chunk_size == 0 {
+            return Err("drip chunk size must be positive".into());
+        }
+        Ok(Self {
+            chunk_size,
+            interval: humantime::parse_duration(interval.trim())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, rand::SeedableRng};
+
+    /// returns a deterministic random number generator.
+    fn rng() -> rand::rngs::StdRng {
+        rand::rngs::StdRng::seed_from_u64(0x5eed)
+    }
+
+    #[test]
+    fn latencies_can_be_parsed() -> Result<(), Error> {
+        const MS: fn(u64) -> Duration = Duration::from_millis;
+        assert_eq!("fixed:10ms".parse::<Latency>()?, Latency::Fixed(MS(10)));
+        assert_eq!(
+            "uniform:5ms..50ms".parse::<Latency>()?,
+            Latency::Uniform {
+                min: MS(5),
+                max: MS(50)
+            }
+        );
+        assert_eq!(
+            "normal:20ms, 5ms".parse::<Latency>()?,
+            Latency::Normal {
+                mean: MS(20),
+                stddev: MS(5)
+            }
+        );
+        assert_eq!(
+            "pareto:5ms,1.5".parse::<Latency>()?,
+            Latency::Pareto {
+                min: MS(5),
+                shape: 1.5
+            }
+        );
+        for latency in ["fixed:10ms", "uniform:5ms..50ms", "pareto:5ms,1.5"] {
+            assert_eq!(latency.parse::<Latency>()?.to_string(), latency);
+        }
+        "10ms".parse::<Latency>().unwrap_err();
+        "uniform:50ms..5ms".parse::<Latency>().unwrap_err();
+        "pareto:5ms,0".parse::<Latency>().unwrap_err();
+        "gamma:5ms".parse::<Latency>().unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn latencies_are_sampled_within_bounds() {
+        let mut rng = rng();
+        let (min, max) = (Duration::from_millis(5), Duration::from_millis(50));
+        let uniform = Latency::Uniform { min, max };
+        let pareto = Latency::Pareto { min, shape: 1.5 };
+        let normal = Latency::Normal {
+            mean: Duration::from_millis(1),
+            stddev: Duration::from_millis(10),
+        };
+        for _ in 0..1000 {
+            assert!((min..=max).contains(&uniform.sample(&mut rng)));
+            assert!(pareto.sample(&mut rng) >= min);
+            normal.sample(&mut rng); // NB: negative samples are clamped, rather than panicking.
+        }
+        assert_eq!(Latency::None.sample(&mut rng), Duration::ZERO);
+    }
+
+    #[test]
+    fn errors_are_injected_at_the_given_rate() {
+        let mut rng = rng();
+        let behavior = Behavior {
+            error_rate: 25.0,
+            error_statuses: vec![StatusCode::TOO_MANY_REQUESTS, StatusCode::BAD_GATEWAY],
+            ..Default::default()
+        };
+        let statuses = (0..10_000)
+            .map(|_| behavior.status(&mut rng))
+            .collect::<Vec<_>>();
+        let errors = statuses.iter().filter(|s| **s != StatusCode::OK).count();
+        assert!((2_000..3_000).contains(&errors), "{errors} errors");
+        assert!(statuses.contains(&StatusCode::TOO_MANY_REQUESTS));
+        assert!(statuses.contains(&StatusCode::BAD_GATEWAY));
+
+        let behavior = Behavior {
+            error_rate: 100.0,
+            ..Default::default()
+        };
+        assert_eq!(behavior.status(&mut rng), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(Behavior::default().status(&mut rng), StatusCode::OK);
+    }
+
+    #[test]
+    fn drips_can_be_parsed() -> Result<(), Error> {
+        assert_eq!(
+            "64,10ms".parse::<Drip>()?,
+            Drip {
+                chunk_size: 64,
+                interval: Duration::from_millis(10)
+            }
+        );
+        "0,10ms".parse::<Drip>().unwrap_err();
+        "64".parse::<Drip>().unwrap_err();
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use {super::*, faults::Faults, h2::Reason, std::num::NonZeroU32};
+
+/// a [`TestServer`] builder.
+///
+/// by default, the test server responds to each request immediately with a `200 OK`, echoing the
+/// request body. use this to inject latency, errors, and larger or slower response bodies.
+#[derive(Clone, Debug, Default)]
+pub struct Builder {
+    behavior: Behavior,
+    /// if true, tls clients must present a certificate signed by the server's authority.
+    client_auth: bool,
+}
+
+// === impl Builder ===
+
+impl Builder {
+    /// sets the distribution of latencies to wait for before responding.
+    pub fn latency(self, latency: Latency) -> Self {
+        let behavior = Behavior {
+            latency,
+            ..self.behavior
+        };
+        Self { behavior, ..self }
+    }
+
+    /// sets the percentage of requests, from 0 to 100, that receive an error response.
+    ///
+    /// see [`Builder::error_statuses()`].
+    pub fn error_rate(self, percent: f64) -> Self {
+        let behavior = Behavior {
+            error_rate: percent.clamp(0.0, 100.0),
+            ..self.behavior
+        };
+        Self { behavior, ..self }
+    }
+
+    /// sets the status codes of error responses, e.g. `503 Service Unavailable`.
+    ///
+    /// each error response is given one of these at random. if this is empty, errors are
+    /// `503 Service Unavailable` responses.
+    pub fn error_statuses(self, error_statuses: Vec<StatusCode>) -> Self {
+        let behavior = Behavior {
+            error_statuses,
+            ..self.behavior
+        };
+        Self { behavior, ..self }
+    }
+
+    /// sets the size of each response body, in bytes.
+    ///
+    /// if this is `None`, the request body is echoed back to the client.
+    pub fn body_size(self, body_size: Option<usize>) -> Self {
+        let behavior = Behavior {
+            body_size,
+            ..self.behavior
+        };
+        Self { behavior, ..self }
+    }
+
+    /// sends response bodies in chunks, with a delay between each.
+    ///
+    /// if this is `None`, response bodies are sent as quickly as possible.
+    pub fn drip(self, drip: Option<Drip>) -> Self {
+        let behavior = Behavior {
+            drip,
+            ..self.behavior
+        };
+        Self { behavior, ..self }
+    }
+
+    /// shuts down each connection with a GOAWAY frame, after it has received `streams` streams.
+    ///
+    /// streams that are already open may finish, but the client must open a new connection to
+    /// send more requests.
+    pub fn goaway_after(self, streams: Option<NonZeroU32>) -> Self {
+        let faults = Faults {
+            goaway_after: streams,
+            ..self.behavior.faults
+        };
+        let behavior = Behavior {
+            faults,
+            ..self.behavior
+        };
+        Self { behavior, ..self }
+    }
+
+    /// sets the percentage of streams, from 0 to 100, that are reset with a RST_STREAM frame.
+    ///
+    /// see [`Builder::reset_reasons()`].
+    pub fn reset_rate(self, percent: f64) -> Self {
+        let faults = Faults {
+            reset_rate: percent.clamp(0.0, 100.0),
+            ..self.behavior.faults
+        };
+        let behavior = Behavior {
+            faults,
+            ..self.behavior
+        };
+        Self { behavior, ..self }
+    }
+
+    /// sets the error codes that streams are reset with, e.g. `REFUSED_STREAM`.
+    ///
+    /// each reset stream is given one of these at random. if this is empty, streams are reset
+    /// with `REFUSED_STREAM`.
+    pub fn reset_reasons(self, reset_reasons: Vec<Reason>) -> Self {
+        let faults = Faults {
+            reset_reasons,
+            ..self.behavior.faults
+        };
+        let behavior = Behavior {
+            faults,
+            ..self.behavior
+        };
+        Self { behavior, ..self }
+    }
+
+    /// sets the maximum number of streams that each connection may have open at once.
+    pub fn max_concurrent_streams(self, max_concurrent_streams: Option<u32>) -> Self {
+        let faults = Faults {
+            max_concurrent_streams,
+            ..self.behavior.faults
+        };
+        let behavior = Behavior {
+            faults,
+            ..self.behavior
+        };
+        Self { behavior, ..self }
+    }
+
+    /// if true, clients are never given flow-control capacity to send request bodies.
+    ///
+    /// requests with a body larger than the default window of 64KiB will stall, while smaller
+    /// requests are unaffected.
+    pub fn stall(self, stall: bool) -> Self {
+        let faults = Faults {
+            stall,
+            ..self.behavior.faults
+        };
+        let behavior = Behavior {
+            faults,
+            ..self.behavior
+        };
+        Self { behavior, ..self }
+    }
+
+    /// sets the percentage of responses, from 0 to 100, during which the connection is dropped.
+    ///
+    /// the response headers and part of the body are sent, before the tcp connection is closed
+    /// without a GOAWAY frame. other streams on the connection are also interrupted.
+    pub fn close_rate(self, percent: f64) -> Self {
+        let faults = Faults {
+            close_rate: percent.clamp(0.0, 100.0),
+            ..self.behavior.faults
+        };
+        let behavior = Behavior {
+            faults,
+            ..self.behavior
+        };
+        Self { behavior, ..self }
+    }
+
+    /// requires tls clients to present a certificate signed by the server's certificate authority.
+    ///
+    /// see [`TestServer::client_cert()`] and [`TestServer::client_key()`].
+    pub fn client_auth(self, client_auth: bool) -> Self {
+        Self {
+            client_auth,
+            ..self
+        }
+    }
+
+    /// starts a new test server on the specified port.
+    pub async fn start_on_port(self, port: u16) -> Result<TestServer, Error> {
+        let addr = SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), port);
+        TestServer::start_(addr, None, self.behavior).await
+    }
+
+    /// starts a new test server.
+    ///
+    /// see [`TestServer::start()`].
+    pub async fn start(self) -> Result<TestServer, Error> {
+        TestServer::start_(TestServer::EPHEMERAL, None, self.behavior).await
+    }
+
+    /// starts a new test server on the specified port, accepting connections over tls.
+    ///
+    /// see [`TestServer::start_tls()`].
+    pub async fn start_tls_on_port(self, port: u16) -> Result<TestServer, Error> {
+        let addr = SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), port);
+        let tls = tls::acceptor(self.client_auth)?;
+        TestServer::start_(addr, Some(tls), self.behavior).await
+    }
+
+    /// starts a new test server, accepting connections over tls.
+    ///
+    /// see [`TestServer::start_tls()`].
+    pub async fn start_tls(self) -> Result<TestServer, Error> {
+        let tls = tls::acceptor(self.client_auth)?;
+        TestServer::start_(TestServer::EPHEMERAL, Some(tls), self.behavior).await
+    }
+}
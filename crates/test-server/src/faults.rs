@@ -0,0 +1,174 @@
+//! protocol-level faults injected by the test service.
+//!
+//! unlike injected errors, which are well-formed responses, these faults misbehave at the http/2
+//! layer: resetting streams, sending GOAWAY frames, limiting concurrent streams, withholding
+//! flow-control capacity, or dropping the connection in the middle of a response.
+
+use {
+    super::behavior::Body,
+    futures::{stream, StreamExt},
+    h2::Reason,
+    http_body_util::{BodyExt, StreamBody},
+    hyper::{
+        body::{Bytes, Frame},
+        server::conn::http2,
+    },
+    hyper_util::rt::TokioExecutor,
+    rand::Rng,
+    std::{
+        num::NonZeroU32,
+        sync::atomic::{AtomicU32, Ordering},
+        time::Duration,
+    },
+    tokio::sync::Notify,
+};
+
+/// protocol-level faults that the test service may inject.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct Faults {
+    /// if set, each connection is shut down with a GOAWAY frame after this many streams.
+    pub(super) goaway_after: Option<NonZeroU32>,
+    /// the percentage of streams that are reset, from 0 to 100.
+    pub(super) reset_rate: f64,
+    /// the error codes that streams are reset with.
+    ///
+    /// each reset stream is given one of these at random.
+    pub(super) reset_reasons: Vec<Reason>,
+    /// the maximum number of streams that each connection may have open at once.
+    pub(super) max_concurrent_streams: Option<u32>,
+    /// if true, clients are never given flow-control capacity to send request bodies.
+    pub(super) stall: bool,
+    /// the percentage of responses, from 0 to 100, during which the connection is dropped.
+    pub(super) close_rate: f64,
+}
+
+/// a fault injected into a single stream.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Fault {
+    /// reset the stream with the given error code.
+    Reset(Reason),
+    /// send the response headers and part of the body, then drop the connection.
+    Close,
+}
+
+/// a handle to a connection, used by the test service to shut it down.
+#[derive(Debug, Default)]
+pub(super) struct Control {
+    /// the number of streams received on this connection.
+    streams: AtomicU32,
+    /// notified when the connection should be gracefully shut down with a GOAWAY frame.
+    pub(super) goaway: Notify,
+    /// notified when the connection should be dropped abruptly.
+    pub(super) close: Notify,
+}
+
+// === impl Faults ===
+
+impl Faults {
+    /// the error code of reset streams, if none were given.
+    pub(super) const DEFAULT_RESET_REASON: Reason = Reason::REFUSED_STREAM;
+
+    /// applies connection-level faults to an http/2 connection builder.
+    pub(super) fn configure(&self, builder: &mut http2::Builder<TokioExecutor>) {
+        if let Some(max) = self.max_concurrent_streams {
+            builder.max_concurrent_streams(max);
+        }
+        if self.stall {
+            // NB: capacity is only released as request bodies are read, so advertising an empty
+            // window stalls any client that tries to send more than the default window.
+            builder
+                .initial_stream_window_size(0)
+                .initial_connection_window_size(0);
+        }
+    }
+
+    /// returns the fault, if any, that should be injected into the next stream.
+    pub(super) fn fault(&self, rng: &mut impl Rng) -> Option<Fault> {
+        let roll = rng.gen::<f64>() * 100.0;
+        if roll < self.reset_rate {
+            let reason = match self.reset_reasons.as_slice() {
+                [] => Self::DEFAULT_RESET_REASON,
+                reasons => reasons[rng.gen_range(0..reasons.len())],
+            };
+            Some(Fault::Reset(reason))
+        } else if roll < self.reset_rate + self.close_rate {
+            Some(Fault::Close)
+        } else {
+            None
+        }
+    }
+}
+
+// === impl Control ===
+
+impl Control {
+    /// how long to wait after sending part of a response, before dropping the connection.
+    const CLOSE_DELAY: Duration = Duration::from_millis(10);
+
+    /// records that a stream was received, sending a GOAWAY frame if the limit was reached.
+    pub(super) fn received(&self, faults: &Faults) {
+        let streams = self.streams.fetch_add(1, Ordering::Relaxed) + 1;
+        if faults
+            .goaway_after
+            .is_some_and(|limit| limit.get() == streams)
+        {
+            self.goaway.notify_one();
+        }
+    }
+
+    /// returns a response body that sends one chunk, and then drops the connection.
+    pub(super) fn close_body(self: std::sync::Arc<Self>) -> Body {
+        let partial = stream::once(async { Ok(Frame::data(Bytes::from_static(b"partial"))) });
+        let close = stream::once(async move {
+            // give the connection a moment to flush the response headers and first chunk.
+            tokio::time::sleep(Self::CLOSE_DELAY).await;
+            self.close.notify_one();
+            futures::future::pending().await
+        });
+        BodyExt::boxed(StreamBody::new(partial.chain(close)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, rand::SeedableRng};
+
+    #[test]
+    fn faults_are_injected_at_the_given_rates() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0x5eed);
+        let faults = Faults {
+            reset_rate: 20.0,
+            reset_reasons: vec![Reason::CANCEL],
+            close_rate: 10.0,
+            ..Default::default()
+        };
+        let injected = (0..10_000)
+            .filter_map(|_| faults.fault(&mut rng))
+            .collect::<Vec<_>>();
+        let resets = injected
+            .iter()
+            .filter(|f| **f == Fault::Reset(Reason::CANCEL))
+            .count();
+        let closes = injected.iter().filter(|f| **f == Fault::Close).count();
+        assert!((1_500..2_500).contains(&resets), "{resets} resets");
+        assert!((500..1_500).contains(&closes), "{closes} closes");
+        assert_eq!(Faults::default().fault(&mut rng), None);
+    }
+
+    #[test]
+    fn goaway_is_sent_after_the_limit() {
+        let faults = Faults {
+            goaway_after: NonZeroU32::new(2),
+            ..Default::default()
+        };
+        let control = Control::default();
+        control.received(&faults);
+        control.received(&faults);
+        control.received(&faults);
+        // NB: a permit is stored once, when the limit is reached.
+        let mut notified = std::pin::pin!(control.goaway.notified());
+        assert!(futures::FutureExt::now_or_never(notified.as_mut()).is_some());
+        let mut notified = std::pin::pin!(control.goaway.notified());
+        assert!(futures::FutureExt::now_or_never(notified.as_mut()).is_none());
+    }
+}
@@ -2,13 +2,22 @@
 //!
 //! see [`TestServer`].
 
+pub use self::{
+    behavior::{Drip, Latency},
+    builder::Builder,
+};
+
 use {
+    self::{
+        behavior::{Behavior, Body},
+        faults::{Control, Fault},
+    },
     futures::FutureExt,
-    http::{Request, Response},
-    hyper::{body::Incoming, server::conn::http2::Builder, service::Service},
+    http::{Request, Response, StatusCode},
+    http_body_util::{BodyExt, Full},
+    hyper::{body::Incoming, server::conn::http2, service::Service},
     hyper_util::rt::{TokioExecutor, TokioIo},
     std::{
-        convert::Infallible,
         future::Future,
         net::{IpAddr, Ipv6Addr, SocketAddr},
         pin::Pin,
@@ -16,13 +25,27 @@ use {
     },
     tap::{Pipe, Tap, TapFallible},
     tokio::{
+        io::{AsyncRead, AsyncWrite},
         net::{TcpListener, TcpStream},
         sync::RwLock,
         task::{AbortHandle, JoinError, JoinHandle, JoinSet},
     },
+    tokio_rustls::TlsAcceptor,
     tracing::{debug, error, info, info_span, instrument, trace, Instrument, Span},
 };
 
+/// configurable behavior of the test service.
+mod behavior;
+
+/// a test server builder.
+mod builder;
+
+/// protocol-level faults.
+mod faults;
+
+/// tls facilities.
+mod tls;
+
 /// a boxed error.
 type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
 
@@ -42,10 +65,20 @@ pub struct TestServer {
     tasks: Tasks,
     /// the number of requests that have been received.
     reqs_received: Arc<AtomicU32>,
+    /// the number of connections that have been accepted.
+    conns_accepted: Arc<AtomicU32>,
+    /// the PEM-encoded credentials that clients may use to connect to this server.
+    ///
+    /// this is `None` if the server is not using tls.
+    credentials: Option<tls::Credentials>,
 }
 
 /// a simple [`Service`].
 struct TestService {
+    /// how this service responds to requests.
+    behavior: Arc<Behavior>,
+    /// a handle to the connection this service is serving.
+    control: Arc<Control>,
     /// an optional callback.
     ///
     /// this will be invoked when this service is [called][Service::call].
@@ -67,10 +100,14 @@ impl TestServer {
     /// see [`TcpListener::bind()`] for more information.
     const EPHEMERAL: SocketAddr = SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 0);
 
+    /// returns a new [`Builder`], to configure how the test server responds to requests.
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
     /// starts a new test server on the specified port.
     pub async fn start_on_port(port: u16) -> Result<Self, Error> {
-        let addr = SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), port);
-        Self::start_(addr).await
+        Self::builder().start_on_port(port).await
     }
 
     /// starts a new test server.
@@ -78,12 +115,34 @@ impl TestServer {
     /// this will bind the server to a port assigned by the operating system. check `port` to see
     /// what port the server is listening on.
     pub async fn start() -> Result<Self, Error> {
-        Self::start_(Self::EPHEMERAL).await
+        Self::builder().start().await
+    }
+
+    /// starts a new test server on the specified port, accepting connections over tls.
+    ///
+    /// see [`TestServer::start_tls()`].
+    pub async fn start_tls_on_port(port: u16) -> Result<Self, Error> {
+        Self::builder().start_tls_on_port(port).await
+    }
+
+    /// starts a new test server, accepting connections over tls.
+    ///
+    /// the server presents a freshly generated certificate, and negotiates http/2 via ALPN. check
+    /// [`TestServer::ca_cert()`] for the certificate authority that clients should trust.
+    pub async fn start_tls() -> Result<Self, Error> {
+        Self::builder().start_tls().await
     }
 
-    async fn start_(addr: SocketAddr) -> Result<Self, Error> {
+    async fn start_(
+        addr: SocketAddr,
+        tls: Option<(TlsAcceptor, tls::Credentials)>,
+        behavior: Behavior,
+    ) -> Result<Self, Error> {
+        let behavior = Arc::new(behavior);
+        let (tls, credentials) = tls.unzip();
         let tasks = JoinSet::new().pipe(RwLock::new).pipe(Arc::new);
         let reqs_received = Arc::new(AtomicU32::new(0));
+        let conns_accepted = Arc::new(AtomicU32::new(0));
 
         // bind the server to a local "ephemeral" port.
         let listener = TcpListener::bind(addr).await?;
@@ -93,7 +152,15 @@ impl TestServer {
         let listener = {
             let tasks = Arc::clone(&tasks);
             let reqs_received = Arc::clone(&reqs_received);
-            let fut = Self::listen(tasks, listener, reqs_received);
+            let conns_accepted = Arc::clone(&conns_accepted);
+            let fut = Self::listen(
+                tasks,
+                listener,
+                tls,
+                behavior,
+                reqs_received,
+                conns_accepted,
+            );
             let span = info_span!("test server listener", %port);
             span.follows_from(Span::current());
             fut.instrument(span).pipe(tokio::spawn)
@@ -104,6 +171,8 @@ impl TestServer {
             listener,
             tasks,
             reqs_received,
+            conns_accepted,
+            credentials,
         })
         .tap(|_| info!(%port, "test server is listening on local port"))
     }
@@ -123,6 +192,8 @@ impl TestServer {
             tasks,
             listener,
             reqs_received,
+            conns_accepted: _,
+            credentials: _,
             port: _,
         } = &self;
 
@@ -154,12 +225,49 @@ impl TestServer {
             .load(std::sync::atomic::Ordering::Relaxed)
     }
 
+    /// returns the number of connections accepted by the test server.
+    pub fn conns_accepted(&self) -> u32 {
+        self.conns_accepted
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// returns the PEM-encoded certificate authority that signed this server's certificate.
+    ///
+    /// returns `None` if the server is not using tls.
+    pub fn ca_cert(&self) -> Option<&str> {
+        self.credentials
+            .as_ref()
+            .map(|credentials| credentials.ca_cert.as_str())
+    }
+
+    /// returns a PEM-encoded client certificate, signed by this server's certificate authority.
+    ///
+    /// returns `None` if the server is not using tls. see [`Builder::client_auth()`].
+    pub fn client_cert(&self) -> Option<&str> {
+        self.credentials
+            .as_ref()
+            .map(|credentials| credentials.client_cert.as_str())
+    }
+
+    /// returns the PEM-encoded private key of the [`TestServer::client_cert()`].
+    ///
+    /// returns `None` if the server is not using tls.
+    pub fn client_key(&self) -> Option<&str> {
+        self.credentials
+            .as_ref()
+            .map(|credentials| credentials.client_key.as_str())
+    }
+
     /// listens for incoming connections, spawning tasks to process them.
     #[instrument(skip_all)]
+    #[allow(clippy::manual_is_multiple_of)] // NB: newer toolchains suggest `u64::is_multiple_of`.
     async fn listen(
         tasks: Tasks,
         listener: TcpListener,
+        tls: Option<TlsAcceptor>,
+        behavior: Arc<Behavior>,
         reqs_received: Arc<AtomicU32>,
+        conns_accepted: Arc<AtomicU32>,
     ) -> Result<(), Error> {
         let mut conns = 0_u64;
 
@@ -172,6 +280,7 @@ impl TestServer {
                 .tap_ok(|_| {
                     // emit an info-level event every 10th connection.
                     conns += 1;
+                    conns_accepted.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
                     if conns % 10 == 0 {
                         info!(%conns, "accepted a new connection")
                     } else {
@@ -181,8 +290,13 @@ impl TestServer {
                 .tap_err(|err| error!(?err, "error accepting connection"))?;
 
             // create the future for the connection handler.
-            let fut = Self::handle_conn(conn, Arc::clone(&reqs_received))
-                .instrument(info_span!("test server connection", ?client_addr));
+            let fut = Self::handle_conn(
+                conn,
+                tls.clone(),
+                Arc::clone(&behavior),
+                Arc::clone(&reqs_received),
+            )
+            .instrument(info_span!("test server connection", ?client_addr));
 
             // spawn the connection handler into our pool of tasks.
             Self::spawn_task(&tasks, fut)
@@ -191,21 +305,63 @@ impl TestServer {
         }
     }
 
-    /// handles an http/2 connection.
-    #[instrument]
-    async fn handle_conn(conn: TcpStream, reqs_received: Arc<AtomicU32>) -> Result<(), Error> {
+    /// handles a connection, performing a tls handshake if applicable.
+    #[instrument(skip(tls, behavior))]
+    async fn handle_conn(
+        conn: TcpStream,
+        tls: Option<TlsAcceptor>,
+        behavior: Arc<Behavior>,
+        reqs_received: Arc<AtomicU32>,
+    ) -> Result<(), Error> {
+        match tls {
+            Some(tls) => {
+                let conn = tls
+                    .accept(conn)
+                    .tap(|_| trace!("beginning tls handshake"))
+                    .await
+                    .tap_ok(|_| debug!("finished tls handshake"))?;
+                Self::serve_conn(conn, behavior, reqs_received).await
+            }
+            None => Self::serve_conn(conn, behavior, reqs_received).await,
+        }
+    }
+
+    /// serves an http/2 connection.
+    async fn serve_conn<I>(
+        conn: I,
+        behavior: Arc<Behavior>,
+        reqs_received: Arc<AtomicU32>,
+    ) -> Result<(), Error>
+    where
+        I: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    {
         let exec = TokioExecutor::new();
         let io = TokioIo::new(conn);
-        let service = TestService::new().on_call(move || {
+        let control = Arc::new(Control::default());
+        let mut builder = http2::Builder::new(exec);
+        behavior.faults.configure(&mut builder);
+        let service = TestService::new(behavior, Arc::clone(&control)).on_call(move || {
             // when the test service is called, increment our counter tracking how many requests
             // the test server has received.
             reqs_received.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
         });
 
-        Builder::new(exec)
-            .serve_connection(io, service)
-            .await
-            .map_err(Into::into)
+        // serve the connection, until it closes or the test service shuts it down.
+        let conn = builder.serve_connection(io, service);
+        tokio::pin!(conn);
+        loop {
+            tokio::select! {
+                res = conn.as_mut() => return res.map_err(Into::into),
+                _ = control.goaway.notified() => {
+                    debug!("shutting down connection with a GOAWAY frame");
+                    conn.as_mut().graceful_shutdown();
+                }
+                _ = control.close.notified() => {
+                    debug!("dropping connection abruptly");
+                    return Ok(());
+                }
+            }
+        }
     }
 
     /// spawns a task in the test server.
@@ -228,8 +384,12 @@ impl TestServer {
 
 impl TestService {
     /// returns a new test service.
-    pub fn new() -> TestService {
-        Self { on_call: None }
+    pub fn new(behavior: Arc<Behavior>, control: Arc<Control>) -> TestService {
+        Self {
+            behavior,
+            control,
+            on_call: None,
+        }
     }
 
     /// sets a callback to be invoked when this service is [called][Service::call].
@@ -239,39 +399,80 @@ impl TestService {
     {
         Self {
             on_call: Some(Box::new(f)),
+            ..self
         }
     }
 }
 
 impl Service<Request<Incoming>> for TestService {
-    type Response = Response<Incoming>;
-    type Error = Infallible;
+    type Response = Response<Body>;
+    type Error = Error;
     type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
 
     /// processes the request, return a response asynchronously.
     ///
-    /// the response body will echo the contents of the inbound request body.
+    /// by default, the response body will echo the contents of the inbound request body. latency,
+    /// errors, and protocol-level faults may be injected, see [`Builder`].
     fn call(&self, req: Request<Incoming>) -> Self::Future {
         // if an `on_call(..)` callback was provided, invoke that now.
         if let Some(f) = self.on_call.as_ref() {
             f();
         }
 
-        // return a response, echoing the request body back to the client.
-        req.into_body().pipe(Self::response).boxed()
+        // decide how to respond to this request.
+        let behavior = Arc::clone(&self.behavior);
+        let control = Arc::clone(&self.control);
+        control.received(&behavior.faults);
+        let (fault, status, latency) = {
+            let mut rng = rand::thread_rng();
+            (
+                behavior.faults.fault(&mut rng),
+                behavior.status(&mut rng),
+                behavior.latency.sample(&mut rng),
+            )
+        };
+
+        async move {
+            if !latency.is_zero() {
+                tokio::time::sleep(latency).await;
+            }
+
+            // NB: hyper resets the stream with the reason of an `h2::Error` returned here.
+            match fault {
+                Some(Fault::Reset(reason)) => return Err(h2::Error::from(reason).into()),
+                Some(Fault::Close) => {
+                    return Self::response(StatusCode::OK, control.close_body()).pipe(Ok)
+                }
+                None => {}
+            }
+
+            // return a response, echoing the request body back to the client if it succeeded.
+            let body = if status.is_success() {
+                behavior.body(req.into_body()).await?
+            } else {
+                Self::error_body(status)
+            };
+            Self::response(status, body).pipe(Ok)
+        }
+        .boxed()
     }
 }
 
 impl TestService {
     /// returns a newly allocated response.
-    ///
-    //  NB: this doesn't need to be asynchronous, but it is helpful for the sake of implementing
-    //  a [`Service`].
-    async fn response<T>(body: T) -> Result<Response<T>, Infallible> {
-        Ok(Response::builder()
-            .status(200)
+    fn response(status: StatusCode, body: Body) -> Response<Body> {
+        Response::builder()
+            .status(status)
             .header("hello", "world")
             .body(body)
-            .expect("response should be valid"))
+            .expect("response should be valid")
+    }
+
+    /// returns the body of an injected error response.
+    fn error_body(status: StatusCode) -> Body {
+        format!("injected error: {status}\n")
+            .pipe(Full::from)
+            .map_err(|never| match never {})
+            .boxed()
     }
 }
@@ -1,16 +1,139 @@
 //! a small test server binary.
 
+use {
+    aquarius_test_server::{Drip, Latency, TestServer},
+    clap::Parser,
+    h2::Reason,
+    http::StatusCode,
+    std::num::NonZeroU32,
+};
+
+/// the port that cleartext connections are accepted on.
 const PORT: u16 = 8080;
 
+/// the port that tls connections are accepted on.
+const TLS_PORT: u16 = 8443;
+
+/// a small http/2 server for testing aquarius.
+///
+/// by default, each request receives a `200 OK` response immediately, echoing the request body.
+#[derive(Debug, Parser)]
+struct Cli {
+    /// the distribution of latencies to wait for before responding.
+    ///
+    /// this may be `fixed:10ms`, `uniform:5ms..50ms`, `normal:20ms,5ms` (a mean and standard
+    /// deviation), or `pareto:5ms,1.5` (a minimum and shape, for a long tail of latencies).
+    #[clap(long)]
+    latency: Option<Latency>,
+    /// the percentage of requests that receive an error response, from 0 to 100.
+    #[clap(long, default_value_t = 0.0)]
+    error_rate: f64,
+    /// the status code of error responses. this may be given many times.
+    ///
+    /// each error response is given one of these at random. defaults to `503`.
+    #[clap(long = "error-status", value_name = "STATUS")]
+    error_statuses: Vec<StatusCode>,
+    /// the size of each response body, in bytes.
+    ///
+    /// by default, the request body is echoed back to the client.
+    #[clap(long, value_name = "BYTES")]
+    body_size: Option<usize>,
+    /// send response bodies in chunks of `SIZE` bytes, waiting for `INTERVAL` between each.
+    ///
+    /// this is written as `SIZE,INTERVAL`, e.g. `64,10ms`.
+    #[clap(long, value_name = "SIZE,INTERVAL")]
+    drip: Option<Drip>,
+    /// shut down each connection with a GOAWAY frame, after it has received this many streams.
+    #[clap(long, value_name = "STREAMS")]
+    goaway_after: Option<NonZeroU32>,
+    /// the percentage of streams that are reset with a RST_STREAM frame, from 0 to 100.
+    #[clap(long, default_value_t = 0.0)]
+    reset_rate: f64,
+    /// the error code that streams are reset with. this may be given many times.
+    ///
+    /// this may be a name such as `REFUSED_STREAM`, or a number. each reset stream is given one
+    /// of these at random. defaults to `REFUSED_STREAM`.
+    #[clap(long = "reset-reason", value_name = "REASON", value_parser = parse_reason)]
+    reset_reasons: Vec<Reason>,
+    /// the maximum number of streams that each connection may have open at once.
+    #[clap(long, value_name = "STREAMS")]
+    max_concurrent_streams: Option<u32>,
+    /// never give clients flow-control capacity to send request bodies.
+    ///
+    /// requests with a body larger than the default window of 64KiB will stall.
+    #[clap(long)]
+    stall: bool,
+    /// the percentage of responses during which the connection is dropped, from 0 to 100.
+    #[clap(long, default_value_t = 0.0)]
+    close_rate: f64,
+    /// require tls clients to present a certificate signed by the server's authority.
+    ///
+    /// a client certificate and key are written to the system's temporary directory.
+    #[clap(long)]
+    client_auth: bool,
+}
+
 #[tokio::main(flavor = "multi_thread", worker_threads = 64)]
 async fn main() {
+    let Cli {
+        latency,
+        error_rate,
+        error_statuses,
+        body_size,
+        drip,
+        goaway_after,
+        reset_rate,
+        reset_reasons,
+        max_concurrent_streams,
+        stall,
+        close_rate,
+        client_auth,
+    } = Cli::parse();
+
     tracing_subscriber::fmt()
         .with_writer(std::io::stderr)
         .init();
 
-    let _server = aquarius_test_server::TestServer::start_on_port(PORT)
-        .await
-        .unwrap();
+    let builder = TestServer::builder()
+        .latency(latency.unwrap_or_default())
+        .error_rate(error_rate)
+        .error_statuses(error_statuses)
+        .body_size(body_size)
+        .drip(drip)
+        .goaway_after(goaway_after)
+        .reset_rate(reset_rate)
+        .reset_reasons(reset_reasons)
+        .max_concurrent_streams(max_concurrent_streams)
+        .stall(stall)
+        .close_rate(close_rate)
+        .client_auth(client_auth);
+
+    let _server = builder.clone().start_on_port(PORT).await.unwrap();
+
+    // write the tls server's certificate authority to a file, so that clients can trust it.
+    let tls_server = builder.start_tls_on_port(TLS_PORT).await.unwrap();
+    let ca_cert = std::env::temp_dir().join("aquarius-test-server-ca.pem");
+    std::fs::write(&ca_cert, tls_server.ca_cert().unwrap()).unwrap();
+    tracing::info!(path = %ca_cert.display(), "wrote certificate authority");
+
+    // write a client certificate and key, so that clients can authenticate themselves.
+    let client_cert = std::env::temp_dir().join("aquarius-test-server-client.pem");
+    let client_key = std::env::temp_dir().join("aquarius-test-server-client-key.pem");
+    std::fs::write(&client_cert, tls_server.client_cert().unwrap()).unwrap();
+    std::fs::write(&client_key, tls_server.client_key().unwrap()).unwrap();
+    tracing::info!(path = %client_cert.display(), "wrote client certificate");
 
     futures::future::pending::<()>().await;
 }
+
+/// parses an http/2 error code, given its name or number.
+fn parse_reason(s: &str) -> Result<Reason, String> {
+    if let Ok(code) = s.parse::<u32>() {
+        return Ok(Reason::from(code));
+    }
+    // NB: the names of the error codes defined by rfc 9113 are their `Debug` representation.
+    (0..=0xd)
+        .map(Reason::from)
+        .find(|reason| format!("{reason:?}").eq_ignore_ascii_case(s))
+        .ok_or_else(|| format!("unknown http/2 error code `{s}`"))
+}
@@ -0,0 +1,79 @@
+//! tls facilities for the test server.
+
+use {
+    super::Error,
+    rcgen::{BasicConstraints, CertificateParams, IsCa, KeyPair},
+    rustls::{
+        pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer},
+        server::WebPkiClientVerifier,
+        RootCertStore, ServerConfig,
+    },
+    std::sync::Arc,
+    tokio_rustls::TlsAcceptor,
+    tracing::debug,
+};
+
+/// the names that the test server's certificate is valid for.
+const NAMES: &[&str] = &["localhost", "127.0.0.1", "::1"];
+
+/// PEM-encoded credentials that clients may use to connect to the test server.
+pub(super) struct Credentials {
+    /// the certificate authority that signed the server's and the client's certificates.
+    pub(super) ca_cert: String,
+    /// a client certificate, signed by the certificate authority.
+    pub(super) client_cert: String,
+    /// the private key of the client certificate.
+    pub(super) client_key: String,
+}
+
+/// generates a certificate authority, and server and client certificates signed by it.
+///
+/// returns a [`TlsAcceptor`] presenting the server certificate, which negotiates http/2 via ALPN,
+/// and the [`Credentials`] that clients may use to verify the server, and to authenticate
+/// themselves. if `client_auth` is true, clients must present a certificate signed by the
+/// authority.
+pub(super) fn acceptor(client_auth: bool) -> Result<(TlsAcceptor, Credentials), Error> {
+    // generate a certificate authority.
+    let ca_key = KeyPair::generate()?;
+    let ca = {
+        let mut params = CertificateParams::new(Vec::new())?;
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.self_signed(&ca_key)?
+    };
+
+    // generate a certificate for the server, signed by the authority.
+    let key = KeyPair::generate()?;
+    let names = NAMES.iter().copied().map(str::to_owned).collect::<Vec<_>>();
+    let cert = CertificateParams::new(names)?.signed_by(&key, &ca, &ca_key)?;
+    debug!("generated test server certificate");
+
+    // generate a certificate for clients, signed by the authority.
+    let client_key = KeyPair::generate()?;
+    let client_cert = CertificateParams::new(Vec::new())?.signed_by(&client_key, &ca, &ca_key)?;
+    debug!("generated test client certificate");
+
+    // verify client certificates against the authority, if clients must authenticate.
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ServerConfig::builder_with_provider(Arc::clone(&provider))
+        .with_safe_default_protocol_versions()?;
+    let builder = if client_auth {
+        let mut roots = RootCertStore::empty();
+        roots.add(ca.der().clone())?;
+        let verifier =
+            WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider).build()?;
+        builder.with_client_cert_verifier(verifier)
+    } else {
+        builder.with_no_client_auth()
+    };
+
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key.serialize_der()));
+    let mut config = builder.with_single_cert(vec![cert.der().clone()], key)?;
+    config.alpn_protocols = vec![b"h2".to_vec()];
+
+    let credentials = Credentials {
+        ca_cert: ca.pem(),
+        client_cert: client_cert.pem(),
+        client_key: client_key.serialize_pem(),
+    };
+    Ok((TlsAcceptor::from(Arc::new(config)), credentials))
+}
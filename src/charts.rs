@@ -1,11 +1,24 @@
 //! charts and other reporting facilities.
 
 use {
-    crate::summary::Summary,
+    crate::summary::{Interval, Summary},
     textplots::{Plot, Shape},
 };
 
-pub use self::{in_flight::render_in_flight, progress::render_progress, table::render_table};
+pub use self::{
+    compare::{render_budgets, render_comparison},
+    heatmap::render_heatmap,
+    histogram::render_histogram,
+    in_flight::render_in_flight,
+    latency::render_latency_over_time,
+    percentiles::render_percentile_distribution,
+    phases::render_phases,
+    progress::render_progress,
+    stages::render_stages,
+    table::render_table,
+    thresholds::render_thresholds,
+    throughput::render_throughput,
+};
 
 /// charts the number of in-flight jobs.
 mod in_flight;
@@ -13,9 +26,36 @@ mod in_flight;
 /// charts the progress over time.
 mod progress;
 
+/// charts the rate of completed requests over time.
+mod throughput;
+
+/// charts latency percentiles over time.
+mod latency;
+
+/// display a histogram of latencies.
+mod histogram;
+
+/// charts latency by percentile.
+mod percentiles;
+
+/// display a heatmap of latencies over time.
+mod heatmap;
+
 /// display a table
 mod table;
 
+/// display a table of load profile stages
+mod stages;
+
+/// display a breakdown of the time spent in each phase
+mod phases;
+
+/// display a table of thresholds, and whether they passed
+mod thresholds;
+
+/// display a comparison of two load-tests
+mod compare;
+
 /// chart dimensions.
 //
 //  TODO: for now these are hard-coded for simplicity.
@@ -26,3 +66,24 @@ mod dimensions {
     /// the height of rendered charts.
     pub const HEIGHT: u32 = 64;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a load-test interrupted before any worker finished has an empty summary.
+    #[test]
+    fn empty_summaries_can_be_rendered() {
+        let summary = Summary::default();
+        render_progress(&summary);
+        render_in_flight(&summary);
+        render_throughput(&summary);
+        render_latency_over_time(&summary);
+        render_histogram(&summary);
+        render_percentile_distribution(&summary);
+        render_heatmap(&summary);
+        render_table(&summary);
+        render_stages(&summary);
+        render_phases(&summary);
+    }
+}
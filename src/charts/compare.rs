@@ -0,0 +1,90 @@
+use {
+    crate::compare::{Comparison, Delta, Verdict},
+    comfy_table::{presets::UTF8_FULL, Row, Table},
+    lazy_static::lazy_static,
+    std::ops::Deref,
+};
+
+lazy_static! {
+    static ref HEADER: Row = vec!["metric", "baseline", "candidate", "delta", "change"].into();
+    static ref BUDGET_HEADER: Row = vec!["budget", "change", "result"].into();
+}
+
+/// render a table comparing two load-tests, and whether their latencies differ significantly.
+///
+/// `alpha` is the significance level of the test, e.g. `0.05`.
+pub fn render_comparison(comparison: &Comparison, alpha: f64) {
+    let mut table = Table::new();
+    table
+        .set_width(super::dimensions::WIDTH as u16)
+        .set_header(HEADER.deref().to_owned())
+        .load_preset(UTF8_FULL);
+
+    // add a row for each metric.
+    for delta @ Delta {
+        metric,
+        baseline,
+        candidate,
+    } in comparison.deltas()
+    {
+        let sign = if delta.difference() >= 0.0 { "+" } else { "" };
+        let row: Row = [
+            metric.to_string(),
+            metric.format(*baseline),
+            metric.format(*candidate),
+            format!("{sign}{}", metric.format(delta.difference())),
+            format_change(delta),
+        ]
+        .into();
+        table.add_row(row);
+    }
+    println!("{table}");
+
+    // report whether the latencies differ significantly.
+    match comparison.test() {
+        Some(test) => {
+            let verdict = match (test.is_significant(alpha), test.effect > 0.5) {
+                (false, _) => "no significant difference",
+                (true, true) => "the candidate is significantly slower",
+                (true, false) => "the candidate is significantly faster",
+            };
+            println!(
+                "mann-whitney u test: {verdict} (p = {:.4}, α = {alpha}, P(candidate > baseline) = {:.3})",
+                test.p_value, test.effect
+            );
+        }
+        None => println!("mann-whitney u test: skipped, both runs must be request logs"),
+    }
+}
+
+/// render a table of regression budgets, and whether the candidate stayed within them.
+pub fn render_budgets(verdicts: &[Verdict]) {
+    let mut table = Table::new();
+    table
+        .set_width(super::dimensions::WIDTH as u16)
+        .set_header(BUDGET_HEADER.deref().to_owned())
+        .load_preset(UTF8_FULL);
+
+    // add a row for each budget.
+    for Verdict {
+        budget,
+        delta,
+        passed,
+    } in verdicts
+    {
+        let result = if *passed { "pass" } else { "FAIL" };
+        let row: Row = [budget.to_string(), format_change(delta), result.to_owned()].into();
+        table.add_row(row);
+    }
+
+    // print the table
+    println!("{table}");
+}
+
+/// formats the percentage change of a delta.
+fn format_change(delta: &Delta) -> String {
+    match delta.change() {
+        Some(change) => format!("{change:+.2}%"),
+        None => "n/a".to_owned(),
+    }
+}
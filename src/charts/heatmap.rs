@@ -0,0 +1,64 @@
+use {super::*, crate::summary::Bucket};
+
+/// the characters used to shade a cell, from the fewest workers to the most.
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// writes a heatmap of worker durations over time to stdout.
+///
+/// each column is an interval of the load-test, and each row is a log-scaled bucket of worker
+/// durations, with the longest at the top. cells are shaded by how many workers finished in that
+/// interval with a duration in that bucket, relative to the busiest cell.
+pub fn render_heatmap(summary: &Summary) {
+    let interval = summary.default_interval();
+    let heatmap = summary.heatmap(interval);
+    let most = heatmap
+        .iter()
+        .flatten()
+        .map(Bucket::count)
+        .max()
+        .unwrap_or_default();
+    if most == 0 {
+        return;
+    }
+
+    println!(
+        "latency heatmap (each column is {}ms):",
+        interval.as_millis()
+    );
+    let rows = summary.histogram();
+    for (row, bucket) in rows.iter().enumerate().rev() {
+        let cells = heatmap
+            .iter()
+            .map(|column| shade(column[row].count(), most))
+            .collect::<String>();
+        println!("{:>10?} ▕{cells}▏", bucket.low());
+    }
+    let elapsed = format!("{:.1}s", (interval * heatmap.len() as u32).as_secs_f32());
+    println!("{:>12}0{elapsed:>width$}", "", width = heatmap.len());
+}
+
+/// returns the character used to shade a cell, relative to the busiest cell.
+fn shade(count: u64, most: u64) -> char {
+    if count == 0 {
+        return SHADES[0];
+    }
+
+    // any cell with workers in it is at least lightly shaded.
+    let levels = (SHADES.len() - 1) as u64;
+    let level = (count * levels).div_ceil(most).clamp(1, levels);
+    SHADES[level as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells_are_shaded_relative_to_the_busiest() {
+        assert_eq!(shade(0, 100), ' ');
+        assert_eq!(shade(1, 100), '░');
+        assert_eq!(shade(50, 100), '▒');
+        assert_eq!(shade(75, 100), '▓');
+        assert_eq!(shade(100, 100), '█');
+    }
+}
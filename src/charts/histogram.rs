@@ -0,0 +1,25 @@
+use {super::*, crate::summary::Bucket};
+
+/// the width of the longest bar, in characters.
+const BAR_WIDTH: usize = 96;
+
+/// writes a histogram of worker durations to stdout, in log-scaled buckets.
+pub fn render_histogram(summary: &Summary) {
+    let histogram = summary.histogram();
+    let most = histogram
+        .iter()
+        .map(Bucket::count)
+        .max()
+        .unwrap_or_default();
+    if most == 0 {
+        return;
+    }
+
+    println!("latency histogram:");
+    for bucket in &histogram {
+        let width = (bucket.count() as f64 / most as f64 * BAR_WIDTH as f64).round() as usize;
+        let bar = "█".repeat(width);
+        let range = format!("{:?}..{:?}", bucket.low(), bucket.high());
+        println!("{range:>22} ▕{bar:<BAR_WIDTH$}▏ {}", bucket.count());
+    }
+}
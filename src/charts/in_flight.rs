@@ -5,7 +5,9 @@ use super::*;
 //  NB: it's a little icky that this writes to stdout. the `textplots` interface doesn't give us a
 //  particularly easy way to work around this.
 pub fn render_in_flight(summary: &Summary) {
-    let (start, end) = summary.time_range();
+    let Some((start, end)) = summary.time_range() else {
+        return;
+    };
     let in_flight = summary
         .compute_in_flight_observations()
         .into_iter()
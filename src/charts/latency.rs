@@ -0,0 +1,43 @@
+use super::*;
+
+/// writes a chart of p50 and p99 latencies over time to stdout, in milliseconds.
+pub fn render_latency_over_time(summary: &Summary) {
+    let series = summary.time_series(summary.default_interval());
+    let points = |percentile: f64| {
+        series
+            .iter()
+            .filter(|interval| interval.total() > 0)
+            .map(|interval| {
+                let x = interval.start().as_millis() as f32;
+                let y = interval.percentile(percentile).as_secs_f32() * 1000.0;
+                (x, y)
+            })
+            .collect::<Vec<(f32, f32)>>()
+    };
+    let p50 = points(50.0);
+    let p99 = points(99.0);
+    if p99.is_empty() {
+        return;
+    }
+
+    // compute the dimensions of our latency chart.
+    let (xmin, ymin) = (0.0, 0.0); // use (0,0) as our origin.
+    let xmax = series.last().map_or(0.0, |i| i.start().as_millis() as f32);
+    let ymax = p99.iter().map(|(_, y)| *y).fold(0.0, f32::max);
+
+    // configure and render a chart.
+    println!("p50 and p99 latency over time (ms):");
+    let mut chart = textplots::Chart::new_with_y_range(
+        super::dimensions::WIDTH,
+        super::dimensions::HEIGHT,
+        xmin,
+        xmax,
+        ymin,
+        ymax,
+    );
+    chart.axis();
+    chart
+        .lineplot(&Shape::Lines(&p99))
+        .lineplot(&Shape::Lines(&p50))
+        .display();
+}
@@ -0,0 +1,41 @@
+use super::*;
+
+/// writes a chart of worker durations at each percentile to stdout, in milliseconds.
+///
+/// like HdrHistogram's percentile distribution plots, the x-axis is log-inverse, i.e.
+/// `log10(1 / (1 - percentile))`, so that the tail of the distribution is legible: 1 is the
+/// 90th percentile, 2 is the 99th, 3 is the 99.9th, and so on.
+pub fn render_percentile_distribution(summary: &Summary) {
+    let points = summary
+        .percentile_distribution()
+        .into_iter()
+        // NB: the 100th percentile would lie infinitely far to the right.
+        .filter(|(percentile, _)| *percentile < 100.0)
+        .map(|(percentile, duration)| {
+            let x = (1.0 / (1.0 - percentile / 100.0)).log10() as f32;
+            let y = duration.as_secs_f32() * 1000.0;
+            (x, y)
+        })
+        .collect::<Vec<(f32, f32)>>();
+    if points.is_empty() {
+        return;
+    }
+
+    // compute the dimensions of our percentile chart.
+    let (xmin, ymin) = (0.0, 0.0); // use (0,0) as our origin.
+    let xmax = points.last().map_or(0.0, |(x, _)| *x);
+    let ymax = points.iter().map(|(_, y)| *y).fold(0.0, f32::max);
+
+    // configure and render a chart.
+    println!("latency (ms) by percentile (x: 1 = p90, 2 = p99, 3 = p99.9, ...):");
+    let mut chart = textplots::Chart::new_with_y_range(
+        super::dimensions::WIDTH,
+        super::dimensions::HEIGHT,
+        xmin,
+        xmax,
+        ymin,
+        ymax,
+    );
+    chart.axis();
+    chart.lineplot(&Shape::Lines(&points)).display();
+}
@@ -0,0 +1,113 @@
+use {
+    super::*,
+    crate::worker::Phase,
+    comfy_table::{presets::UTF8_FULL, Row, Table},
+    lazy_static::lazy_static,
+    std::{ops::Deref, time::Duration},
+};
+
+lazy_static! {
+    static ref HEADER: Row = vec!["phase", "count", "mean", "p50", "p90", "p99"].into();
+}
+
+/// the width of the stacked bars, in characters.
+const BAR_WIDTH: usize = 96;
+
+/// render a table and stacked chart, breaking down the time spent in each phase of the workers.
+///
+/// nothing is rendered if no workers reached any phase.
+pub fn render_phases(summary: &Summary) {
+    let phases = Phase::ALL
+        .into_iter()
+        .filter(|phase| summary.phase_count(*phase) > 0)
+        .collect::<Vec<_>>();
+    if phases.is_empty() {
+        return;
+    }
+
+    let mut table = Table::new();
+    table
+        .set_width(dimensions::WIDTH as u16)
+        .set_header(HEADER.deref().to_owned())
+        .load_preset(UTF8_FULL);
+
+    // add a row for each phase that workers reached.
+    for phase in phases.iter().copied() {
+        let micros = |duration: Duration| format!("{}µs", duration.as_micros());
+        let row: Row = [
+            phase.to_string(),
+            summary.phase_count(phase).to_string(),
+            micros(summary.phase_mean(phase)),
+            micros(summary.phase_percentile(phase, 50.0)),
+            micros(summary.phase_percentile(phase, 90.0)),
+            micros(summary.phase_percentile(phase, 99.0)),
+        ]
+        .into();
+        table.add_row(row);
+    }
+    println!("{table}");
+
+    // render a stacked bar for the mean, and some percentiles, of each phase.
+    let bars = [
+        ("mean", None),
+        ("p50", Some(50.0)),
+        ("p90", Some(90.0)),
+        ("p99", Some(99.0)),
+    ]
+    .map(|(name, percentile)| {
+        let segments = phases
+            .iter()
+            .map(|&phase| match percentile {
+                Some(percentile) => (phase, summary.phase_percentile(phase, percentile)),
+                None => (phase, summary.phase_mean(phase)),
+            })
+            .collect::<Vec<_>>();
+        (name, segments)
+    });
+    let longest = bars
+        .iter()
+        .map(|(_, segments)| segments.iter().map(|(_, d)| *d).sum::<Duration>())
+        .max()
+        .unwrap_or_default();
+
+    println!("time spent in each phase:");
+    for (name, segments) in &bars {
+        let total = segments.iter().map(|(_, d)| *d).sum::<Duration>();
+        let bar = stacked_bar(segments, longest);
+        println!("{name:>5} ▕{bar:<BAR_WIDTH$}▏ {}µs", total.as_micros());
+    }
+    let legend = phases
+        .iter()
+        .map(|phase| format!("{} {phase}", glyph(*phase)))
+        .collect::<Vec<_>>()
+        .join("  ");
+    println!("       {legend}");
+}
+
+/// returns a bar, with a segment for each phase, scaled relative to the longest bar.
+fn stacked_bar(segments: &[(Phase, Duration)], longest: Duration) -> String {
+    if longest.is_zero() {
+        return String::new();
+    }
+
+    let scale = BAR_WIDTH as f64 / longest.as_secs_f64();
+    segments
+        .iter()
+        .map(|(phase, duration)| {
+            let width = (duration.as_secs_f64() * scale).round() as usize;
+            glyph(*phase).to_string().repeat(width)
+        })
+        .collect()
+}
+
+/// returns the character used to draw a phase in a stacked bar.
+fn glyph(phase: Phase) -> char {
+    match phase {
+        Phase::Dns => '░',
+        Phase::Connect => '▒',
+        Phase::Tls => '▓',
+        Phase::Handshake => '█',
+        Phase::Request => '▞',
+        Phase::Body => '▚',
+    }
+}
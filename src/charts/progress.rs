@@ -2,7 +2,9 @@ use super::*;
 
 /// writes a chart of progress over time to stdout.
 pub fn render_progress(summary: &Summary) {
-    let (start, end) = summary.time_range();
+    let Some((start, end)) = summary.time_range() else {
+        return;
+    };
     let finished = summary
         .compute_progress_observations()
         .into_iter()
@@ -0,0 +1,44 @@
+use {
+    super::*,
+    comfy_table::{presets::UTF8_FULL, Row, Table},
+    lazy_static::lazy_static,
+    std::ops::Deref,
+};
+
+lazy_static! {
+    static ref HEADER: Row = vec!["stage", "requests", "success rate", "p50", "p99"].into();
+}
+
+/// render a table breaking down statistics for each stage of a load profile.
+///
+/// nothing is rendered if the load-test did not follow a load profile.
+pub fn render_stages(summary: &Summary) {
+    let mut stages = summary.stages().peekable();
+    if stages.peek().is_none() {
+        return;
+    }
+
+    let mut table = Table::new();
+    table
+        .set_width(dimensions::WIDTH as u16)
+        .set_header(HEADER.deref().to_owned())
+        .load_preset(UTF8_FULL);
+
+    // add a row for each stage.
+    for (index, stage) in stages {
+        let p50 = stage.percentile(50.0).as_micros();
+        let p99 = stage.percentile(99.0).as_micros();
+        let row: Row = [
+            index.to_string(),
+            stage.total().to_string(),
+            format!("{}%", stage.success_rate()),
+            format!("{p50}µs"),
+            format!("{p99}µs"),
+        ]
+        .into();
+        table.add_row(row);
+    }
+
+    // print the table
+    println!("{table}");
+}
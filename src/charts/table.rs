@@ -21,18 +21,48 @@ pub fn render_table(summary: &Summary) {
     // add a row containing the success rate. how many requests were 2XX's?
     let success_rate: Row = {
         const NAME: &str = "success rate (percentage)";
-        let rate = summary.success_rate();
-        [NAME.to_owned(), format!("{rate}%")].into()
+        let rate = match summary.total() {
+            0 => "n/a".to_owned(),
+            _ => format!("{}%", summary.success_rate()),
+        };
+        [NAME.to_owned(), rate].into()
     };
     table.add_row(success_rate);
 
-    // add a row containing the median duration.
-    let duration_median: Row = {
-        const NAME: &str = "duration (median)";
-        let duration = summary.median_duration().as_micros();
-        [NAME.to_owned(), format!("{duration}µs")].into()
+    // add rows containing the distribution of durations.
+    let duration_row = |name: String, duration: std::time::Duration| -> Row {
+        let duration = duration.as_micros();
+        [name, format!("{duration}µs")].into()
     };
-    table.add_row(duration_median);
+    table.add_row(duration_row(
+        "duration (min)".to_owned(),
+        summary.min_duration(),
+    ));
+    for (percentile, duration) in summary.percentiles() {
+        table.add_row(duration_row(format!("duration (p{percentile})"), duration));
+    }
+    table.add_row(duration_row(
+        "duration (max)".to_owned(),
+        summary.max_duration(),
+    ));
+    for (percentile, duration) in summary.response_time_percentiles() {
+        table.add_row(duration_row(
+            format!("response time (p{percentile})"),
+            duration,
+        ));
+    }
+    table.add_row(duration_row(
+        "response time (max)".to_owned(),
+        summary.max_response_time(),
+    ));
+    table.add_row(duration_row(
+        "duration (mean)".to_owned(),
+        summary.mean_duration(),
+    ));
+    table.add_row(duration_row(
+        "duration (stddev)".to_owned(),
+        summary.stddev_duration(),
+    ));
 
     // add a row containing the average number of in-flight requests.
     let in_flight_average: Row = {
@@ -42,6 +72,36 @@ pub fn render_table(summary: &Summary) {
     };
     table.add_row(in_flight_average);
 
+    // add rows counting the dispatches that were affected by backpressure.
+    for (name, count) in [
+        ("dispatches (skipped)", summary.skipped()),
+        ("dispatches (delayed)", summary.delayed()),
+    ] {
+        let row: Row = [name.to_owned(), count.to_string()].into();
+        table.add_row(row);
+    }
+
+    // add rows counting the workers that failed, by the kind of failure.
+    let failures: Row = {
+        const NAME: &str = "failures (total)";
+        let count = summary.failure_count();
+        [NAME.to_owned(), count.to_string()].into()
+    };
+    table.add_row(failures);
+    for (failure, count) in summary.failures() {
+        let row: Row = [format!("failures ({failure})"), count.to_string()].into();
+        table.add_row(row);
+    }
+
     // print the table
     println!("{table}");
+
+    // skipped dispatches are not reported as requests, but they did count toward `--total`.
+    if summary.skipped() > 0 {
+        println!(
+            "note: {} requests were skipped due to backpressure. skipped requests were not sent, \
+            but count toward `--total`.",
+            summary.skipped()
+        );
+    }
 }
@@ -0,0 +1,39 @@
+use {
+    super::*,
+    crate::threshold::Outcome,
+    comfy_table::{presets::UTF8_FULL, Row, Table},
+    lazy_static::lazy_static,
+    std::ops::Deref,
+};
+
+lazy_static! {
+    static ref HEADER: Row = vec!["threshold", "observed", "result"].into();
+}
+
+/// render a table of thresholds, the values observed, and whether each threshold passed.
+pub fn render_thresholds(outcomes: &[Outcome]) {
+    let mut table = Table::new();
+    table
+        .set_width(dimensions::WIDTH as u16)
+        .set_header(HEADER.deref().to_owned())
+        .load_preset(UTF8_FULL);
+
+    // add a row for each threshold.
+    for Outcome {
+        threshold,
+        observed,
+        passed,
+    } in outcomes
+    {
+        let result = if *passed { "pass" } else { "FAIL" };
+        let observed = match observed {
+            Some(observed) => observed.to_string(),
+            None => "n/a (no successful responses)".to_owned(),
+        };
+        let row: Row = [threshold.to_string(), observed, result.to_owned()].into();
+        table.add_row(row);
+    }
+
+    // print the table
+    println!("{table}");
+}
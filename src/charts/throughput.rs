@@ -0,0 +1,41 @@
+use super::*;
+
+/// writes a chart of completed requests per second over time to stdout.
+///
+/// the upper line counts each worker that finished, and the lower line counts those that were
+/// unsuccessful.
+pub fn render_throughput(summary: &Summary) {
+    let series = summary.time_series(summary.default_interval());
+    if series.is_empty() {
+        return;
+    }
+    let points = |rps: fn(&Interval) -> f64| {
+        series
+            .iter()
+            .map(|interval| (interval.start().as_millis() as f32, rps(interval) as f32))
+            .collect::<Vec<(f32, f32)>>()
+    };
+    let completed = points(Interval::rps);
+    let errors = points(Interval::error_rps);
+
+    // compute the dimensions of our throughput chart.
+    let (xmin, ymin) = (0.0, 0.0); // use (0,0) as our origin.
+    let xmax = completed.last().map_or(0.0, |(x, _)| *x);
+    let ymax = completed.iter().map(|(_, y)| *y).fold(0.0, f32::max);
+
+    // configure and render a chart.
+    println!("completed requests per second (all, and unsuccessful):");
+    let mut chart = textplots::Chart::new_with_y_range(
+        super::dimensions::WIDTH,
+        super::dimensions::HEIGHT,
+        xmin,
+        xmax,
+        ymin,
+        ymax,
+    );
+    chart.axis();
+    chart
+        .lineplot(&Shape::Lines(&completed))
+        .lineplot(&Shape::Lines(&errors))
+        .display();
+}
@@ -1,17 +1,38 @@
 //! command-line options.
 //!
-//! defines a [`Cli`] structure for parsing command-line arguments.
+//! defines a [`Cli`] structure for parsing command-line arguments. the `compare` subcommand is
+//! parsed as a [`Compare`] structure instead, see [`parse_command()`].
 
 pub use self::{
-    parse::{parse, try_parse_from},
+    compare::Compare,
+    output::Output,
+    parse::{parse, parse_command, try_parse_command_from, try_parse_from},
+    request::{Header, Request},
     server::Server,
+    timeouts::Timeouts,
+    tls::Tls,
+    validation::Validation,
 };
 
 use {
-    crate::error::Error,
-    clap::Parser,
-    std::{ffi::OsString, str::FromStr},
-    tap::Tap,
+    crate::{
+        compare::Budget,
+        error::Error,
+        output::Format,
+        syndicate::{Backpressure, Stage},
+        threshold::Threshold,
+        worker::{HeaderRule, JsonRule, Pattern, Sha256, StatusRange},
+    },
+    clap::{error::ErrorKind, parser::ValueSource, Args, FromArgMatches, Parser, Subcommand},
+    http::{
+        uri::{PathAndQuery, Scheme},
+        HeaderName, HeaderValue, Method,
+    },
+    std::{
+        ffi::OsString, net::SocketAddr, num::NonZeroU32, path::PathBuf, str::FromStr,
+        time::Duration,
+    },
+    tap::{Pipe, Tap},
     tracing::{debug, trace},
     url::Host,
 };
@@ -19,51 +40,254 @@ use {
 /// command-line options for `aquarius`.
 ///
 /// these are acquired via [`parse()`].
-#[derive(Debug, Eq, Parser, PartialEq)]
-#[command(
-    about = "aquarius: a lightweight http/2 load-tester",
-    long_about = "aquarius: a lightweight http/2 load-tester\n\n  __ _ __ _ _  _ __ _ _ _(_)_  _ ___\n / _` / _` | || / _` | '_| | || (_-<\n \\__,_\\__, |\\_,_\\__,_|_| |_|\\_,_/__/\n         |_|                     "
-)]
+#[derive(Args, Debug, Eq, PartialEq)]
 pub struct Cli {
     /// the number of requests to send to the server.
+    ///
+    /// requests skipped due to `--backpressure drop` count toward this total, although they are
+    /// not sent.
     #[clap(long = "total")]
     pub requests_total: Option<u32>,
     /// the rate at which to send requests to the server.
     #[clap(long = "rate")]
     pub requests_per_second: Option<u32>,
+    /// the number of connections to share among requests.
+    ///
+    /// if omitted, each request will open its own connection to the server. otherwise, requests
+    /// are multiplexed upon a pool of long-lived connections.
+    #[clap(long)]
+    pub connections: Option<NonZeroU32>,
+    /// the number of virtual users sending requests to the server.
+    ///
+    /// if given, each user sends a request, awaits the response, and then sends another. the
+    /// number of requests in flight is thus bounded by the number of users.
+    #[clap(long)]
+    pub concurrency: Option<NonZeroU32>,
+    /// how long each virtual user waits after receiving a response, e.g. `100ms`.
+    #[clap(long, requires = "concurrency", value_parser = humantime::parse_duration)]
+    pub think_time: Option<Duration>,
+    /// how long to send requests to the server for, e.g. `5m`.
+    ///
+    /// if `--total` is also given, the load-test stops sending requests once either limit is
+    /// reached.
+    #[clap(long, value_parser = humantime::parse_duration)]
+    pub duration: Option<Duration>,
+    /// how long in-flight requests may run once the load-test stops sending requests, e.g. `10s`.
+    ///
+    /// requests still in flight after this grace period are cancelled. if omitted, in-flight
+    /// requests are allowed to finish, unless the load-test is interrupted, in which case they
+    /// are cancelled after 5s. a second interrupt cancels them immediately.
+    #[clap(long, value_parser = humantime::parse_duration)]
+    pub grace: Option<Duration>,
+    /// a stage of the load profile, written as `DURATION:RATE`, e.g. `30s:500`.
+    ///
+    /// this may be given many times. each stage ramps the rate of requests linearly to its
+    /// target over its duration, starting from the previous stage's target, or zero. the
+    /// load-test ends once the last stage has finished.
+    #[clap(
+        long = "stage",
+        value_name = "STAGE",
+        conflicts_with_all = ["requests_per_second", "profile"]
+    )]
+    pub stages: Vec<Stage>,
+    /// a file containing the load profile, with one `DURATION:RATE` stage per line.
+    #[clap(long, conflicts_with = "requests_per_second")]
+    pub profile: Option<PathBuf>,
+    /// what to do when a request cannot be sent immediately.
+    ///
+    /// this applies when `--max-in-flight` requests are already in flight, or when the
+    /// load-tester falls behind on spawning requests. requests may be delayed, or skipped.
+    /// skipped requests are not sent, but count toward `--total`.
+    #[clap(long, value_enum, default_value_t)]
+    pub backpressure: Backpressure,
+    /// the maximum number of requests that may be in flight at once.
+    #[clap(long)]
+    pub max_in_flight: Option<NonZeroU32>,
+    /// a threshold that the results must satisfy, e.g. `p99<250ms` or `success_rate>=99.9`.
+    ///
+    /// this may be given many times. the metric may be a percentile such as `p99`, `mean`, or
+    /// `max` duration, or the `success_rate`, `rps`, or number of `failures`. if any threshold is
+    /// not satisfied, the process exits with a non-zero exit code.
+    #[clap(long = "assert", value_name = "THRESHOLD")]
+    pub thresholds: Vec<Threshold>,
+    /// request options.
+    #[command(flatten)]
+    pub request: Request,
+    /// tls options.
+    #[command(flatten)]
+    pub tls: Tls,
+    /// timeout options.
+    #[command(flatten)]
+    pub timeouts: Timeouts,
+    /// response validation options.
+    #[command(flatten)]
+    pub validation: Validation,
+    /// output options.
+    #[command(flatten)]
+    pub output: Output,
+    /// the number of significant figures that latencies are recorded with.
+    #[clap(long, default_value_t = 3, value_parser = clap::value_parser!(u8).range(1..=5))]
+    pub precision: u8,
     /// if true, render ascii charts after finishing.
     #[clap(long)]
     pub show_charts: bool,
-    /// if true, configure a [`tracing`] subscriber.
+    /// if true, show a live dashboard while the load-test is running.
     ///
-    /// these logs will be written to stdout.
+    /// if stdout is not a terminal, a line summarizing the load-test is printed to stderr
+    /// periodically instead.
     #[clap(long)]
+    pub live: bool,
+    /// an address to serve prometheus metrics upon while the load-test is running.
+    ///
+    /// metrics are served at the `/metrics` path, e.g. `127.0.0.1:9464`.
+    #[clap(long, value_name = "ADDR")]
+    pub metrics_listen: Option<SocketAddr>,
+    /// if true, configure a [`tracing`] subscriber.
+    ///
+    /// these logs will be written to stderr. this may also be given to `aquarius compare`.
+    #[clap(skip)]
     pub trace: bool,
     /// the address of the server to be load-tested.
     ///
-    /// this should be provided in the form of a `hostname:port` pair.
+    /// this should be provided as a url, e.g. `http://localhost:8080/api/v1/items?x=1`. use an
+    /// `https://` url to connect to the server over tls. if the scheme is omitted, `http://` is
+    /// assumed, so a `hostname:port` pair is also accepted.
     pub server: Server,
 }
 
+/// a command run by `aquarius`.
+///
+/// this is acquired via [`parse_command()`].
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// run a load-test.
+    LoadTest(Box<Cli>),
+    /// compare the results of two load-tests.
+    Compare(Compare),
+}
+
+/// the command-line interface of `aquarius`.
+///
+/// this runs a load-test described by a [`Cli`], unless a subcommand is given. the [`Cli`]
+/// arguments are added to this command when it is parsed, see [`parse::command()`].
+#[derive(Debug, Parser)]
+#[command(
+    name = "aquarius",
+    about = "aquarius: a lightweight http/2 load-tester",
+    long_about = "aquarius: a lightweight http/2 load-tester\n\n  __ _ __ _ _  _ __ _ _ _(_)_  _ ___\n / _` / _` | || / _` | '_| | || (_-<\n \\__,_\\__, |\\_,_\\__,_|_| |_|\\_,_/__/\n         |_|                     ",
+    override_usage = "aquarius [OPTIONS] <SERVER>\n       aquarius compare [OPTIONS] <BASELINE> <CANDIDATE>",
+    subcommand_negates_reqs = true
+)]
+struct Aquarius {
+    /// a subcommand to run instead of a load-test.
+    #[command(subcommand)]
+    command: Option<Subcommands>,
+    /// if true, configure a [`tracing`] subscriber.
+    ///
+    /// these logs will be written to stderr.
+    #[clap(long, global = true)]
+    trace: bool,
+}
+
+/// the subcommands of `aquarius`.
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    /// compare the results of two load-tests.
+    #[command(long_about = "compare the results of two load-tests.\n\n\
+        each run may be a summary written by `--output json`, or a request log written by \
+        `--log-requests`. if both runs are request logs, a mann-whitney u test determines \
+        whether their latencies differ significantly.")]
+    Compare(Compare),
+}
+
 mod parse {
     use super::*;
 
+    /// parses the command line arguments, returning a [`Command`].
+    ///
+    /// # panics
+    ///
+    /// NB: this prints an error and exits the process if the given arguments are malformed.
+    pub fn parse_command() -> Command {
+        try_parse_command_from(std::env::args_os()).unwrap_or_else(|error| error.exit())
+    }
+
+    /// parse a command from the provided iterator.
+    ///
+    /// if the `compare` subcommand is given, its arguments are parsed as a [`Compare`].
+    /// otherwise, they are parsed as a [`Cli`].
+    pub fn try_parse_command_from<I, T>(i: I) -> Result<Command, clap::error::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let mut matches = command().try_get_matches_from(i)?;
+        let Aquarius {
+            command: subcommand,
+            trace,
+        } = Aquarius::from_arg_matches_mut(&mut matches)?;
+        match subcommand {
+            Some(Subcommands::Compare(_)) if load_test_given(&matches) => command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "load-test options cannot be given with a subcommand",
+                )
+                .pipe(Err),
+            Some(Subcommands::Compare(compare)) => {
+                Command::Compare(Compare { trace, ..compare }).pipe(Ok)
+            }
+            None => Cli::from_arg_matches_mut(&mut matches)
+                .map(|cli| Cli { trace, ..cli })
+                .map(Box::new)
+                .map(Command::LoadTest),
+        }
+    }
+
+    /// returns the command-line interface of `aquarius`, accepting a [`Cli`] or a subcommand.
+    pub(super) fn command() -> clap::Command {
+        // NB: the [`Cli`] arguments are added first, so that its docs do not describe `aquarius`.
+        clap::Command::new("aquarius")
+            .pipe(Cli::augment_args)
+            .pipe(Aquarius::augment_args)
+    }
+
+    /// returns true if any load-test options were given on the command line.
+    ///
+    /// NB: clap ignores load-test options given alongside a subcommand, rather than rejecting them.
+    fn load_test_given(matches: &clap::ArgMatches) -> bool {
+        Cli::augment_args(clap::Command::new("aquarius"))
+            .get_arguments()
+            .any(|arg| {
+                matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine)
+            })
+    }
+
     /// parses the command line arguments, returning a [`Cli`].
     ///
     /// # panics
     ///
     /// NB: this prints an error and exits the process if the given arguments are malformed.
     pub fn parse() -> Cli {
-        Cli::parse()
+        try_parse_from(std::env::args_os()).unwrap_or_else(|error| error.exit())
     }
 
     /// parse arguments from the provided iterator.
+    ///
+    /// returns an error if a subcommand was given, see [`try_parse_command_from()`].
     pub fn try_parse_from<I, T>(i: I) -> Result<Cli, clap::error::Error>
     where
         I: IntoIterator<Item = T>,
         T: Into<OsString> + Clone,
     {
-        Cli::try_parse_from(i)
+        match try_parse_command_from(i)? {
+            Command::LoadTest(cli) => Ok(*cli),
+            Command::Compare(_) => command()
+                .error(
+                    ErrorKind::InvalidSubcommand,
+                    "expected a load-test, not a subcommand",
+                )
+                .pipe(Err),
+        }
     }
 
     // === test parse() ===
@@ -77,8 +301,10 @@ mod parse {
     #[cfg(test)]
     lazy_static::lazy_static! {
         static ref SERVER: Server = Server {
+                scheme: Scheme::HTTP,
                 host: Host::Domain("localhost").to_owned(),
                 port: 8080,
+                path: PathAndQuery::from_static("/"),
             };
     }
 
@@ -90,7 +316,25 @@ mod parse {
             Cli {
                 requests_total: None,
                 requests_per_second: None,
+                connections: None,
+                concurrency: None,
+                think_time: None,
+                duration: None,
+                grace: None,
+                stages: vec![],
+                profile: None,
+                backpressure: Backpressure::Block,
+                max_in_flight: None,
+                thresholds: vec![],
+                request: Default::default(),
+                tls: Default::default(),
+                timeouts: Default::default(),
+                validation: Default::default(),
+                output: Default::default(),
+                precision: 3,
                 show_charts: false,
+                live: false,
+                metrics_listen: None,
                 trace: false,
                 server: SERVER.clone(),
             },
@@ -107,7 +351,25 @@ mod parse {
             Cli {
                 requests_total: None,
                 requests_per_second: Some(42),
+                connections: None,
+                concurrency: None,
+                think_time: None,
+                duration: None,
+                grace: None,
+                stages: vec![],
+                profile: None,
+                backpressure: Backpressure::Block,
+                max_in_flight: None,
+                thresholds: vec![],
+                request: Default::default(),
+                tls: Default::default(),
+                timeouts: Default::default(),
+                validation: Default::default(),
+                output: Default::default(),
+                precision: 3,
                 show_charts: false,
+                live: false,
+                metrics_listen: None,
                 trace: false,
                 server: SERVER.clone(),
             },
@@ -124,7 +386,25 @@ mod parse {
             Cli {
                 requests_total: Some(666),
                 requests_per_second: None,
+                connections: None,
+                concurrency: None,
+                think_time: None,
+                duration: None,
+                grace: None,
+                stages: vec![],
+                profile: None,
+                backpressure: Backpressure::Block,
+                max_in_flight: None,
+                thresholds: vec![],
+                request: Default::default(),
+                tls: Default::default(),
+                timeouts: Default::default(),
+                validation: Default::default(),
+                output: Default::default(),
+                precision: 3,
                 show_charts: false,
+                live: false,
+                metrics_listen: None,
                 trace: false,
                 server: SERVER.clone(),
             },
@@ -141,7 +421,25 @@ mod parse {
             Cli {
                 requests_total: Some(666),
                 requests_per_second: Some(42),
+                connections: None,
+                concurrency: None,
+                think_time: None,
+                duration: None,
+                grace: None,
+                stages: vec![],
+                profile: None,
+                backpressure: Backpressure::Block,
+                max_in_flight: None,
+                thresholds: vec![],
+                request: Default::default(),
+                tls: Default::default(),
+                timeouts: Default::default(),
+                validation: Default::default(),
+                output: Default::default(),
+                precision: 3,
                 show_charts: false,
+                live: false,
+                metrics_listen: None,
                 trace: false,
                 server: SERVER.clone(),
             },
@@ -149,6 +447,724 @@ mod parse {
         );
         Ok(())
     }
+
+    #[test]
+    fn args_parser_handles_connections() -> Result<(), Error> {
+        const ARGS: &[&str] = &[AQUARIUS, "--connections", "4", ADDRESS];
+        assert_eq!(
+            try_parse_from(ARGS)?,
+            Cli {
+                requests_total: None,
+                requests_per_second: None,
+                connections: NonZeroU32::new(4),
+                concurrency: None,
+                think_time: None,
+                duration: None,
+                grace: None,
+                stages: vec![],
+                profile: None,
+                backpressure: Backpressure::Block,
+                max_in_flight: None,
+                thresholds: vec![],
+                request: Default::default(),
+                tls: Default::default(),
+                timeouts: Default::default(),
+                validation: Default::default(),
+                output: Default::default(),
+                precision: 3,
+                show_charts: false,
+                live: false,
+                metrics_listen: None,
+                trace: false,
+                server: SERVER.clone(),
+            },
+            "can parse command-line args with `--connections`"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn args_parser_rejects_zero_connections() {
+        const ARGS: &[&str] = &[AQUARIUS, "--connections", "0", ADDRESS];
+        try_parse_from(ARGS).unwrap_err();
+    }
+
+    #[test]
+    fn args_parser_handles_concurrency() -> Result<(), Error> {
+        const ARGS: &[&str] = &[
+            AQUARIUS,
+            "--concurrency",
+            "8",
+            "--think-time",
+            "250ms",
+            ADDRESS,
+        ];
+        let cli = try_parse_from(ARGS)?;
+        assert_eq!(cli.concurrency, NonZeroU32::new(8));
+        assert_eq!(cli.think_time, Some(Duration::from_millis(250)));
+        Ok(())
+    }
+
+    #[test]
+    fn args_parser_requires_concurrency_for_think_time() {
+        const ARGS: &[&str] = &[AQUARIUS, "--think-time", "1s", ADDRESS];
+        try_parse_from(ARGS).unwrap_err();
+    }
+
+    #[test]
+    fn args_parser_handles_duration() -> Result<(), Error> {
+        const ARGS: &[&str] = &[AQUARIUS, "--duration", "5m", "--grace", "10s", ADDRESS];
+        let cli = try_parse_from(ARGS)?;
+        assert_eq!(cli.duration, Some(Duration::from_secs(300)));
+        assert_eq!(cli.grace, Some(Duration::from_secs(10)));
+        Ok(())
+    }
+
+    #[test]
+    fn args_parser_handles_stages() -> Result<(), Error> {
+        const ARGS: &[&str] = &[AQUARIUS, "--stage", "30s:500", "--stage", "2m:500", ADDRESS];
+        assert_eq!(
+            try_parse_from(ARGS)?.stages,
+            vec![
+                Stage {
+                    duration: Duration::from_secs(30),
+                    target: 500
+                },
+                Stage {
+                    duration: Duration::from_secs(120),
+                    target: 500
+                },
+            ]
+        );
+        const BAD_ARGS: &[&str] = &[AQUARIUS, "--stage", "30s:500", "--rate", "5", ADDRESS];
+        try_parse_from(BAD_ARGS).unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn args_parser_handles_backpressure() -> Result<(), Error> {
+        const ARGS: &[&str] = &[
+            AQUARIUS,
+            "--backpressure",
+            "drop",
+            "--max-in-flight",
+            "64",
+            ADDRESS,
+        ];
+        let cli = try_parse_from(ARGS)?;
+        assert_eq!(cli.backpressure, Backpressure::Drop);
+        assert_eq!(cli.max_in_flight, NonZeroU32::new(64));
+        Ok(())
+    }
+
+    #[test]
+    fn args_parser_handles_tls_options() -> Result<(), Error> {
+        const ARGS: &[&str] = &[
+            AQUARIUS,
+            "--ca-cert",
+            "ca.pem",
+            "--client-cert",
+            "client.pem",
+            "--client-key",
+            "client.key",
+            "--sni",
+            "example.com",
+            "--insecure",
+            ADDRESS,
+        ];
+        assert_eq!(
+            try_parse_from(ARGS)?,
+            Cli {
+                requests_total: None,
+                requests_per_second: None,
+                connections: None,
+                concurrency: None,
+                think_time: None,
+                duration: None,
+                grace: None,
+                stages: vec![],
+                profile: None,
+                backpressure: Backpressure::Block,
+                max_in_flight: None,
+                thresholds: vec![],
+                request: Default::default(),
+                tls: Tls {
+                    ca_cert: Some("ca.pem".into()),
+                    client_cert: Some("client.pem".into()),
+                    client_key: Some("client.key".into()),
+                    sni: Some("example.com".to_owned()),
+                    insecure: true,
+                },
+                timeouts: Default::default(),
+                validation: Default::default(),
+                output: Default::default(),
+                precision: 3,
+                show_charts: false,
+                live: false,
+                metrics_listen: None,
+                trace: false,
+                server: SERVER.clone(),
+            },
+            "can parse command-line args with tls options"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn args_parser_requires_client_cert_and_key() {
+        const ARGS: &[&str] = &[AQUARIUS, "--client-cert", "client.pem", ADDRESS];
+        try_parse_from(ARGS).unwrap_err();
+    }
+
+    #[test]
+    fn args_parser_handles_request_options() -> Result<(), Error> {
+        const ARGS: &[&str] = &[
+            AQUARIUS,
+            "--method",
+            "POST",
+            "-H",
+            "content-type:application/json",
+            "--header",
+            "x-hello: world",
+            "--data",
+            "{}",
+            ADDRESS,
+        ];
+        assert_eq!(
+            try_parse_from(ARGS)?,
+            Cli {
+                requests_total: None,
+                requests_per_second: None,
+                connections: None,
+                concurrency: None,
+                think_time: None,
+                duration: None,
+                grace: None,
+                stages: vec![],
+                profile: None,
+                backpressure: Backpressure::Block,
+                max_in_flight: None,
+                thresholds: vec![],
+                request: Request {
+                    method: Method::POST,
+                    headers: vec![
+                        "content-type:application/json".parse()?,
+                        "x-hello: world".parse()?,
+                    ],
+                    data: Some("{}".to_owned()),
+                    data_file: None,
+                },
+                tls: Default::default(),
+                timeouts: Default::default(),
+                validation: Default::default(),
+                output: Default::default(),
+                precision: 3,
+                show_charts: false,
+                live: false,
+                metrics_listen: None,
+                trace: false,
+                server: SERVER.clone(),
+            },
+            "can parse command-line args with request options"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn args_parser_handles_live() -> Result<(), Error> {
+        const ARGS: &[&str] = &[AQUARIUS, "--live", ADDRESS];
+        assert!(try_parse_from(ARGS)?.live);
+        Ok(())
+    }
+
+    #[test]
+    fn args_parser_handles_metrics_listen() -> Result<(), Error> {
+        const ARGS: &[&str] = &[AQUARIUS, "--metrics-listen", "127.0.0.1:9464", ADDRESS];
+        assert_eq!(
+            try_parse_from(ARGS)?.metrics_listen,
+            Some(SocketAddr::from(([127, 0, 0, 1], 9464)))
+        );
+        const BAD_ARGS: &[&str] = &[AQUARIUS, "--metrics-listen", "localhost", ADDRESS];
+        try_parse_from(BAD_ARGS).unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn args_parser_handles_timeouts() -> Result<(), Error> {
+        const ARGS: &[&str] = &[
+            AQUARIUS,
+            "--connect-timeout",
+            "1s",
+            "--handshake-timeout",
+            "2s",
+            "--request-timeout",
+            "500ms",
+            "--total-timeout",
+            "10s",
+            ADDRESS,
+        ];
+        assert_eq!(
+            try_parse_from(ARGS)?.timeouts,
+            Timeouts {
+                connect_timeout: Some(Duration::from_secs(1)),
+                handshake_timeout: Some(Duration::from_secs(2)),
+                request_timeout: Some(Duration::from_millis(500)),
+                total_timeout: Some(Duration::from_secs(10)),
+            }
+        );
+        const BAD_ARGS: &[&str] = &[AQUARIUS, "--request-timeout", "soon", ADDRESS];
+        try_parse_from(BAD_ARGS).unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn args_parser_handles_validation_options() -> Result<(), Error> {
+        const ARGS: &[&str] = &[
+            AQUARIUS,
+            "--expect-status",
+            "200",
+            "--expect-status",
+            "3xx",
+            "--expect-header",
+            "content-type:application/json",
+            "--expect-body-regex",
+            "\\bok\\b",
+            "--expect-json",
+            "/status=ok",
+            "--max-body-size",
+            "1024",
+            ADDRESS,
+        ];
+        assert_eq!(
+            try_parse_from(ARGS)?.validation,
+            Validation {
+                expect_statuses: vec!["200".parse()?, "3xx".parse()?],
+                expect_headers: vec!["content-type:application/json".parse()?],
+                expect_body: None,
+                expect_body_regex: Some("\\bok\\b".parse()?),
+                expect_json: vec!["/status=ok".parse()?],
+                max_body_size: Some(1024),
+                expect_sha256: None,
+            }
+        );
+        const BAD_ARGS: &[&str] = &[AQUARIUS, "--expect-body-regex", "(", ADDRESS];
+        try_parse_from(BAD_ARGS).unwrap_err();
+        const BAD_DIGEST: &[&str] = &[AQUARIUS, "--expect-sha256", "abc", ADDRESS];
+        try_parse_from(BAD_DIGEST).unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn args_parser_handles_precision() -> Result<(), Error> {
+        const ARGS: &[&str] = &[AQUARIUS, "--precision", "5", ADDRESS];
+        assert_eq!(try_parse_from(ARGS)?.precision, 5);
+        const BAD_ARGS: &[&str] = &[AQUARIUS, "--precision", "6", ADDRESS];
+        try_parse_from(BAD_ARGS).unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn args_parser_handles_thresholds() -> Result<(), Error> {
+        const ARGS: &[&str] = &[
+            AQUARIUS,
+            "--assert",
+            "p99<250ms",
+            "--assert",
+            "success_rate>=99.9",
+            ADDRESS,
+        ];
+        assert_eq!(
+            try_parse_from(ARGS)?.thresholds,
+            vec!["p99<250ms".parse()?, "success_rate>=99.9".parse()?]
+        );
+        const BAD_ARGS: &[&str] = &[AQUARIUS, "--assert", "p99", ADDRESS];
+        try_parse_from(BAD_ARGS).unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn args_parser_handles_output_options() -> Result<(), Error> {
+        const ARGS: &[&str] = &[
+            AQUARIUS,
+            "--output",
+            "json",
+            "--output-path",
+            "summary.json",
+            "--log-requests",
+            "requests.ndjson",
+            "--report",
+            "report.html",
+            ADDRESS,
+        ];
+        assert_eq!(
+            try_parse_from(ARGS)?.output,
+            Output {
+                format: Some(Format::Json),
+                output_path: Some("summary.json".into()),
+                log_requests: Some("requests.ndjson".into()),
+                report: Some("report.html".into()),
+            }
+        );
+        const BAD_ARGS: &[&str] = &[AQUARIUS, "--output-path", "summary.csv", ADDRESS];
+        try_parse_from(BAD_ARGS).unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn command_parser_handles_compare() -> Result<(), Error> {
+        const ARGS: &[&str] = &[
+            AQUARIUS,
+            "compare",
+            "--budget",
+            "p99:10%",
+            "baseline.json",
+            "candidate.ndjson",
+        ];
+        assert_eq!(
+            try_parse_command_from(ARGS)?,
+            Command::Compare(Compare {
+                budgets: vec!["p99:10%".parse()?],
+                alpha: 0.05,
+                baseline: "baseline.json".into(),
+                candidate: "candidate.ndjson".into(),
+                trace: false,
+            })
+        );
+        const BAD_ARGS: &[&str] = &[AQUARIUS, "compare", "baseline.json"];
+        try_parse_command_from(BAD_ARGS).unwrap_err();
+        // a comparison is not a load-test.
+        try_parse_from(ARGS).unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn command_is_well_formed() {
+        command().debug_assert();
+    }
+
+    #[test]
+    fn command_parser_handles_trace_before_and_after_compare() -> Result<(), Error> {
+        const BEFORE: &[&str] = &[AQUARIUS, "--trace", "compare", "a.json", "b.json"];
+        const AFTER: &[&str] = &[AQUARIUS, "compare", "a.json", "b.json", "--trace"];
+        for args in [BEFORE, AFTER] {
+            let Command::Compare(compare) = try_parse_command_from(args)? else {
+                panic!("{args:?} should compare two runs");
+            };
+            assert!(compare.trace);
+        }
+
+        const LOAD_TEST: &[&str] = &[AQUARIUS, "--trace", ADDRESS];
+        assert!(try_parse_from(LOAD_TEST)?.trace);
+        assert!(!try_parse_from([AQUARIUS, ADDRESS])?.trace);
+        Ok(())
+    }
+
+    #[test]
+    fn command_parser_rejects_load_test_options_with_compare() {
+        const ARGS: &[&str] = &[AQUARIUS, "--total", "1", "compare", "a.json", "b.json"];
+        try_parse_command_from(ARGS).unwrap_err();
+    }
+
+    #[test]
+    fn command_parser_bounds_alpha() -> Result<(), Error> {
+        let parse =
+            |alpha| try_parse_command_from([AQUARIUS, "compare", "--alpha", alpha, "a", "b"]);
+        assert!(matches!(parse("0.01")?, Command::Compare(Compare { alpha, .. }) if alpha == 0.01));
+        for alpha in ["0", "1", "-0.5", "1.5", "NaN", "inf", "five"] {
+            parse(alpha).expect_err(alpha);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn command_parser_handles_load_tests() -> Result<(), Error> {
+        const ARGS: &[&str] = &[AQUARIUS, "--total", "1", ADDRESS];
+        assert_eq!(
+            try_parse_command_from(ARGS)?,
+            Command::LoadTest(Box::new(try_parse_from(ARGS)?))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn args_parser_rejects_data_and_data_file() {
+        const ARGS: &[&str] = &[AQUARIUS, "--data", "a", "--data-file", "b", ADDRESS];
+        try_parse_from(ARGS).unwrap_err();
+    }
+}
+
+mod describe {
+    use {super::*, clap::ValueEnum};
+
+    /// the value shown in place of a redacted value.
+    const REDACTED: &str = "<redacted>";
+
+    // === impl Cli ===
+
+    impl Cli {
+        /// returns the options that the load-test was run with, as `(name, value)` pairs.
+        ///
+        /// options that were not given are omitted. this is included in the html report, so
+        /// request bodies are described by their size rather than their contents, and the query
+        /// of the server's url, and the values of credential headers, are redacted.
+        pub fn describe(&self) -> Vec<(&'static str, String)> {
+            let Self {
+                server,
+                requests_total,
+                requests_per_second,
+                connections,
+                concurrency,
+                think_time,
+                duration,
+                grace,
+                stages,
+                profile,
+                backpressure,
+                max_in_flight,
+                thresholds,
+                request:
+                    Request {
+                        method,
+                        headers,
+                        data,
+                        data_file,
+                    },
+                tls:
+                    Tls {
+                        ca_cert,
+                        client_cert,
+                        client_key,
+                        sni,
+                        insecure,
+                    },
+                timeouts:
+                    Timeouts {
+                        connect_timeout,
+                        handshake_timeout,
+                        request_timeout,
+                        total_timeout,
+                    },
+                validation:
+                    Validation {
+                        expect_statuses,
+                        expect_headers,
+                        expect_body,
+                        expect_body_regex,
+                        expect_json,
+                        max_body_size,
+                        expect_sha256,
+                    },
+                output: _,
+                precision,
+                show_charts: _,
+                live: _,
+                metrics_listen: _,
+                trace: _,
+            } = self;
+
+            let server = {
+                let Server {
+                    scheme,
+                    host,
+                    port,
+                    path,
+                } = server;
+                let query = match path.query() {
+                    Some(_) => format!("?{REDACTED}"),
+                    None => String::new(),
+                };
+                format!("{scheme}://{host}:{port}{}{query}", path.path())
+            };
+            let headers = list(headers, |Header { name, value }| {
+                let value = match value.is_sensitive() {
+                    true => REDACTED.into(),
+                    false => String::from_utf8_lossy(value.as_bytes()),
+                };
+                format!("{name}: {value}")
+            });
+            let body = data
+                .as_ref()
+                .map(|data| bytes(data.len()))
+                .or_else(|| data_file.as_deref().map(path));
+            let statuses = list(expect_statuses, |range| match range.min == range.max {
+                true => range.min.as_str().to_owned(),
+                false => format!("{}-{}", range.min.as_str(), range.max.as_str()),
+            });
+            let expect_headers = list(expect_headers, |rule| match &rule.value {
+                Some(value) => format!(
+                    "{}: {}",
+                    rule.name,
+                    String::from_utf8_lossy(value.as_bytes())
+                ),
+                None => rule.name.to_string(),
+            });
+            let expect_json = list(expect_json, |rule| {
+                format!("{}={}", rule.pointer, rule.value)
+            });
+
+            [
+                ("server", Some(server)),
+                ("total", requests_total.as_ref().map(u32::to_string)),
+                ("rate", requests_per_second.as_ref().map(u32::to_string)),
+                (
+                    "connections",
+                    connections.as_ref().map(NonZeroU32::to_string),
+                ),
+                (
+                    "concurrency",
+                    concurrency.as_ref().map(NonZeroU32::to_string),
+                ),
+                ("think time", think_time.as_ref().map(duration_of)),
+                ("duration", duration.as_ref().map(duration_of)),
+                ("grace", grace.as_ref().map(duration_of)),
+                (
+                    "stages",
+                    list(stages, |stage| {
+                        format!("{}:{}", duration_of(&stage.duration), stage.target)
+                    }),
+                ),
+                ("profile", profile.as_deref().map(path)),
+                ("backpressure", name(backpressure)),
+                (
+                    "max in flight",
+                    max_in_flight.as_ref().map(NonZeroU32::to_string),
+                ),
+                ("thresholds", list(thresholds, Threshold::to_string)),
+                ("method", Some(method.to_string())),
+                ("headers", headers),
+                ("body", body),
+                ("ca cert", ca_cert.as_deref().map(path)),
+                ("client cert", client_cert.as_deref().map(path)),
+                ("client key", client_key.as_deref().map(path)),
+                ("sni", sni.clone()),
+                ("insecure", insecure.then(|| insecure.to_string())),
+                ("connect timeout", connect_timeout.as_ref().map(duration_of)),
+                (
+                    "handshake timeout",
+                    handshake_timeout.as_ref().map(duration_of),
+                ),
+                ("request timeout", request_timeout.as_ref().map(duration_of)),
+                ("total timeout", total_timeout.as_ref().map(duration_of)),
+                ("expected statuses", statuses),
+                ("expected headers", expect_headers),
+                (
+                    "expected body",
+                    expect_body.as_ref().map(|body| bytes(body.len())),
+                ),
+                (
+                    "expected body regex",
+                    expect_body_regex.as_ref().map(Pattern::to_string),
+                ),
+                ("expected json", expect_json),
+                ("max body size", max_body_size.map(bytes)),
+                (
+                    "expected sha-256",
+                    expect_sha256.as_ref().map(Sha256::to_string),
+                ),
+                ("precision", Some(precision.to_string())),
+            ]
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|value| (name, value)))
+            .collect()
+        }
+    }
+
+    /// joins a list of values, returning `None` if it is empty.
+    fn list<T>(values: &[T], f: impl FnMut(&T) -> String) -> Option<String> {
+        match values {
+            [] => None,
+            values => values
+                .iter()
+                .map(f)
+                .collect::<Vec<_>>()
+                .join(", ")
+                .pipe(Some),
+        }
+    }
+
+    /// formats a duration, e.g. `1m 30s`.
+    fn duration_of(duration: &Duration) -> String {
+        humantime::format_duration(*duration).to_string()
+    }
+
+    /// formats a path.
+    fn path(path: &std::path::Path) -> String {
+        path.display().to_string()
+    }
+
+    /// formats a number of bytes.
+    fn bytes(len: usize) -> String {
+        format!("{len} bytes")
+    }
+
+    /// returns the name of a value given on the command-line, e.g. `block`.
+    fn name<T: ValueEnum>(value: &T) -> Option<String> {
+        value
+            .to_possible_value()
+            .map(|value| value.get_name().to_owned())
+    }
+
+    // === test Cli::describe() ===
+
+    #[test]
+    fn descriptions_omit_options_that_were_not_given() -> Result<(), Error> {
+        let _guard = aquarius_test_subscriber::set_default();
+        let cli = try_parse_from([
+            "aquarius",
+            "--rate",
+            "10",
+            "--grace",
+            "5s",
+            "localhost:8080",
+        ])?;
+        assert_eq!(
+            cli.describe(),
+            [
+                ("server", "http://localhost:8080/".to_owned()),
+                ("rate", "10".to_owned()),
+                ("grace", "5s".to_owned()),
+                ("backpressure", "block".to_owned()),
+                ("method", "GET".to_owned()),
+                ("precision", "3".to_owned()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn descriptions_redact_secrets() -> Result<(), Error> {
+        let _guard = aquarius_test_subscriber::set_default();
+        let cli = try_parse_from([
+            "aquarius",
+            "-H",
+            "authorization: Bearer hunter2",
+            "-H",
+            "x-hello: world",
+            "--data",
+            "{\"password\":\"hunter2\"}",
+            "localhost:8080/login?token=hunter2",
+        ])?;
+        let description = cli.describe();
+        let value = |name| {
+            description
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(
+            value("server"),
+            Some("http://localhost:8080/login?<redacted>")
+        );
+        assert_eq!(
+            value("headers"),
+            Some("authorization: <redacted>, x-hello: world")
+        );
+        assert_eq!(value("body"), Some("22 bytes"));
+        assert!(description
+            .iter()
+            .all(|(_, value)| !value.contains("hunter2")));
+        Ok(())
+    }
 }
 
 mod server {
@@ -157,10 +1173,14 @@ mod server {
     /// the address of the server to be load-tested.
     #[derive(Clone, Debug, Eq, PartialEq)]
     pub struct Server {
+        /// the scheme, either `http` or `https`.
+        pub scheme: Scheme,
         /// the host name.
         pub host: Host,
         /// the port number.
         pub port: u16,
+        /// the path and query to send requests to.
+        pub path: PathAndQuery,
     }
 
     // === impl Server ===
@@ -168,16 +1188,41 @@ mod server {
     impl FromStr for Server {
         type Err = Error;
         fn from_str(server: &str) -> Result<Self, Self::Err> {
-            let (host, port) = server
-                .rsplit_once(':')
-                .ok_or("server address must be given as `hostname:port`")?;
+            // assume that the server speaks cleartext http/2 if no scheme was given.
+            let url = match server.contains("://") {
+                true => url::Url::parse(server)?,
+                false => url::Url::parse(&format!("http://{server}"))?,
+            };
+
+            let scheme = match url.scheme() {
+                "http" => Scheme::HTTP,
+                "https" => Scheme::HTTPS,
+                scheme => return Err(format!("unsupported scheme `{scheme}`").into()),
+            };
 
-            let host = Host::parse(host)?.tap(|host| trace!(?host, "parsed server hostname"));
-            let port = port
-                .parse::<u16>()?
-                .tap(|host| trace!(?host, "parsed server port"));
+            let host = url
+                .host()
+                .ok_or("server address must include a host")?
+                .to_owned()
+                .tap(|host| trace!(?host, "parsed server hostname"));
+            let port = url
+                .port_or_known_default()
+                .ok_or("server address must include a port")?
+                .tap(|port| trace!(?port, "parsed server port"));
+            let path = match url.query() {
+                Some(query) => format!("{}?{query}", url.path()),
+                None => url.path().to_owned(),
+            }
+            .pipe(PathAndQuery::try_from)?
+            .tap(|path| trace!(?path, "parsed server path"));
 
-            Ok(Self { host, port }.tap(|server| debug!(?server, "parsed server address")))
+            Ok(Self {
+                scheme,
+                host,
+                port,
+                path,
+            }
+            .tap(|server| debug!(?server, "parsed server address")))
         }
     }
 
@@ -201,6 +1246,46 @@ mod server {
         "localhost:8080".parse::<Server>().map(|_| ())
     }
 
+    #[test]
+    fn https_scheme_can_be_parsed() -> Result<(), Error> {
+        let _guard = aquarius_test_subscriber::set_default();
+        let server = "https://localhost:8443".parse::<Server>()?;
+        assert_eq!(server.scheme, Scheme::HTTPS);
+        assert_eq!(server.port, 8443);
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_scheme_is_rejected() -> Result<(), Error> {
+        let _guard = aquarius_test_subscriber::set_default();
+        "ftp://localhost:8080".parse::<Server>().unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn full_url_can_be_parsed() -> Result<(), Error> {
+        let _guard = aquarius_test_subscriber::set_default();
+        let server = "http://localhost:8080/api/v1/items?x=1".parse::<Server>()?;
+        assert_eq!(
+            server,
+            Server {
+                scheme: Scheme::HTTP,
+                host: Host::Domain("localhost".to_owned()),
+                port: 8080,
+                path: PathAndQuery::from_static("/api/v1/items?x=1"),
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn port_defaults_to_scheme_port() -> Result<(), Error> {
+        let _guard = aquarius_test_subscriber::set_default();
+        assert_eq!("http://localhost".parse::<Server>()?.port, 80);
+        assert_eq!("https://localhost/path".parse::<Server>()?.port, 443);
+        Ok(())
+    }
+
     #[test]
     fn port_must_be_a_number() -> Result<(), Error> {
         let _guard = aquarius_test_subscriber::set_default();
@@ -218,3 +1303,271 @@ mod server {
         Ok(())
     }
 }
+
+mod request {
+    use super::*;
+
+    /// request options.
+    ///
+    /// these describe the request that each worker sends to the server.
+    #[derive(Args, Clone, Debug, Default, Eq, PartialEq)]
+    pub struct Request {
+        /// the request method.
+        #[clap(long, short = 'X', default_value = "GET")]
+        pub method: Method,
+        /// a header to include in each request, given as a `name:value` pair.
+        ///
+        /// this may be provided multiple times.
+        #[clap(long = "header", short = 'H', value_name = "NAME:VALUE")]
+        pub headers: Vec<Header>,
+        /// the request body.
+        #[clap(long, conflicts_with = "data_file")]
+        pub data: Option<String>,
+        /// a file whose contents will be used as the request body.
+        #[clap(long)]
+        pub data_file: Option<PathBuf>,
+    }
+
+    /// a request header, given as a `name:value` pair.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct Header {
+        /// the header name.
+        pub name: HeaderName,
+        /// the header value.
+        pub value: HeaderValue,
+    }
+
+    // === impl Header ===
+
+    impl Header {
+        /// headers whose values are redacted when formatted with [`Debug`].
+        const SENSITIVE: &'static [HeaderName] = &[
+            http::header::AUTHORIZATION,
+            http::header::COOKIE,
+            http::header::PROXY_AUTHORIZATION,
+        ];
+    }
+
+    impl FromStr for Header {
+        type Err = Error;
+        fn from_str(header: &str) -> Result<Self, Self::Err> {
+            let (name, value) = header
+                .split_once(':')
+                .ok_or("header must be given as `name:value`")?;
+
+            let name = name.trim().parse::<HeaderName>()?;
+            let mut value = value.trim().parse::<HeaderValue>()?;
+
+            // credentials should not be logged, or written to a report.
+            if Self::SENSITIVE.contains(&name) {
+                value.set_sensitive(true);
+            }
+
+            Ok(Self { name, value }.tap(|header| trace!(?header, "parsed header")))
+        }
+    }
+
+    // === test Header ===
+
+    #[test]
+    fn header_can_be_parsed() -> Result<(), Error> {
+        let _guard = aquarius_test_subscriber::set_default();
+        let Header { name, value } = "X-Hello: world".parse::<Header>()?;
+        assert_eq!(name, "x-hello");
+        assert_eq!(value, "world");
+        Ok(())
+    }
+
+    #[test]
+    fn credential_headers_are_redacted() -> Result<(), Error> {
+        let _guard = aquarius_test_subscriber::set_default();
+        let header = "Authorization: Bearer hunter2".parse::<Header>()?;
+        assert_eq!(header.value, "Bearer hunter2");
+        assert!(!format!("{header:?}").contains("hunter2"));
+        Ok(())
+    }
+
+    #[test]
+    fn header_must_have_a_value() -> Result<(), Error> {
+        let _guard = aquarius_test_subscriber::set_default();
+        "x-hello".parse::<Header>().unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn header_name_must_be_valid() -> Result<(), Error> {
+        let _guard = aquarius_test_subscriber::set_default();
+        "bad header:value".parse::<Header>().unwrap_err();
+        Ok(())
+    }
+}
+
+mod tls {
+    use super::*;
+
+    /// tls options.
+    ///
+    /// these are used when connecting to an `https://` server.
+    #[derive(Args, Clone, Debug, Default, Eq, PartialEq)]
+    pub struct Tls {
+        /// a PEM-encoded bundle of ca certificates used to verify the server.
+        ///
+        /// if omitted, the server is verified using the mozilla root certificates.
+        #[clap(long)]
+        pub ca_cert: Option<PathBuf>,
+        /// a PEM-encoded client certificate, used for mutual tls.
+        #[clap(long, requires = "client_key")]
+        pub client_cert: Option<PathBuf>,
+        /// a PEM-encoded private key for the client certificate.
+        #[clap(long, requires = "client_cert")]
+        pub client_key: Option<PathBuf>,
+        /// the server name to send during the tls handshake.
+        ///
+        /// if omitted, the server's host name is used.
+        #[clap(long)]
+        pub sni: Option<String>,
+        /// if true, skip verification of the server's certificate.
+        ///
+        /// this is useful for testing against servers using self-signed certificates.
+        #[clap(long)]
+        pub insecure: bool,
+    }
+}
+
+mod timeouts {
+    use super::*;
+
+    /// timeout options.
+    ///
+    /// requests that exceed a timeout fail, and are reported with the phase that timed out.
+    #[derive(Args, Clone, Debug, Default, Eq, PartialEq)]
+    pub struct Timeouts {
+        /// how long to wait for the server's host name to resolve, and a tcp connection to be
+        /// established, e.g. `1s`.
+        #[clap(long, value_parser = humantime::parse_duration)]
+        pub connect_timeout: Option<Duration>,
+        /// how long to wait for the tls and http/2 handshakes to finish, e.g. `1s`.
+        #[clap(long, value_parser = humantime::parse_duration)]
+        pub handshake_timeout: Option<Duration>,
+        /// how long to wait for a response, including its body, once a request is sent, e.g.
+        /// `5s`.
+        #[clap(long, value_parser = humantime::parse_duration)]
+        pub request_timeout: Option<Duration>,
+        /// how long each request may take from start to finish, including connecting, e.g.
+        /// `10s`.
+        #[clap(long, value_parser = humantime::parse_duration)]
+        pub total_timeout: Option<Duration>,
+    }
+}
+
+mod validation {
+    use super::*;
+
+    /// response validation options.
+    ///
+    /// by default, responses with a `2xx` status code are successful. responses that break any of
+    /// these rules are counted as failures, by the kind of rule that they broke.
+    #[derive(Args, Clone, Debug, Default, Eq, PartialEq)]
+    pub struct Validation {
+        /// a status code that responses may have, e.g. `200`, `200-299` or `2xx`.
+        ///
+        /// this may be given many times. if given, responses with any other status code fail.
+        #[clap(long = "expect-status", value_name = "STATUS")]
+        pub expect_statuses: Vec<StatusRange>,
+        /// a header that responses must include, given as `name` or a `name:value` pair.
+        ///
+        /// this may be given many times.
+        #[clap(long = "expect-header", value_name = "NAME[:VALUE]")]
+        pub expect_headers: Vec<HeaderRule>,
+        /// the exact body that responses must have.
+        #[clap(long, value_name = "BODY")]
+        pub expect_body: Option<String>,
+        /// a regular expression that response bodies must match.
+        #[clap(long, value_name = "REGEX")]
+        pub expect_body_regex: Option<Pattern>,
+        /// a json value that response bodies must contain, given as a `POINTER=VALUE` pair.
+        ///
+        /// this may be given many times. the pointer identifies a value within the body, e.g.
+        /// `/data/0/status=ok`. the value is parsed as json, or treated as a string otherwise.
+        #[clap(long = "expect-json", value_name = "POINTER=VALUE")]
+        pub expect_json: Vec<JsonRule>,
+        /// the largest response body that is permitted, in bytes.
+        #[clap(long, value_name = "BYTES")]
+        pub max_body_size: Option<usize>,
+        /// the sha-256 digest that response bodies must have, in hexadecimal.
+        #[clap(long, value_name = "HEX")]
+        pub expect_sha256: Option<Sha256>,
+    }
+}
+
+mod output {
+    use super::*;
+
+    /// output options.
+    ///
+    /// these describe how results are written, in addition to the charts printed by
+    /// `--show-charts`.
+    #[derive(Args, Clone, Debug, Default, Eq, PartialEq)]
+    pub struct Output {
+        /// write the summary in a machine-readable format.
+        #[clap(long = "output", value_enum, value_name = "FORMAT")]
+        pub format: Option<Format>,
+        /// the file that the summary is written to.
+        ///
+        /// if omitted, the summary is written to stdout.
+        #[clap(long, requires = "format")]
+        pub output_path: Option<PathBuf>,
+        /// a file to log each request to as it completes, as newline-delimited json.
+        #[clap(long, value_name = "PATH")]
+        pub log_requests: Option<PathBuf>,
+        /// a file to write a self-contained html report to, once the load-test has finished.
+        ///
+        /// the report includes tables and charts of the results, and the options that the
+        /// load-test was run with. request bodies are described only by their size, and the
+        /// server's query string and the values of credential headers, such as `authorization`,
+        /// are redacted.
+        #[clap(long, value_name = "PATH")]
+        pub report: Option<PathBuf>,
+    }
+}
+
+mod compare {
+    use super::*;
+
+    /// command-line options for `aquarius compare`.
+    ///
+    /// these are acquired via [`parse_command()`].
+    #[derive(Args, Debug, PartialEq)]
+    pub struct Compare {
+        /// how far a metric may regress, written as `METRIC:PERCENT`, e.g. `p99:10%`.
+        ///
+        /// this may be given many times. the metric may be a percentile such as `p99`, or the
+        /// `success_rate`, `rps`, or number of `requests`. if any metric regresses beyond its
+        /// budget, the process exits with a non-zero exit code.
+        #[clap(long = "budget", value_name = "BUDGET")]
+        pub budgets: Vec<Budget>,
+        /// the significance level of the mann-whitney u test.
+        ///
+        /// this must be strictly between 0 and 1.
+        #[clap(long, default_value_t = 0.05, value_parser = parse_alpha)]
+        pub alpha: f64,
+        /// the results of the baseline load-test.
+        pub baseline: PathBuf,
+        /// the results of the candidate load-test.
+        pub candidate: PathBuf,
+        /// if true, configure a [`tracing`] subscriber.
+        ///
+        /// these logs will be written to stderr. this may be given before or after `compare`.
+        #[clap(skip)]
+        pub trace: bool,
+    }
+
+    /// parses a significance level, which must be strictly between 0 and 1.
+    fn parse_alpha(alpha: &str) -> Result<f64, String> {
+        match alpha.parse::<f64>() {
+            Ok(alpha) if alpha > 0.0 && alpha < 1.0 => Ok(alpha),
+            Ok(alpha) => Err(format!("{alpha} is not between 0 and 1")),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+}
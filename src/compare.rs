@@ -0,0 +1,215 @@
+//! facilities for comparing the results of two load-tests.
+//!
+//! a baseline [`Run`] and a candidate [`Run`] are read from summaries written by `--output json`,
+//! or from request logs written by `--log-requests`. a [`Comparison`] of the two reports the
+//! change in each metric. if both runs were read from request logs, their latencies are also
+//! compared using a [`MannWhitney`] U test, to determine whether the difference is significant.
+//! a [`Budget`] bounds how far a metric may regress.
+
+pub use self::{
+    budget::{Budget, Verdict},
+    mann_whitney::MannWhitney,
+    run::Run,
+};
+
+use {
+    crate::{error::Error, summary::Summary},
+    std::{fmt, str::FromStr, time::Duration},
+    tap::Pipe,
+};
+
+mod budget;
+mod mann_whitney;
+mod run;
+
+/// a comparison of a baseline and candidate [`Run`].
+pub struct Comparison {
+    deltas: Vec<Delta>,
+    test: Option<MannWhitney>,
+}
+
+/// the change in a [`Metric`] between a baseline and candidate [`Run`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Delta {
+    /// the metric that was compared.
+    pub metric: Metric,
+    /// the value observed in the baseline.
+    pub baseline: f64,
+    /// the value observed in the candidate.
+    pub candidate: f64,
+}
+
+/// a metric that may be compared between two runs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Metric {
+    /// the total number of requests.
+    Requests,
+    /// the success rate, as a percentage.
+    SuccessRate,
+    /// the number of requests that finished per second.
+    Rps,
+    /// the request duration at a percentile, in microseconds.
+    Percentile(f64),
+}
+
+// === impl Comparison ===
+
+impl Comparison {
+    /// compares a baseline run to a candidate run.
+    pub fn new(baseline: &Run, candidate: &Run) -> Self {
+        let deltas = [Metric::Requests, Metric::SuccessRate, Metric::Rps]
+            .into_iter()
+            .chain(Summary::PERCENTILES.iter().copied().map(Metric::Percentile))
+            .filter_map(|metric| Delta::new(metric, baseline, candidate))
+            .collect();
+        let test = baseline
+            .latencies()
+            .zip(candidate.latencies())
+            .and_then(|(baseline, candidate)| MannWhitney::test(baseline, candidate));
+        Self { deltas, test }
+    }
+
+    /// returns the change in each metric.
+    pub fn deltas(&self) -> &[Delta] {
+        &self.deltas
+    }
+
+    /// returns the result of testing whether latencies differ significantly.
+    ///
+    /// this is `None` unless both runs were read from request logs.
+    pub fn test(&self) -> Option<&MannWhitney> {
+        self.test.as_ref()
+    }
+}
+
+// === impl Delta ===
+
+impl Delta {
+    /// returns the change in a metric between two runs.
+    ///
+    /// returns `None` if the metric was not recorded by either run.
+    pub fn new(metric: Metric, baseline: &Run, candidate: &Run) -> Option<Self> {
+        Some(Self {
+            metric,
+            baseline: metric.observe(baseline)?,
+            candidate: metric.observe(candidate)?,
+        })
+    }
+
+    /// returns the difference between the candidate and the baseline.
+    pub fn difference(&self) -> f64 {
+        self.candidate - self.baseline
+    }
+
+    /// returns the difference between the candidate and the baseline, as a percentage.
+    ///
+    /// returns `None` if the baseline was zero.
+    pub fn change(&self) -> Option<f64> {
+        (self.baseline != 0.0).then(|| self.difference() / self.baseline * 100.0)
+    }
+
+    /// returns how far the candidate regressed, as a percentage of the baseline.
+    ///
+    /// this is negative if the candidate improved. regressing from a baseline of zero is treated
+    /// as an unbounded regression.
+    pub fn regression(&self) -> f64 {
+        let change = match self.change() {
+            Some(change) => change,
+            None if self.difference() == 0.0 => 0.0,
+            None => self.difference().signum() * f64::INFINITY,
+        };
+        match self.metric.higher_is_better() {
+            true => -change,
+            false => change,
+        }
+    }
+}
+
+// === impl Metric ===
+
+impl Metric {
+    /// returns the value of this metric observed in a run, if it was recorded.
+    fn observe(&self, run: &Run) -> Option<f64> {
+        match self {
+            Self::Requests => Some(run.total() as f64),
+            Self::SuccessRate => Some(run.success_rate()),
+            Self::Rps => Some(run.rps()),
+            Self::Percentile(p) => run.percentile(*p).map(|d| d.as_micros() as f64),
+        }
+    }
+
+    /// returns true if larger values of this metric are an improvement.
+    fn higher_is_better(&self) -> bool {
+        match self {
+            Self::Requests | Self::SuccessRate | Self::Rps => true,
+            Self::Percentile(_) => false,
+        }
+    }
+
+    /// formats a value of this metric.
+    pub fn format(&self, value: f64) -> String {
+        match self {
+            Self::Requests => format!("{value}"),
+            Self::SuccessRate => format!("{value:.2}%"),
+            Self::Rps => format!("{value:.1}"),
+            Self::Percentile(_) => format!("{value}µs"),
+        }
+    }
+}
+
+impl FromStr for Metric {
+    type Err = Error;
+    fn from_str(metric: &str) -> Result<Self, Self::Err> {
+        match metric {
+            "requests" => Ok(Self::Requests),
+            "success_rate" => Ok(Self::SuccessRate),
+            "rps" => Ok(Self::Rps),
+            _ => match metric.strip_prefix('p').map(str::parse::<f64>) {
+                Some(Ok(p)) if (0.0..=100.0).contains(&p) => Ok(Self::Percentile(p)),
+                Some(_) => Err("percentile must be between 0 and 100".into()),
+                None => Err(format!(
+                    "unknown metric `{metric}`, expected one of `pNN`, `requests`, \
+                     `success_rate`, or `rps`"
+                )
+                .into()),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Requests => f.write_str("requests"),
+            Self::SuccessRate => f.write_str("success_rate"),
+            Self::Rps => f.write_str("rps"),
+            Self::Percentile(p) => write!(f, "p{p}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regressions_account_for_the_direction_of_a_metric() {
+        let delta = |metric, baseline, candidate| Delta {
+            metric,
+            baseline,
+            candidate,
+        };
+
+        let slower = delta(Metric::Percentile(99.0), 100.0, 110.0);
+        assert_eq!(slower.change(), Some(10.0));
+        assert_eq!(slower.regression(), 10.0);
+
+        let faster = delta(Metric::Rps, 100.0, 110.0);
+        assert_eq!(faster.change(), Some(10.0));
+        assert_eq!(faster.regression(), -10.0);
+
+        let from_zero = delta(Metric::Percentile(50.0), 0.0, 1.0);
+        assert_eq!(from_zero.change(), None);
+        assert_eq!(from_zero.regression(), f64::INFINITY);
+    }
+}
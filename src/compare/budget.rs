@@ -0,0 +1,146 @@
+//! regression budgets.
+
+use super::*;
+
+/// a bound on how far a [`Metric`] may regress, as a percentage of the baseline.
+///
+/// budgets are written as `METRIC:PERCENT`, e.g. `p99:10%` allows the p99 duration to grow by
+/// up to 10%, while `rps:5%` allows throughput to fall by up to 5%.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Budget {
+    /// the metric that is bounded.
+    pub metric: Metric,
+    /// how far the metric may regress, as a percentage.
+    pub percent: f64,
+}
+
+/// the outcome of checking a [`Delta`] against a [`Budget`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Verdict {
+    /// the budget that was checked.
+    pub budget: Budget,
+    /// the change that was observed.
+    pub delta: Delta,
+    /// true if the change was within budget.
+    pub passed: bool,
+}
+
+// === impl Budget ===
+
+impl Budget {
+    /// checks whether the candidate regressed beyond this budget.
+    ///
+    /// returns an error if the metric was not recorded by both runs.
+    pub fn check(&self, baseline: &Run, candidate: &Run) -> Result<Verdict, Error> {
+        let delta = Delta::new(self.metric, baseline, candidate)
+            .ok_or_else(|| format!("`{}` was not recorded by both runs", self.metric))?;
+        Ok(Verdict {
+            budget: *self,
+            delta,
+            passed: delta.regression() <= self.percent,
+        })
+    }
+}
+
+impl FromStr for Budget {
+    type Err = Error;
+    fn from_str(budget: &str) -> Result<Self, Self::Err> {
+        let (metric, percent) = budget
+            .split_once(':')
+            .ok_or("budget must be written as `METRIC:PERCENT`, e.g. `p99:10%`")?;
+        let metric = metric.trim().parse()?;
+        let percent = percent
+            .trim()
+            .trim_end_matches('%')
+            .parse::<f64>()?
+            .pipe(|percent| {
+                (percent.is_finite() && percent >= 0.0)
+                    .then_some(percent)
+                    .ok_or("budget must be a non-negative percentage")
+            })?;
+        Ok(Self { metric, percent })
+    }
+}
+
+impl fmt::Display for Budget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { metric, percent } = self;
+        write!(f, "{metric}:{percent}%")
+    }
+}
+
+// === impl Verdict ===
+
+impl Verdict {
+    /// returns true if every verdict passed.
+    pub fn all_passed<'a>(verdicts: impl IntoIterator<Item = &'a Self>) -> bool {
+        verdicts.into_iter().all(|verdict| verdict.passed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// returns a run read from a saved summary.
+    fn run(rps: f64, p99: u64) -> Run {
+        serde_json::json!({
+            "total": 100,
+            "successes": 100,
+            "rps": rps,
+            "duration": { "percentiles": { "p99_us": p99 } },
+        })
+        .to_string()
+        .parse()
+        .expect("run is valid")
+    }
+
+    #[test]
+    fn budgets_can_be_parsed() -> Result<(), Error> {
+        let budget = "p99:10%".parse::<Budget>()?;
+        assert_eq!(budget.metric, Metric::Percentile(99.0));
+        assert_eq!(budget.percent, 10.0);
+        assert_eq!(budget.to_string(), "p99:10%");
+        assert_eq!("rps:5".parse::<Budget>()?.metric, Metric::Rps);
+        "p99".parse::<Budget>().unwrap_err();
+        "p99:-1%".parse::<Budget>().unwrap_err();
+        "latency:1%".parse::<Budget>().unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn regressions_beyond_budget_fail() -> Result<(), Error> {
+        let baseline = run(1000.0, 100);
+        let candidate = run(900.0, 108);
+
+        assert!(
+            "p99:10%"
+                .parse::<Budget>()?
+                .check(&baseline, &candidate)?
+                .passed
+        );
+        assert!(
+            !"p99:5%"
+                .parse::<Budget>()?
+                .check(&baseline, &candidate)?
+                .passed
+        );
+        assert!(
+            !"rps:5%"
+                .parse::<Budget>()?
+                .check(&baseline, &candidate)?
+                .passed
+        );
+        assert!(
+            "rps:5%"
+                .parse::<Budget>()?
+                .check(&candidate, &baseline)?
+                .passed
+        );
+        "p50:5%"
+            .parse::<Budget>()?
+            .check(&baseline, &candidate)
+            .unwrap_err();
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+//! the mann-whitney u test.
+
+/// the result of a mann-whitney u test, comparing baseline and candidate latencies.
+///
+/// this tests whether latencies from one run tend to be larger than latencies from the other,
+/// without assuming that they are normally distributed. the p-value is computed using a normal
+/// approximation, corrected for ties, which is accurate for the sample sizes of a load-test.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MannWhitney {
+    /// the u statistic of the candidate's latencies.
+    pub u: f64,
+    /// the standard score of the u statistic.
+    pub z: f64,
+    /// the two-sided p-value.
+    pub p_value: f64,
+    /// the probability that a candidate latency exceeds a baseline latency.
+    ///
+    /// ties count as half. this is `0.5` if neither run is faster than the other, and greater if
+    /// the candidate is slower.
+    pub effect: f64,
+}
+
+// === impl MannWhitney ===
+
+impl MannWhitney {
+    /// tests whether the given samples differ.
+    ///
+    /// returns `None` if either sample is empty, or if every value is identical.
+    pub fn test(baseline: &[u64], candidate: &[u64]) -> Option<Self> {
+        let (n1, n2) = (baseline.len() as f64, candidate.len() as f64);
+        if baseline.is_empty() || candidate.is_empty() {
+            return None;
+        }
+
+        // rank the combined samples, giving tied values the mean of their ranks.
+        let mut combined = baseline
+            .iter()
+            .map(|v| (*v, false))
+            .chain(candidate.iter().map(|v| (*v, true)))
+            .collect::<Vec<_>>();
+        combined.sort_unstable_by_key(|(value, _)| *value);
+
+        let (mut rank_sum, mut ties) = (0.0, 0.0);
+        let mut start = 0;
+        while start < combined.len() {
+            let value = combined[start].0;
+            let end = start + combined[start..].partition_point(|(v, _)| *v == value);
+            let count = (end - start) as f64;
+            let rank = (start + end + 1) as f64 / 2.0; // the mean of ranks `start+1..=end`.
+            let from_candidate = combined[start..end].iter().filter(|(_, c)| *c).count();
+            rank_sum += rank * from_candidate as f64;
+            ties += count.powi(3) - count;
+            start = end;
+        }
+
+        // compute the u statistic, and its standard score.
+        let n = n1 + n2;
+        let u = rank_sum - n2 * (n2 + 1.0) / 2.0;
+        let mean = n1 * n2 / 2.0;
+        let variance = n1 * n2 / 12.0 * ((n + 1.0) - ties / (n * (n - 1.0)));
+        if variance <= 0.0 {
+            return None;
+        }
+        let z = (u - mean).signum() * ((u - mean).abs() - 0.5).max(0.0) / variance.sqrt();
+
+        Some(Self {
+            u,
+            z,
+            p_value: erfc(z.abs() / std::f64::consts::SQRT_2).min(1.0),
+            effect: u / (n1 * n2),
+        })
+    }
+
+    /// returns true if the difference is significant at the given level, e.g. `0.05`.
+    pub fn is_significant(&self, alpha: f64) -> bool {
+        self.p_value < alpha
+    }
+}
+
+/// the complementary error function.
+///
+/// this uses a chebyshev approximation, with a fractional error below 1.2e-7.
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = [
+        -1.26551223,
+        1.00002368,
+        0.37409196,
+        0.09678418,
+        -0.18628806,
+        0.27886807,
+        -1.13520398,
+        1.48851587,
+        -0.82215223,
+        0.17087277,
+    ]
+    .iter()
+    .rev()
+    .fold(0.0, |acc, c| c + t * acc);
+    let ans = t * (-z * z + poly).exp();
+    if x >= 0.0 {
+        ans
+    } else {
+        2.0 - ans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn erfc_is_approximated() {
+        assert!((erfc(0.0) - 1.0).abs() < 1e-6);
+        assert!((erfc(1.0) - 0.157_299_207).abs() < 1e-6);
+        assert!((erfc(-1.0) - 1.842_700_793).abs() < 1e-6);
+    }
+
+    #[test]
+    fn separated_samples_differ_significantly() {
+        let test = MannWhitney::test(&[1, 2, 3, 4, 5], &[6, 7, 8, 9, 10]).unwrap();
+        assert_eq!(test.u, 25.0);
+        assert_eq!(test.effect, 1.0, "the candidate is always slower");
+        assert!(test.z > 2.5);
+        assert!(test.is_significant(0.05));
+        assert!((test.p_value - 0.0122).abs() < 1e-3);
+    }
+
+    #[test]
+    fn interleaved_samples_do_not_differ_significantly() {
+        let test = MannWhitney::test(&[1, 3, 5, 7, 9], &[2, 4, 6, 8, 10]).unwrap();
+        assert_eq!(test.effect, 0.6);
+        assert!(!test.is_significant(0.05));
+    }
+
+    #[test]
+    fn ties_share_their_ranks() {
+        let test = MannWhitney::test(&[1, 2, 2], &[2, 2, 3]).unwrap();
+        assert_eq!(test.u, 7.0);
+        assert_eq!(test.effect, 7.0 / 9.0);
+        assert!(!test.is_significant(0.05));
+
+        assert_eq!(MannWhitney::test(&[1, 1], &[1, 1]), None, "all values tie");
+        assert_eq!(MannWhitney::test(&[], &[1]), None);
+    }
+}
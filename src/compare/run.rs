@@ -0,0 +1,254 @@
+//! facilities for reading the results of a load-test.
+
+use {
+    super::*,
+    serde::Deserialize,
+    std::{collections::BTreeMap, path::Path},
+};
+
+/// the results of a load-test, to be compared with another.
+///
+/// see [`Run::read()`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Run {
+    total: u64,
+    successes: u64,
+    rps: f64,
+    /// request durations at each recorded percentile.
+    percentiles: Vec<(f64, Duration)>,
+    /// the duration of each request in microseconds, in ascending order, if they were logged.
+    latencies: Option<Vec<u64>>,
+}
+
+/// the fields of a summary written by `--output json` that are compared.
+#[derive(Deserialize)]
+struct Saved {
+    total: u64,
+    successes: u64,
+    rps: f64,
+    duration: SavedDistribution,
+}
+
+/// the fields of a distribution written by `--output json` that are compared.
+#[derive(Deserialize)]
+struct SavedDistribution {
+    percentiles: BTreeMap<String, u64>,
+}
+
+/// the fields of a request logged by `--log-requests` that are compared.
+#[derive(Deserialize)]
+struct Logged {
+    start_us: u64,
+    end_us: u64,
+    duration_us: u64,
+    success: bool,
+}
+
+// === impl Run ===
+
+impl FromStr for Run {
+    type Err = Error;
+    /// parses a summary, or a request log.
+    ///
+    /// NB: both are json objects, so if neither can be parsed, both errors are reported.
+    fn from_str(contents: &str) -> Result<Self, Self::Err> {
+        let saved = match serde_json::from_str::<Saved>(contents) {
+            Ok(saved) => return Self::from_saved(saved),
+            Err(error) => error,
+        };
+        Self::from_log(contents).map_err(|logged| {
+            format!("not a json summary ({saved}), nor a request log ({logged})").into()
+        })
+    }
+}
+
+impl Run {
+    /// reads a run from the given path.
+    ///
+    /// this accepts a summary written by `--output json`, or a request log written by
+    /// `--log-requests`.
+    pub fn read(path: &Path) -> Result<Self, Error> {
+        std::fs::read_to_string(path)?
+            .parse()
+            .map_err(|error| format!("could not read `{}`: {error}", path.display()).into())
+    }
+
+    /// returns a run from a saved summary.
+    fn from_saved(
+        Saved {
+            total,
+            successes,
+            rps,
+            duration: SavedDistribution { percentiles },
+        }: Saved,
+    ) -> Result<Self, Error> {
+        let percentiles = percentiles
+            .into_iter()
+            .map(|(key, micros)| {
+                let percentile = key
+                    .strip_prefix('p')
+                    .and_then(|key| key.strip_suffix("_us"))
+                    .ok_or("malformed percentile")?
+                    .parse::<f64>()?;
+                Ok::<_, Error>((percentile, Duration::from_micros(micros)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            total,
+            successes,
+            rps,
+            percentiles,
+            latencies: None,
+        })
+    }
+
+    /// returns a run from the lines of a request log.
+    fn from_log(log: &str) -> Result<Self, Error> {
+        let requests = log
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str::<Logged>(line)
+                    .map_err(|error| format!("line {}: {error}", index + 1))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if requests.is_empty() {
+            return Err("no requests were logged".into());
+        }
+
+        let total = requests.len() as u64;
+        let successes = requests.iter().filter(|r| r.success).count() as u64;
+        let start = requests
+            .iter()
+            .map(|r| r.start_us)
+            .min()
+            .unwrap_or_default();
+        let end = requests.iter().map(|r| r.end_us).max().unwrap_or_default();
+        let rps = match end.saturating_sub(start) {
+            0 => 0.0,
+            micros => total as f64 / Duration::from_micros(micros).as_secs_f64(),
+        };
+        let mut latencies = requests.iter().map(|r| r.duration_us).collect::<Vec<_>>();
+        latencies.sort_unstable();
+        let percentiles = Summary::PERCENTILES
+            .iter()
+            .map(|p| (*p, nearest_rank(&latencies, *p)))
+            .collect();
+
+        Ok(Self {
+            total,
+            successes,
+            rps,
+            percentiles,
+            latencies: Some(latencies),
+        })
+    }
+
+    /// returns the total number of requests.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// returns the success rate, as a percentage.
+    pub fn success_rate(&self) -> f64 {
+        match self.total {
+            0 => 0.0,
+            total => self.successes as f64 / total as f64 * 100.0,
+        }
+    }
+
+    /// returns the number of requests that finished per second.
+    pub fn rps(&self) -> f64 {
+        self.rps
+    }
+
+    /// returns the request duration at the given percentile, if it was recorded.
+    ///
+    /// runs read from a request log may be queried at any percentile. runs read from a summary
+    /// only record the [`Summary::PERCENTILES`].
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        match &self.latencies {
+            Some(latencies) => Some(nearest_rank(latencies, percentile)),
+            None => self
+                .percentiles
+                .iter()
+                .find(|(p, _)| (p - percentile).abs() < f64::EPSILON)
+                .map(|(_, duration)| *duration),
+        }
+    }
+
+    /// returns the duration of each request in microseconds, in ascending order.
+    ///
+    /// this is `None` unless the run was read from a request log.
+    pub fn latencies(&self) -> Option<&[u64]> {
+        self.latencies.as_deref()
+    }
+}
+
+/// returns the value at the given percentile of a non-empty, sorted slice of microseconds.
+fn nearest_rank(latencies: &[u64], percentile: f64) -> Duration {
+    let rank = (percentile / 100.0 * latencies.len() as f64).ceil() as usize;
+    let index = rank.clamp(1, latencies.len()) - 1;
+    Duration::from_micros(latencies[index])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_can_be_read_from_summaries() -> Result<(), Error> {
+        let run = r#"{
+            "total": 4, "successes": 3, "rps": 2.0,
+            "duration": { "percentiles": { "p50_us": 10, "p99.9_us": 40 } }
+        }"#
+        .parse::<Run>()?;
+        assert_eq!(run.total(), 4);
+        assert_eq!(run.success_rate(), 75.0);
+        assert_eq!(run.rps(), 2.0);
+        assert_eq!(run.percentile(99.9), Some(Duration::from_micros(40)));
+        assert_eq!(run.percentile(90.0), None);
+        assert_eq!(run.latencies(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn runs_can_be_read_from_request_logs() -> Result<(), Error> {
+        let line = |start, duration, success| {
+            format!(
+                r#"{{"start_us":{start},"end_us":{},"duration_us":{duration},"success":{success}}}"#,
+                start + duration
+            )
+        };
+        let log = [
+            line(0, 30, true),
+            line(500_000, 10, true),
+            line(999_980, 20, false),
+        ]
+        .join("\n");
+
+        let run = log.parse::<Run>()?;
+        assert_eq!(run.total(), 3);
+        assert_eq!(run.rps(), 3.0);
+        assert_eq!(run.latencies(), Some([10, 20, 30].as_slice()));
+        assert_eq!(run.percentile(50.0), Some(Duration::from_micros(20)));
+        assert_eq!(run.percentile(99.0), Some(Duration::from_micros(30)));
+        Ok(())
+    }
+
+    #[test]
+    fn empty_request_logs_are_rejected() {
+        "\n".parse::<Run>().unwrap_err();
+    }
+
+    #[test]
+    fn malformed_runs_report_both_errors() {
+        let error = r#"{ "total": 4, "successes": 3 }"#.parse::<Run>().unwrap_err().to_string();
+        assert!(error.contains("missing field `rps`"), "{error}");
+        assert!(
+            error.contains("line 1: missing field `start_us`"),
+            "{error}"
+        );
+    }
+}
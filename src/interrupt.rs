@@ -0,0 +1,108 @@
+//! graceful handling of interrupts, such as ctrl-c.
+//!
+//! see [`Interrupt`].
+
+use {
+    crate::syndicate::Stop,
+    std::{
+        io,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::Duration,
+    },
+    tokio::task::JoinHandle,
+    tracing::{info, warn},
+};
+
+/// listens for interrupts while a load-test is running.
+///
+/// the first `SIGINT` or `SIGTERM` stops the [`Syndicate`][crate::Syndicate] from spawning new
+/// workers. workers that are already in flight may finish within a grace period, and the rest
+/// are cancelled, so that a partial summary of the load-test can still be reported. a second
+/// interrupt cancels in-flight workers immediately.
+///
+/// the listener is stopped when this is dropped.
+pub struct Interrupt {
+    /// the task listening for interrupts.
+    listener: JoinHandle<()>,
+    /// true if an interrupt was received.
+    interrupted: Arc<AtomicBool>,
+}
+
+// === impl Interrupt ===
+
+impl Interrupt {
+    /// the exit code that a process should use if its load-test was interrupted.
+    pub const EXIT_CODE: u8 = 130;
+
+    /// the grace period used when the load-test was not given one, see `--grace`.
+    pub const DEFAULT_GRACE: Duration = Duration::from_secs(5);
+
+    /// listens for interrupts, stopping the syndicate when one is received.
+    ///
+    /// workers still in flight after `grace` has elapsed are cancelled.
+    ///
+    /// # panics
+    ///
+    /// this function panics if called outside of a tokio runtime.
+    pub fn listen(stop: Stop, grace: Duration) -> Self {
+        let interrupted = Arc::new(AtomicBool::new(false));
+        let listener = {
+            let interrupted = Arc::clone(&interrupted);
+            tokio::spawn(async move {
+                if let Err(err) = Self::signal().await {
+                    warn!(%err, "could not listen for interrupts");
+                    return;
+                }
+                interrupted.store(true, Ordering::Relaxed);
+                warn!(
+                    ?grace,
+                    "interrupted, stopping load-test. interrupt again to cancel in-flight requests"
+                );
+                stop.stop_within(grace);
+
+                if Self::signal().await.is_ok() {
+                    warn!("interrupted again, cancelling in-flight requests");
+                    stop.cancel();
+                }
+            })
+        };
+
+        Self {
+            listener,
+            interrupted,
+        }
+    }
+
+    /// returns true if an interrupt was received.
+    pub fn interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::Relaxed)
+    }
+
+    /// waits for the next `SIGINT` or `SIGTERM`.
+    async fn signal() -> io::Result<()> {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            let mut terminate = signal(SignalKind::terminate())?;
+            tokio::select! {
+                res = tokio::signal::ctrl_c() => res?,
+                _ = terminate.recv() => {},
+            }
+        }
+        #[cfg(not(unix))]
+        tokio::signal::ctrl_c().await?;
+
+        info!("received interrupt");
+        Ok(())
+    }
+}
+
+impl Drop for Interrupt {
+    /// the listener should stop once the load-test has finished.
+    fn drop(&mut self) {
+        self.listener.abort();
+    }
+}
@@ -36,55 +36,245 @@ pub use self::{
 
 pub mod charts;
 pub mod cli;
+pub mod compare;
+pub mod interrupt;
+pub mod live;
+pub mod metrics;
+pub mod output;
 pub mod summary;
 pub mod syndicate;
+pub mod threshold;
+pub mod tls;
 pub mod worker;
 
 /// runs a load-test against an http/2 server.
 ///
-/// using the provided [`Cli`][cli::Cli] command-line options, this will spawn a group of workers
-/// that each connect to the server, send it a request, and read the response. this returns a
-/// [`Summary`][summary::Summary] containing information about the observed success rate, latency,
-/// and average number of in-flight requests.
+/// using the provided [`Cli`][cli::Cli] command-line options, this sends requests to the server
+/// with a [`Syndicate`] of workers, see [`syndicate`], and returns a [`Summary`] of the results,
+/// see [`summary`]. results may also be written to files, see [`output`], or observed while the
+/// load-test is running, see [`live`] and [`metrics`]. if the load-test is interrupted, a partial
+/// summary is returned, see [`interrupt`].
 ///
 /// a [`Cli`][cli::Cli] may be obtained by [`cli::parse()`], parsing the arguments given to the
 /// current process via [`std::env::args_os()`]. or, use [`cli::try_parse_from`] to parse
 /// arguments from an [`Iterator`].
 ///
 /// see [`charts`] for facilities related to printing graphs of the generated data.
-pub async fn run_load_test(
-    cli::Cli {
-        server: cli::Server { host, port },
+pub async fn run_load_test(cli: cli::Cli) -> Result<Summary> {
+    use {
+        futures::{future, StreamExt, TryStreamExt},
+        http::uri::Scheme,
+        tap::{Pipe, Tap},
+        tracing::info,
+    };
+
+    // describe how the load-test was run, so that it may be included in a report.
+    let config = cli.describe();
+    let cli::Cli {
+        server:
+            cli::Server {
+                scheme,
+                host,
+                port,
+                path,
+            },
         requests_total,
         requests_per_second,
+        connections,
+        concurrency,
+        think_time,
+        duration,
+        grace,
+        stages,
+        profile,
+        backpressure,
+        max_in_flight,
+        thresholds: _,
+        request:
+            cli::Request {
+                method,
+                headers,
+                data,
+                data_file,
+            },
+        tls,
+        timeouts:
+            cli::Timeouts {
+                connect_timeout,
+                handshake_timeout,
+                request_timeout,
+                total_timeout,
+            },
+        validation:
+            cli::Validation {
+                expect_statuses,
+                expect_headers,
+                expect_body,
+                expect_body_regex,
+                expect_json,
+                max_body_size,
+                expect_sha256,
+            },
+        output:
+            cli::Output {
+                format,
+                output_path,
+                log_requests,
+                report,
+            },
+        precision,
         show_charts: _,
+        live,
+        metrics_listen,
         trace: _,
-    }: cli::Cli,
-) -> Result<Summary> {
-    use {futures::TryStreamExt, tap::Tap, tracing::info};
+    } = cli;
+
+    // connect to the server over tls, if it was given an `https://` address.
+    let tls = (scheme == Scheme::HTTPS)
+        .then(|| tls::Connector::new(&tls))
+        .transpose()?;
+
+    // bound how long each phase of a worker may take.
+    let timeouts = worker::Timeouts {
+        connect: connect_timeout,
+        handshake: handshake_timeout,
+        request: request_timeout,
+        total: total_timeout,
+    };
+
+    // describe the request that each worker will send.
+    let body = match (data, data_file) {
+        (Some(data), _) => data.into(),
+        (None, Some(path)) => std::fs::read(path)?.into(),
+        (None, None) => Default::default(),
+    };
+    let headers = headers
+        .into_iter()
+        .map(|cli::Header { name, value }| (name, value))
+        .collect();
+    let body_rules = expect_body
+        .map(|body| worker::BodyRule::Exact(body.into()))
+        .into_iter()
+        .chain(expect_body_regex.map(worker::BodyRule::Regex))
+        .chain(expect_json.into_iter().map(worker::BodyRule::Json))
+        .collect();
+    let validation = worker::Validation {
+        statuses: expect_statuses,
+        headers: expect_headers,
+        body: body_rules,
+        max_body_size,
+        sha256: expect_sha256,
+    };
+    let request = worker::Template::new(method, path)
+        .headers(headers)
+        .body(body)
+        .validation(validation);
+
+    // follow a load profile, if one was given.
+    let profile = match (stages.is_empty(), profile) {
+        (false, _) => Some(syndicate::Profile::new(stages)),
+        (true, Some(path)) => syndicate::Profile::read(&path)?.pipe(Some),
+        (true, None) => None,
+    };
+
+    // a load-test stops once it reaches its total, or its duration, or its profile finishes.
+    let goal = live::Goal {
+        total: requests_total,
+        duration: duration
+            .into_iter()
+            .chain(profile.as_ref().map(syndicate::Profile::duration))
+            .min(),
+    };
 
     // prepare a stream of workers.
     let workers = Syndicate::builder(host, port)
         .total(requests_total)
-        .rps(requests_per_second);
+        .rps(requests_per_second)
+        .connections(connections)
+        .concurrency(concurrency)
+        .think_time(think_time)
+        .duration(duration)
+        .grace(grace)
+        .profile(profile)
+        .backpressure(backpressure)
+        .max_in_flight(max_in_flight)
+        .tls(tls)
+        .timeouts(timeouts)
+        .request(request);
+
+    // log each request as it completes, if a path was given.
+    let epoch = std::time::Instant::now();
+    let mut log = log_requests
+        .map(|path| output::RequestLog::create(&path, epoch))
+        .transpose()?;
+
+    // bind the metrics listener before starting, so that an unusable address fails early.
+    let listener = match metrics_listen {
+        Some(addr) => tokio::net::TcpListener::bind(addr).await?.pipe(Some),
+        None => None,
+    };
 
     // start the load test, and poll the tasks to completion.
-    let summary: Summary = workers
-        .tap(|_| info!("starting load-test"))
-        .start()?
-        .try_collect()
+    let workers = workers.tap(|_| info!("starting load-test")).start()?;
+    let backlog = workers.backlog();
+    let interrupt = interrupt::Interrupt::listen(
+        workers.stopper(),
+        grace.unwrap_or(interrupt::Interrupt::DEFAULT_GRACE),
+    );
+    let dashboard = live.then(|| live::Dashboard::start(goal, backlog.clone()));
+    let exporter = listener.map(|listener| metrics::Exporter::serve(listener, backlog.clone()));
+    let mut summary: Summary = workers
+        .map(Ok)
+        .try_fold(
+            Summary::with_precision(precision)?,
+            |mut summary, report| {
+                if let Some(dashboard) = &dashboard {
+                    dashboard.record(&report);
+                }
+                if let Some(exporter) = &exporter {
+                    exporter.record(&report);
+                }
+                let logged = log.as_mut().map(|log| log.write(&report)).transpose();
+                summary.extend([report]);
+                future::ready(logged.map(|_| summary))
+            },
+        )
         .tap(|_| info!("collecting worker results"))
-        .await
-        .tap(|_| info!("load-test completed"))?;
+        .await?
+        .tap(|_| info!("load-test completed"));
+    summary.record_backlog(&backlog);
+    if interrupt.interrupted() {
+        tracing::warn!("load-test was interrupted, results are partial");
+        summary.record_interrupt();
+    }
+    log.map(output::RequestLog::finish).transpose()?;
+    if let Some(dashboard) = dashboard {
+        dashboard.finish().await;
+    }
 
     // log some information about the results of the load test.
     tracing::warn!(
         success.rate = %summary.success_rate(),
+        failures = %summary.failure_count(),
         duration.median_us = %summary.median_duration().as_micros(),
+        duration.p99_us = %summary.percentile(99.0).as_micros(),
+        response_time.p99_us = %summary.response_time_percentile(99.0).as_micros(),
         in_flight.avg = %summary.average_in_flight(),
+        dispatches.skipped = %summary.skipped(),
+        dispatches.delayed = %summary.delayed(),
         "finished running load test"
     );
 
+    // write the summary in a machine-readable format, if one was given.
+    if let Some(format) = format {
+        output::write_summary(&summary, format, output_path.as_deref())?;
+    }
+
+    // write a self-contained html report, if a path was given.
+    if let Some(path) = report {
+        output::write_report(&summary, &config, &path)?;
+    }
+
     Ok(summary)
 }
 
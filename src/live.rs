@@ -0,0 +1,455 @@
+//! a live dashboard, rendered while a load-test is running.
+//!
+//! see [`Dashboard`].
+
+use {
+    crate::{
+        syndicate::Backlog,
+        worker::{Failure, Report},
+    },
+    hdrhistogram::Histogram,
+    std::{
+        collections::{BTreeMap, VecDeque},
+        io::{IsTerminal, Write},
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    },
+    tokio::{task::JoinHandle, time::MissedTickBehavior},
+    tokio_util::sync::CancellationToken,
+};
+
+/// a live dashboard, showing the progress of a load-test while it is running.
+///
+/// reports are recorded as they are yielded by the [`Syndicate`][crate::Syndicate]. if stdout is
+/// a terminal, the dashboard is redrawn in place a few times per second. otherwise, a line is
+/// logged to stderr periodically, so that it is not mixed into a summary written to stdout.
+pub struct Dashboard {
+    state: Arc<Mutex<State>>,
+    stop: CancellationToken,
+    task: JoinHandle<()>,
+}
+
+/// how far a load-test is expected to run, used to render its progress.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Goal {
+    /// the total number of requests to send.
+    pub total: Option<u32>,
+    /// how long to send requests for.
+    pub duration: Option<Duration>,
+}
+
+/// the state of a [`Dashboard`].
+struct State {
+    goal: Goal,
+    started: Instant,
+    backlog: Backlog,
+    completed: u64,
+    successes: u64,
+    /// the number of workers that failed, by the kind of failure.
+    failures: BTreeMap<Failure, u64>,
+    /// the number of unsuccessful responses, by status code.
+    statuses: BTreeMap<u16, u64>,
+    window: Window,
+}
+
+/// a rolling window of worker durations.
+///
+/// durations are recorded in buckets, by when the worker finished. old buckets are discarded.
+struct Window {
+    started: Instant,
+    /// histograms of worker durations in microseconds, by bucket index.
+    buckets: VecDeque<(u64, Histogram<u64>)>,
+}
+
+// === impl Dashboard ===
+
+impl Dashboard {
+    /// how often the dashboard is redrawn, if stdout is a terminal.
+    const REFRESH: Duration = Duration::from_millis(250);
+
+    /// how often a line is logged, if stdout is not a terminal.
+    const LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+    /// starts rendering a dashboard.
+    ///
+    /// the backlog is used to count the number of workers in flight.
+    pub fn start(goal: Goal, backlog: Backlog) -> Self {
+        let state = Arc::new(Mutex::new(State::new(goal, backlog)));
+        let stop = CancellationToken::new();
+        let task = tokio::spawn(Self::render(state.clone(), stop.clone()));
+        Self { state, stop, task }
+    }
+
+    /// records a report.
+    pub fn record(&self, report: &Report) {
+        self.state
+            .lock()
+            .expect("dashboard state should not be poisoned")
+            .record(report, Instant::now());
+    }
+
+    /// stops rendering the dashboard, after rendering it one final time.
+    pub async fn finish(self) {
+        self.stop.cancel();
+        if let Err(error) = self.task.await {
+            tracing::warn!(?error, "live dashboard failed");
+        }
+    }
+
+    /// renders the dashboard until it is stopped.
+    async fn render(state: Arc<Mutex<State>>, stop: CancellationToken) {
+        let tty = std::io::stdout().is_terminal();
+        let period = if tty {
+            Self::REFRESH
+        } else {
+            Self::LOG_INTERVAL
+        };
+        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        let mut drawn = 0; // how many lines were drawn in the last frame?
+        loop {
+            let finished = tokio::select! {
+                _ = interval.tick() => false,
+                _ = stop.cancelled() => true,
+            };
+
+            let now = Instant::now();
+            let state = state
+                .lock()
+                .expect("dashboard state should not be poisoned");
+            // NB: errors writing to stdout or stderr are ignored, the dashboard is best-effort.
+            if tty {
+                let lines = state.lines(now);
+                Self::redraw(&lines, drawn).ok();
+                drawn = lines.len();
+            } else {
+                writeln!(std::io::stderr(), "{}", state.log_line(now)).ok();
+            }
+
+            if finished {
+                break;
+            }
+        }
+    }
+
+    /// draws the given lines, replacing the previous frame.
+    fn redraw(lines: &[String], drawn: usize) -> std::io::Result<()> {
+        let mut stdout = std::io::stdout().lock();
+        if drawn > 0 {
+            write!(stdout, "\x1b[{drawn}A")?; // move the cursor up to the previous frame.
+        }
+        for line in lines {
+            write!(stdout, "\r\x1b[2K{line}\n")?; // clear each line, and then draw it.
+        }
+        stdout.flush()
+    }
+}
+
+// === impl State ===
+
+impl State {
+    /// the width of the progress bar, in characters.
+    const BAR_WIDTH: usize = 40;
+
+    /// the percentiles shown on the dashboard.
+    const PERCENTILES: [f64; 3] = [50.0, 90.0, 99.0];
+
+    /// returns the state of a dashboard that started now.
+    fn new(goal: Goal, backlog: Backlog) -> Self {
+        let started = Instant::now();
+        Self {
+            goal,
+            started,
+            backlog,
+            completed: 0,
+            successes: 0,
+            failures: BTreeMap::new(),
+            statuses: BTreeMap::new(),
+            window: Window::new(started),
+        }
+    }
+
+    /// records a report that was received at the given time.
+    fn record(&mut self, report: &Report, now: Instant) {
+        self.completed += 1;
+        if report.success {
+            self.successes += 1;
+        } else if let Some(failure) = report.failure {
+            *self.failures.entry(failure).or_default() += 1;
+        } else if let Some(status) = report.status {
+            *self.statuses.entry(status.as_u16()).or_default() += 1;
+        }
+        self.window.record(now, report.duration);
+    }
+
+    /// returns the fraction of the load-test that has finished, if its goal is known.
+    fn progress(&self, now: Instant) -> Option<f64> {
+        let Goal { total, duration } = self.goal;
+        let by_total = total.map(|total| self.completed as f64 / f64::from(total).max(1.0));
+        let by_duration = duration.map(|duration| {
+            now.duration_since(self.started).as_secs_f64()
+                / duration.as_secs_f64().max(f64::EPSILON)
+        });
+        by_total
+            .into_iter()
+            .chain(by_duration)
+            .reduce(f64::max)
+            .map(|progress| progress.clamp(0.0, 1.0))
+    }
+
+    /// returns the number of workers in flight.
+    fn in_flight(&self) -> u64 {
+        self.backlog.dispatched().saturating_sub(self.completed)
+    }
+
+    /// returns the success rate, as a percentage.
+    fn success_rate(&self) -> f64 {
+        match self.completed {
+            0 => 0.0,
+            completed => self.successes as f64 / completed as f64 * 100.0,
+        }
+    }
+
+    /// returns the number of errors, i.e. failures and unsuccessful responses.
+    fn error_count(&self) -> u64 {
+        self.failures.values().chain(self.statuses.values()).sum()
+    }
+
+    /// returns the lines of the dashboard.
+    fn lines(&self, now: Instant) -> Vec<String> {
+        let elapsed = now.duration_since(self.started).as_secs_f64();
+        let requests = match self.goal.total {
+            Some(total) => format!("{}/{total} requests", self.completed),
+            None => format!("{} requests", self.completed),
+        };
+        let progress = match self.progress(now) {
+            Some(progress) => {
+                let filled = (progress * Self::BAR_WIDTH as f64).round() as usize;
+                let bar = "█".repeat(filled) + &"░".repeat(Self::BAR_WIDTH - filled);
+                format!(
+                    "▕{bar}▏ {:>3.0}% · {requests} · {elapsed:.1}s",
+                    progress * 100.0
+                )
+            }
+            None => format!("{requests} · {elapsed:.1}s"),
+        };
+
+        let rates = format!(
+            "rps {:.1} · in flight {} · success {:.2}%",
+            self.window.rate(now),
+            self.in_flight(),
+            self.success_rate()
+        );
+
+        let latency = Self::PERCENTILES
+            .iter()
+            .map(|p| match self.window.percentile(now, *p) {
+                Some(duration) => format!("p{p} {}µs", duration.as_micros()),
+                None => format!("p{p} -"),
+            })
+            .collect::<Vec<_>>()
+            .join(" · ");
+        let latency = format!("latency (last {}s) {latency}", Window::LENGTH.as_secs());
+
+        let errors = self
+            .failures
+            .iter()
+            .map(|(failure, count)| format!("{failure} ×{count}"))
+            .chain(
+                self.statuses
+                    .iter()
+                    .map(|(status, count)| format!("status {status} ×{count}")),
+            )
+            .collect::<Vec<_>>();
+        let errors = match errors.is_empty() {
+            true => "errors: none".to_owned(),
+            false => format!("errors: {}", errors.join(" · ")),
+        };
+
+        vec![progress, rates, latency, errors]
+    }
+
+    /// returns a line summarizing the dashboard, for when stdout is not a terminal.
+    fn log_line(&self, now: Instant) -> String {
+        let elapsed = now.duration_since(self.started).as_secs_f64();
+        let progress = match self.progress(now) {
+            Some(progress) => format!(" ({:.0}%)", progress * 100.0),
+            None => String::new(),
+        };
+        let [p50, _, p99] =
+            Self::PERCENTILES.map(|p| self.window.percentile(now, p).unwrap_or_default());
+        format!(
+            "[{elapsed:.1}s] {} requests{progress}, {:.1} rps, {} in flight, {:.2}% success, \
+             p50 {}µs, p99 {}µs, {} errors",
+            self.completed,
+            self.window.rate(now),
+            self.in_flight(),
+            self.success_rate(),
+            p50.as_micros(),
+            p99.as_micros(),
+            self.error_count(),
+        )
+    }
+}
+
+// === impl Window ===
+
+impl Window {
+    /// the duration of each bucket.
+    const BUCKET: Duration = Duration::from_secs(1);
+
+    /// the number of buckets in the window.
+    const BUCKETS: u64 = 10;
+
+    /// the length of the window.
+    const LENGTH: Duration = Duration::from_secs(Self::BUCKETS);
+
+    /// returns an empty window, with buckets aligned to the given start time.
+    fn new(started: Instant) -> Self {
+        Self {
+            started,
+            buckets: VecDeque::new(),
+        }
+    }
+
+    /// returns the index of the bucket containing the given time.
+    fn index(&self, when: Instant) -> u64 {
+        (when.duration_since(self.started).as_nanos() / Self::BUCKET.as_nanos()) as u64
+    }
+
+    /// returns the index of the oldest bucket within the window, at the given time.
+    fn oldest(&self, now: Instant) -> u64 {
+        (self.index(now) + 1).saturating_sub(Self::BUCKETS)
+    }
+
+    /// records a worker duration, finishing at the given time.
+    fn record(&mut self, now: Instant, duration: Duration) {
+        let index = self.index(now);
+        if self.buckets.back().map(|(i, _)| *i) != Some(index) {
+            let histogram = Histogram::new(3).expect("precision is valid");
+            self.buckets.push_back((index, histogram));
+        }
+
+        let (_, histogram) = self.buckets.back_mut().expect("a bucket was pushed");
+        histogram
+            .record(duration.as_micros().try_into().unwrap_or(u64::MAX))
+            .expect("histogram should resize to fit values");
+
+        // discard buckets that have fallen out of the window.
+        let oldest = self.oldest(now);
+        while self.buckets.front().is_some_and(|(i, _)| *i < oldest) {
+            self.buckets.pop_front();
+        }
+    }
+
+    /// returns the histograms within the window, at the given time.
+    fn histograms(&self, now: Instant) -> impl Iterator<Item = &Histogram<u64>> + '_ {
+        let oldest = self.oldest(now);
+        self.buckets
+            .iter()
+            .filter(move |(i, _)| *i >= oldest)
+            .map(|(_, histogram)| histogram)
+    }
+
+    /// returns the number of workers that finished per second, within the window.
+    fn rate(&self, now: Instant) -> f64 {
+        let count = self.histograms(now).map(Histogram::len).sum::<u64>();
+        let since = self.started + Self::BUCKET * self.oldest(now) as u32;
+        match now.duration_since(since).as_secs_f64() {
+            secs if secs > 0.0 => count as f64 / secs,
+            _ => 0.0,
+        }
+    }
+
+    /// returns the worker duration at the given percentile, within the window.
+    fn percentile(&self, now: Instant, percentile: f64) -> Option<Duration> {
+        let mut histograms = self.histograms(now);
+        let mut merged = histograms.next()?.clone();
+        for histogram in histograms {
+            merged
+                .add(histogram)
+                .expect("histograms should resize to fit values");
+        }
+        Some(Duration::from_micros(
+            merged.value_at_percentile(percentile),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, http::StatusCode};
+
+    #[test]
+    fn window_discards_old_durations() {
+        let started = Instant::now();
+        let at = |secs: u64| started + Duration::from_millis(secs * 1000 + 500);
+        let mut window = Window::new(started);
+        window.record(at(0), Duration::from_millis(100));
+        window.record(at(5), Duration::from_millis(10));
+        window.record(at(5), Duration::from_millis(10));
+
+        assert_eq!(window.rate(at(5)), 3.0 / 5.5);
+        assert_eq!(
+            window.percentile(at(5), 100.0).map(|d| d.as_millis()),
+            Some(100)
+        );
+
+        // after ten seconds, the first duration falls out of the window.
+        assert_eq!(
+            window.percentile(at(10), 100.0).map(|d| d.as_millis()),
+            Some(10)
+        );
+        assert_eq!(window.rate(at(10)), 2.0 / 9.5);
+        assert_eq!(window.percentile(at(20), 50.0), None);
+        assert_eq!(window.rate(at(20)), 0.0);
+    }
+
+    #[test]
+    fn dashboard_shows_progress_and_errors() {
+        let backlog = Backlog::default();
+        let goal = Goal {
+            total: Some(4),
+            duration: None,
+        };
+        let mut state = State::new(goal, backlog);
+        let now = state.started;
+        let (start, ms) = (Instant::now(), Duration::from_millis);
+        state.record(&Report::test(start, ms(10)), now);
+        state.record(
+            &Report::test(start, ms(20)).status(StatusCode::SERVICE_UNAVAILABLE),
+            now,
+        );
+        state.record(
+            &Report::test(start, ms(0)).failed(Failure::ConnectRefused),
+            now,
+        );
+
+        assert_eq!(state.progress(now), Some(0.75));
+        assert_eq!(state.error_count(), 2);
+        let lines = state.lines(now);
+        assert!(lines[0].contains("75% · 3/4 requests"), "{lines:?}");
+        assert!(lines[1].contains("success 33.33%"), "{lines:?}");
+        assert_eq!(
+            lines[3], "errors: connection refused ×1 · status 503 ×1",
+            "{lines:?}"
+        );
+        assert!(state.log_line(now).contains("3 requests (75%)"));
+    }
+
+    #[test]
+    fn progress_follows_the_furthest_goal() {
+        let goal = Goal {
+            total: Some(100),
+            duration: Some(Duration::from_secs(10)),
+        };
+        let state = State::new(goal, Backlog::default());
+        let later = state.started + Duration::from_secs(5);
+        assert_eq!(state.progress(later), Some(0.5));
+        assert_eq!(
+            State::new(Goal::default(), Backlog::default()).progress(later),
+            None
+        );
+    }
+}
@@ -2,39 +2,123 @@
 //!
 //! see [`main()`] for more information.
 
-use aquarius::{
-    cli::{self, Cli},
-    run_load_test,
+use {
+    aquarius::{
+        cli::{self, Cli, Command, Compare},
+        compare::{Comparison, Run, Verdict},
+        interrupt::Interrupt,
+        run_load_test,
+        threshold::Outcome,
+    },
+    std::process::ExitCode,
 };
 
 /// the entrypoint of `aquarius`.
 ///
-/// this function runs a load-test against a specified endpoint. see [`Cli`] for more information
-/// about accepted command-line arguments. see [`Syndicate`] for more information about how
-/// worker threads are orchestrated. see [`Summary`] for book-keeping related to load-test results.
+/// this function runs a load-test against a specified endpoint, or compares the results of two
+/// load-tests. see [`Cli`] and [`Compare`] for more information about accepted command-line
+/// arguments.
 #[tokio::main(flavor = "multi_thread", worker_threads = 64)]
-async fn main() -> aquarius::Result<()> {
+async fn main() -> aquarius::Result<ExitCode> {
     // parse the command-line arguments.
-    let cli @ Cli {
-        show_charts, trace, ..
-    } = cli::parse();
+    let command = cli::parse_command();
 
     // write logs to stderr if `--trace` was provided.
+    let trace = match &command {
+        Command::LoadTest(cli) => cli.trace,
+        Command::Compare(compare) => compare.trace,
+    };
     if trace {
         tracing_subscriber::fmt()
             .with_writer(std::io::stderr)
             .init();
     }
 
+    match command {
+        Command::LoadTest(cli) => load_test(*cli).await,
+        Command::Compare(compare) => compare_runs(compare),
+    }
+}
+
+/// runs a load-test.
+///
+/// see [`Syndicate`] for more information about how worker threads are orchestrated. see
+/// [`Summary`] for book-keeping related to load-test results.
+///
+/// if the results do not satisfy the thresholds given via `--assert`, this exits with a non-zero
+/// exit code. if the load-test was interrupted, this exits with [`Interrupt::EXIT_CODE`].
+async fn load_test(cli: Cli) -> aquarius::Result<ExitCode> {
+    let Cli { show_charts, .. } = cli;
+
     // run the load test.
+    let thresholds = cli.thresholds.clone();
     let summary = run_load_test(cli).await?;
 
     // render some charts.
     if show_charts {
         aquarius::charts::render_progress(&summary);
         aquarius::charts::render_in_flight(&summary);
+        aquarius::charts::render_throughput(&summary);
+        aquarius::charts::render_latency_over_time(&summary);
+        aquarius::charts::render_histogram(&summary);
+        aquarius::charts::render_percentile_distribution(&summary);
+        aquarius::charts::render_heatmap(&summary);
         aquarius::charts::render_table(&summary);
+        aquarius::charts::render_stages(&summary);
+        aquarius::charts::render_phases(&summary);
+    }
+
+    // check the results against any thresholds.
+    let outcomes = thresholds
+        .iter()
+        .map(|threshold| threshold.check(&summary))
+        .collect::<Vec<_>>();
+    if !outcomes.is_empty() {
+        aquarius::charts::render_thresholds(&outcomes);
+    }
+
+    if summary.interrupted() {
+        eprintln!("load-test was interrupted, results are partial");
+        return Ok(ExitCode::from(Interrupt::EXIT_CODE));
     }
 
-    Ok(())
+    match Outcome::all_passed(&outcomes) {
+        true => Ok(ExitCode::SUCCESS),
+        false => Ok(ExitCode::FAILURE),
+    }
+}
+
+/// compares the results of two load-tests.
+///
+/// if the candidate regresses beyond a budget given via `--budget`, this exits with a non-zero
+/// exit code.
+fn compare_runs(
+    Compare {
+        budgets,
+        alpha,
+        baseline,
+        candidate,
+        trace: _,
+    }: Compare,
+) -> aquarius::Result<ExitCode> {
+    let baseline = Run::read(&baseline)?;
+    let candidate = Run::read(&candidate)?;
+
+    // check the candidate against any regression budgets.
+    let verdicts = budgets
+        .iter()
+        .map(|budget| budget.check(&baseline, &candidate))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // render a table comparing the two runs, and the budgets.
+    let comparison = Comparison::new(&baseline, &candidate);
+    aquarius::charts::render_comparison(&comparison, alpha);
+    if !verdicts.is_empty() {
+        aquarius::charts::render_budgets(&verdicts);
+    }
+
+    match Verdict::all_passed(&verdicts) {
+        true => Ok(ExitCode::SUCCESS),
+        false => Ok(ExitCode::FAILURE),
+    }
 }
@@ -0,0 +1,366 @@
+//! a prometheus metrics endpoint, served while a load-test is running.
+//!
+//! see [`Exporter`].
+
+use {
+    crate::{
+        syndicate::Backlog,
+        worker::{Failure, Report},
+    },
+    http::{header, Method, Request, Response, StatusCode},
+    http_body_util::Full,
+    hyper::{
+        body::{Bytes, Incoming},
+        server::conn::http1,
+        service::service_fn,
+    },
+    hyper_util::rt::TokioIo,
+    std::{
+        collections::BTreeMap,
+        convert::Infallible,
+        fmt::Write,
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
+    tokio::{net::TcpListener, task::JoinHandle},
+    tracing::{debug, warn},
+};
+
+/// serves a prometheus `/metrics` endpoint, describing a load-test as it runs.
+///
+/// metrics are updated as each [`Report`] is recorded. the endpoint is served until the exporter
+/// is dropped.
+pub struct Exporter {
+    metrics: Metrics,
+    listener: JoinHandle<()>,
+}
+
+/// metrics describing a load-test.
+///
+/// this may be cheaply cloned.
+#[derive(Clone)]
+struct Metrics {
+    backlog: Backlog,
+    registry: Arc<Mutex<Registry>>,
+}
+
+/// the metrics recorded from each [`Report`].
+#[derive(Default)]
+struct Registry {
+    /// the number of responses received, by status code.
+    completed: BTreeMap<u16, u64>,
+    /// the number of workers that failed, by the kind of failure.
+    failed: BTreeMap<Failure, u64>,
+    /// a histogram of worker durations.
+    durations: Buckets,
+    /// a histogram of response times, measured from when each worker was intended to start.
+    response_times: Buckets,
+}
+
+/// a prometheus histogram, with a fixed set of buckets.
+#[derive(Default)]
+struct Buckets {
+    /// the number of observations in each bucket, see [`Buckets::BOUNDS`].
+    ///
+    /// the last bucket counts observations beyond the largest bound.
+    counts: [u64; Buckets::BOUNDS.len() + 1],
+    /// the sum of all observations, in seconds.
+    sum: f64,
+}
+
+// === impl Exporter ===
+
+impl Drop for Exporter {
+    fn drop(&mut self) {
+        self.listener.abort();
+    }
+}
+
+impl Exporter {
+    /// serves metrics upon the given listener.
+    ///
+    /// the backlog is used to count the number of workers dispatched, and in flight.
+    pub fn serve(listener: TcpListener, backlog: Backlog) -> Self {
+        let metrics = Metrics {
+            backlog,
+            registry: Default::default(),
+        };
+        let listener = tokio::spawn(Self::accept(listener, metrics.clone()));
+        Self { metrics, listener }
+    }
+
+    /// records a report.
+    pub fn record(&self, report: &Report) {
+        self.metrics
+            .registry
+            .lock()
+            .expect("metrics registry should not be poisoned")
+            .record(report);
+    }
+
+    /// accepts connections, serving metrics to each.
+    async fn accept(listener: TcpListener, metrics: Metrics) {
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, peer)) => {
+                    debug!(%peer, "accepted metrics connection");
+                    stream
+                }
+                Err(error) => {
+                    warn!(?error, "failed to accept metrics connection");
+                    continue;
+                }
+            };
+            let metrics = metrics.clone();
+            let service = service_fn(move |request| {
+                let response = metrics.respond(request);
+                async move { Ok::<_, Infallible>(response) }
+            });
+            tokio::spawn(async move {
+                if let Err(error) = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await
+                {
+                    debug!(?error, "metrics connection failed");
+                }
+            });
+        }
+    }
+}
+
+// === impl Metrics ===
+
+impl Metrics {
+    /// the content type of the prometheus text format.
+    const CONTENT_TYPE: &'static str = "text/plain; version=0.0.4";
+
+    /// responds to a request for metrics.
+    fn respond(&self, request: Request<Incoming>) -> Response<Full<Bytes>> {
+        let (status, body) = match (request.method(), request.uri().path()) {
+            (&Method::GET, "/metrics") => (StatusCode::OK, self.render()),
+            _ => (StatusCode::NOT_FOUND, "not found\n".to_owned()),
+        };
+        let mut response = Response::new(Full::new(Bytes::from(body)));
+        *response.status_mut() = status;
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static(Self::CONTENT_TYPE),
+        );
+        response
+    }
+
+    /// renders the metrics in the prometheus text format.
+    fn render(&self) -> String {
+        let registry = self
+            .registry
+            .lock()
+            .expect("metrics registry should not be poisoned");
+        registry.render(&self.backlog)
+    }
+}
+
+// === impl Registry ===
+
+impl Registry {
+    /// records a report.
+    fn record(&mut self, report: &Report) {
+        match (report.status, report.failure) {
+            (_, Some(failure)) => *self.failed.entry(failure).or_default() += 1,
+            (Some(status), None) => *self.completed.entry(status.as_u16()).or_default() += 1,
+            (None, None) => *self.failed.entry(Failure::Request).or_default() += 1,
+        }
+        self.durations.observe(report.duration);
+        self.response_times
+            .observe(report.end.saturating_duration_since(report.intended));
+    }
+
+    /// renders the metrics in the prometheus text format.
+    fn render(&self, backlog: &Backlog) -> String {
+        let mut out = String::new();
+        let finished = self
+            .completed
+            .values()
+            .chain(self.failed.values())
+            .sum::<u64>();
+
+        // NB: writing to a string cannot fail.
+        let mut metric = |name: &str, help: &str, kind: &str, samples: Vec<(String, u64)>| {
+            writeln!(out, "# HELP {name} {help}").ok();
+            writeln!(out, "# TYPE {name} {kind}").ok();
+            for (labels, value) in samples {
+                writeln!(out, "{name}{labels} {value}").ok();
+            }
+        };
+        metric(
+            "aquarius_requests_dispatched_total",
+            "the number of requests dispatched.",
+            "counter",
+            vec![(String::new(), backlog.dispatched())],
+        );
+        metric(
+            "aquarius_requests_completed_total",
+            "the number of responses received, by status code.",
+            "counter",
+            self.completed
+                .iter()
+                .map(|(status, count)| (format!("{{status=\"{status}\"}}"), *count))
+                .collect(),
+        );
+        metric(
+            "aquarius_requests_failed_total",
+            "the number of requests that failed without a response, by the kind of error.",
+            "counter",
+            self.failed
+                .iter()
+                .map(|(failure, count)| {
+                    let error = escape(&failure.to_string());
+                    (format!("{{error=\"{error}\"}}"), *count)
+                })
+                .collect(),
+        );
+        metric(
+            "aquarius_requests_skipped_total",
+            "the number of requests skipped due to backpressure.",
+            "counter",
+            vec![(String::new(), backlog.skipped())],
+        );
+        metric(
+            "aquarius_requests_delayed_total",
+            "the number of requests delayed due to backpressure.",
+            "counter",
+            vec![(String::new(), backlog.delayed())],
+        );
+        metric(
+            "aquarius_requests_in_flight",
+            "the number of requests in flight.",
+            "gauge",
+            vec![(String::new(), backlog.dispatched().saturating_sub(finished))],
+        );
+
+        self.durations.render(
+            &mut out,
+            "aquarius_request_duration_seconds",
+            "the duration of each request, measured from when it was sent.",
+        );
+        self.response_times.render(
+            &mut out,
+            "aquarius_response_time_seconds",
+            "the response time of each request, measured from when it was intended to be sent.",
+        );
+        out
+    }
+}
+
+// === impl Buckets ===
+
+impl Buckets {
+    /// the upper bounds of each bucket, in seconds.
+    const BOUNDS: [f64; 14] = [
+        0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+    ];
+
+    /// records an observation.
+    fn observe(&mut self, duration: Duration) {
+        let secs = duration.as_secs_f64();
+        let index = Self::BOUNDS.partition_point(|bound| *bound < secs);
+        self.counts[index] += 1;
+        self.sum += secs;
+    }
+
+    /// renders this histogram in the prometheus text format.
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        writeln!(out, "# HELP {name} {help}").ok();
+        writeln!(out, "# TYPE {name} histogram").ok();
+        let mut cumulative = 0;
+        for (bound, count) in Self::BOUNDS.iter().zip(&self.counts) {
+            cumulative += count;
+            writeln!(out, "{name}_bucket{{le=\"{bound}\"}} {cumulative}").ok();
+        }
+        let count = self.counts.iter().sum::<u64>();
+        writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {count}").ok();
+        writeln!(out, "{name}_sum {}", self.sum).ok();
+        writeln!(out, "{name}_count {count}").ok();
+    }
+}
+
+/// escapes a prometheus label value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, h2::Reason, std::time::Instant};
+
+    #[test]
+    fn metrics_are_rendered() {
+        let mut registry = Registry::default();
+        let (start, ms) = (Instant::now(), Duration::from_millis);
+        registry.record(&Report::test(start, ms(3)));
+        registry.record(&Report::test(start, ms(30)));
+        registry.record(&Report::test(start, ms(3)).status(StatusCode::SERVICE_UNAVAILABLE));
+        registry.record(&Report::test(start, ms(0)).failed(Failure::Reset(Reason::CANCEL)));
+
+        let out = registry.render(&Backlog::default());
+        for line in [
+            "aquarius_requests_dispatched_total 0",
+            "aquarius_requests_completed_total{status=\"200\"} 2",
+            "aquarius_requests_completed_total{status=\"503\"} 1",
+            "aquarius_requests_failed_total{error=\"stream reset (CANCEL)\"} 1",
+            "aquarius_requests_in_flight 0",
+            "aquarius_request_duration_seconds_bucket{le=\"0.0005\"} 1",
+            "aquarius_request_duration_seconds_bucket{le=\"0.005\"} 3",
+            "aquarius_request_duration_seconds_bucket{le=\"0.05\"} 4",
+            "aquarius_request_duration_seconds_bucket{le=\"+Inf\"} 4",
+            "aquarius_request_duration_seconds_count 4",
+            "aquarius_response_time_seconds_count 4",
+        ] {
+            assert!(
+                out.lines().any(|l| l == line),
+                "missing `{line}` in:\n{out}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn metrics_are_served() -> Result<(), crate::Error> {
+        use {http_body_util::BodyExt, hyper::client::conn::http1};
+
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let exporter = Exporter::serve(listener, Backlog::default());
+        exporter.record(&Report::test(Instant::now(), Duration::from_millis(3)));
+
+        // scrape the endpoint.
+        let stream = tokio::net::TcpStream::connect(addr).await?;
+        let (mut client, conn) = http1::handshake(TokioIo::new(stream)).await?;
+        tokio::spawn(conn);
+        let get = |path: &str| {
+            Request::get(path)
+                .header(header::HOST, addr.to_string())
+                .body(http_body_util::Empty::<Bytes>::new())
+        };
+
+        let response = client.send_request(get("/metrics")?).await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            Metrics::CONTENT_TYPE
+        );
+        let body = response.into_body().collect().await?.to_bytes();
+        let body = std::str::from_utf8(&body)?;
+        assert!(body.contains("aquarius_requests_completed_total{status=\"200\"} 1"));
+
+        let response = client.send_request(get("/")?).await?;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        Ok(())
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        assert_eq!(escape("a \"b\"\\c\n"), "a \\\"b\\\"\\\\c\\n");
+    }
+}
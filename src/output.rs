@@ -0,0 +1,183 @@
+//! machine-readable output.
+//!
+//! a [`Summary`] may be written as json or csv, see [`write_summary()`]. each [`Report`] may also
+//! be logged as it completes, see [`RequestLog`]. a self-contained html report may also be
+//! written, see [`write_report()`].
+
+pub use self::{
+    html::{write_report, write_report_to},
+    log::RequestLog,
+};
+
+use {
+    crate::{
+        error::Error,
+        summary::{Interval, Summary},
+        worker::{Phase, Report},
+    },
+    serde::Serialize,
+    std::{
+        collections::BTreeMap,
+        fs::File,
+        io::{BufWriter, Write},
+        path::Path,
+        time::{Duration, Instant},
+    },
+    tap::Pipe,
+};
+
+mod csv;
+mod html;
+mod log;
+mod record;
+
+/// the format in which a [`Summary`] is written.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, clap::ValueEnum)]
+pub enum Format {
+    /// a json object.
+    Json,
+    /// a csv file, with a `metric,value` pair on each line.
+    ///
+    /// metrics are named after their path within the json object, e.g.
+    /// `duration.percentiles.p99_us`.
+    Csv,
+}
+
+/// writes a summary in the given format to a file, or to stdout if no path is given.
+pub fn write_summary(summary: &Summary, format: Format, path: Option<&Path>) -> Result<(), Error> {
+    match path {
+        Some(path) => File::create(path)?
+            .pipe(BufWriter::new)
+            .pipe(|writer| write_summary_to(summary, format, writer)),
+        None => write_summary_to(summary, format, std::io::stdout().lock()),
+    }
+}
+
+/// writes a summary in the given format to a writer.
+pub fn write_summary_to<W: Write>(
+    summary: &Summary,
+    format: Format,
+    mut writer: W,
+) -> Result<(), Error> {
+    let record = record::Record::new(summary);
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut writer, &record)?;
+            writeln!(writer)?;
+        }
+        Format::Csv => {
+            serde_json::to_value(&record)?.pipe(|value| csv::write(&value, &mut writer))?
+        }
+    }
+    writer.flush().map_err(Error::from)
+}
+
+/// returns a number of microseconds, saturating if the duration is too long.
+fn micros(duration: Duration) -> u64 {
+    duration.as_micros().try_into().unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::worker::Failure};
+
+    /// returns a summary of a successful report, and a failed one.
+    fn summary() -> Summary {
+        let (start, ms) = (Instant::now(), Duration::from_millis);
+        let mut summary = Summary::default();
+        summary.extend([
+            Report::test(start, ms(10)),
+            Report::test(start, ms(20)).failed(Failure::ConnectRefused),
+        ]);
+        summary
+    }
+
+    #[test]
+    fn summary_can_be_written_as_json() -> Result<(), Error> {
+        let mut buf = Vec::new();
+        write_summary_to(&summary(), Format::Json, &mut buf)?;
+
+        let json: serde_json::Value = serde_json::from_slice(&buf)?;
+        assert_eq!(json["total"], 2);
+        assert_eq!(json["successes"], 1);
+        assert_eq!(json["failures"]["connection refused"], 1);
+        // the failed request received no response, so its duration is not a latency.
+        let max = json["duration"]["max_us"].as_u64();
+        assert!((Some(10_000)..Some(20_000)).contains(&max), "{max:?}");
+        assert!(json["duration"]["percentiles"]["p50_us"].is_u64());
+        assert!(json["time_series"]["in_flight"].is_object());
+        Ok(())
+    }
+
+    #[test]
+    fn summary_can_be_written_as_csv() -> Result<(), Error> {
+        let mut buf = Vec::new();
+        write_summary_to(&summary(), Format::Csv, &mut buf)?;
+
+        let csv = String::from_utf8(buf)?;
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("metric,value"));
+        assert!(csv.lines().any(|line| line == "total,2"));
+        assert!(csv
+            .lines()
+            .any(|line| line == "failures.connection refused,1"));
+        assert!(csv
+            .lines()
+            .any(|line| line.starts_with("duration.percentiles.p99_us,")));
+        Ok(())
+    }
+
+    #[test]
+    fn time_series_are_written_per_interval() -> Result<(), Error> {
+        let (start, ms) = (Instant::now(), Duration::from_millis);
+        let mut summary = Summary::default();
+        summary.extend([
+            Report::test(start, ms(10)),
+            Report::test(start + ms(1000), ms(10)).failed(Failure::ConnectRefused),
+        ]);
+        let mut buf = Vec::new();
+        write_summary_to(&summary, Format::Json, &mut buf)?;
+
+        let json: serde_json::Value = serde_json::from_slice(&buf)?;
+        let series = &json["time_series"];
+        let width = series["interval_us"]
+            .as_f64()
+            .expect("interval is a number");
+        let intervals = series["intervals"]
+            .as_array()
+            .expect("intervals are an array");
+        let totals = intervals.iter().filter_map(|i| i["total"].as_u64());
+        assert_eq!(totals.sum::<u64>(), 2);
+
+        let first = intervals.first().expect("an interval is written");
+        assert_eq!(first["start_ms"], 0);
+        assert_eq!(first["total"], 1);
+        assert_eq!(first["error_rate"], 0.0);
+        let p50 = first["p50_us"].as_u64();
+        assert!((Some(10_000)..Some(10_100)).contains(&p50), "{p50:?}");
+        assert_eq!(first["p99_us"], first["p50_us"]);
+        let rps = first["rps"].as_f64().expect("rps is a number");
+        assert!((rps - 1e6 / width).abs() < 0.1, "{rps} rps in {width}us");
+
+        // the last interval only contains a failure, which received no response.
+        let last = intervals.last().expect("an interval is written");
+        assert_eq!(last["total"], 1);
+        assert_eq!(last["error_rate"], 100.0);
+        assert_eq!(last["error_rps"], last["rps"]);
+        assert!(last["p50_us"].is_null());
+        assert!(last["p99_us"].is_null());
+        Ok(())
+    }
+
+    #[test]
+    fn empty_summary_can_be_written() -> Result<(), Error> {
+        let mut buf = Vec::new();
+        write_summary_to(&Summary::default(), Format::Json, &mut buf)?;
+
+        let json: serde_json::Value = serde_json::from_slice(&buf)?;
+        assert_eq!(json["total"], 0);
+        assert_eq!(json["time_series"]["in_flight"], serde_json::json!({}));
+        assert_eq!(json["time_series"]["intervals"], serde_json::json!([]));
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+//! facilities for writing a json value as csv.
+
+use {super::*, serde_json::Value};
+
+/// writes a json value as csv, with a `metric,value` pair on each line.
+///
+/// nested values are flattened, and named after their path, e.g. `duration.max_us`.
+pub(super) fn write<W: Write>(value: &Value, writer: &mut W) -> Result<(), Error> {
+    writeln!(writer, "metric,value")?;
+    let mut rows = Vec::new();
+    flatten(String::new(), value, &mut rows);
+    for (metric, value) in rows {
+        writeln!(writer, "{},{}", quote(&metric), quote(&value))?;
+    }
+    Ok(())
+}
+
+/// collects the leaves of a json value, named after their path.
+fn flatten(path: String, value: &Value, rows: &mut Vec<(String, String)>) {
+    let join = |key: &str| match path.as_str() {
+        "" => key.to_owned(),
+        path => format!("{path}.{key}"),
+    };
+
+    match value {
+        Value::Object(map) => map
+            .iter()
+            .for_each(|(key, value)| flatten(join(key), value, rows)),
+        Value::Array(values) => values
+            .iter()
+            .enumerate()
+            .for_each(|(index, value)| flatten(join(&index.to_string()), value, rows)),
+        Value::Null => rows.push((path, String::new())),
+        Value::String(s) => rows.push((path, s.to_owned())),
+        Value::Bool(_) | Value::Number(_) => rows.push((path, value.to_string())),
+    }
+}
+
+/// quotes a csv field, if it contains a delimiter, quote, or line break.
+fn quote(field: &str) -> std::borrow::Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json};
+
+    #[test]
+    fn nested_values_are_flattened() -> Result<(), Error> {
+        let value = json!({ "a": { "b": 1, "c": [true, null] }, "d": "x,\"y\"" });
+        let mut buf = Vec::new();
+        write(&value, &mut buf)?;
+        assert_eq!(
+            String::from_utf8(buf)?,
+            "metric,value\na.b,1\na.c.0,true\na.c.1,\nd,\"x,\"\"y\"\"\"\n"
+        );
+        Ok(())
+    }
+}
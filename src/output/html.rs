@@ -0,0 +1,435 @@
+//! a self-contained html report of a [`Summary`].
+//!
+//! charts are drawn as inline svg, and styles are inlined, so that the report may be archived or
+//! shared without fetching anything else.
+
+use {super::*, crate::summary::Interval, std::fmt::Write as _};
+
+/// the width of rendered charts, in pixels.
+const WIDTH: f64 = 800.0;
+
+/// the height of rendered charts, in pixels.
+const HEIGHT: f64 = 240.0;
+
+/// the space to the left of the plotted area of a chart, and around its other sides, in pixels.
+const MARGIN: (f64, f64) = (64.0, 24.0);
+
+/// the number of labelled ticks along each axis of a chart.
+const TICKS: u32 = 5;
+
+/// styles for the report.
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em auto; max-width: 860px; color: #222; }
+h1 { font-size: 1.5em; }
+h2 { font-size: 1.2em; border-bottom: 1px solid #ccc; margin-top: 2em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 0.25em 0.75em; text-align: left; }
+td.number { text-align: right; font-family: monospace; }
+svg text { font-size: 11px; fill: #555; }
+.legend span { margin-right: 1.5em; }
+";
+
+/// a line plotted upon a chart.
+struct Line {
+    /// the name of this line, shown in the legend.
+    name: &'static str,
+    /// the color of this line.
+    color: &'static str,
+    /// the points along this line.
+    points: Vec<(f64, f64)>,
+}
+
+/// writes an html report of a summary to a file.
+///
+/// `config` describes how the load-test was run, as `(name, value)` pairs, see
+/// [`Cli::describe()`][crate::cli::Cli::describe].
+pub fn write_report(
+    summary: &Summary,
+    config: &[(&str, String)],
+    path: &Path,
+) -> Result<(), Error> {
+    File::create(path)?
+        .pipe(BufWriter::new)
+        .pipe(|writer| write_report_to(summary, config, writer))
+}
+
+/// writes an html report of a summary to a writer.
+pub fn write_report_to<W: Write>(
+    summary: &Summary,
+    config: &[(&str, String)],
+    mut writer: W,
+) -> Result<(), Error> {
+    let mut html = String::new();
+    writeln!(html, "<!DOCTYPE html>")?;
+    writeln!(html, "<html lang=\"en\">")?;
+    writeln!(html, "<head>")?;
+    writeln!(html, "<meta charset=\"utf-8\">")?;
+    writeln!(html, "<title>aquarius load-test report</title>")?;
+    writeln!(html, "<style>\n{STYLE}</style>")?;
+    writeln!(html, "</head>")?;
+    writeln!(html, "<body>")?;
+    writeln!(html, "<h1>aquarius load-test report</h1>")?;
+    let generated = humantime::format_rfc3339_seconds(std::time::SystemTime::now());
+    writeln!(html, "<p>generated at {generated}.</p>")?;
+
+    summary_table(&mut html, summary)?;
+    percentile_table(&mut html, summary)?;
+    charts(&mut html, summary)?;
+    failure_table(&mut html, summary)?;
+    config_table(&mut html, config)?;
+
+    writeln!(html, "</body>")?;
+    writeln!(html, "</html>")?;
+
+    writer.write_all(html.as_bytes())?;
+    writer.flush().map_err(Error::from)
+}
+
+/// writes a table of statistics about the load-test.
+fn summary_table(html: &mut String, summary: &Summary) -> Result<(), Error> {
+    // NB: a summary with no workers has no success rate, nor time range.
+    let (success_rate, in_flight) = match summary.total() {
+        0 => ("n/a".to_owned(), "n/a".to_owned()),
+        _ => (
+            format!("{:.2}%", summary.success_rate()),
+            format!("{:.2}", summary.average_in_flight()),
+        ),
+    };
+    let rows = [
+        ("requests", summary.total().to_string()),
+        ("successes", summary.success_count().to_string()),
+        ("success rate", success_rate),
+        ("failures", summary.failure_count().to_string()),
+        (
+            "requests per second",
+            format!("{:.2}", summary.throughput()),
+        ),
+        ("duration (mean)", millis(summary.mean_duration())),
+        ("duration (stddev)", millis(summary.stddev_duration())),
+        ("in-flight (average)", in_flight),
+        ("dispatches (skipped)", summary.skipped().to_string()),
+        ("dispatches (delayed)", summary.delayed().to_string()),
+    ];
+
+    writeln!(html, "<h2>summary</h2>")?;
+    writeln!(html, "<table>")?;
+    for (name, value) in rows {
+        writeln!(
+            html,
+            "<tr><th>{name}</th><td class=\"number\">{value}</td></tr>"
+        )?;
+    }
+    writeln!(html, "</table>")?;
+    Ok(())
+}
+
+/// writes a table of worker durations and response times, at each percentile.
+fn percentile_table(html: &mut String, summary: &Summary) -> Result<(), Error> {
+    let rows = std::iter::once((
+        "min".to_owned(),
+        summary.min_duration(),
+        summary.min_response_time(),
+    ))
+    .chain(
+        summary
+            .percentiles()
+            .zip(summary.response_time_percentiles())
+            .map(|((p, duration), (_, response))| (format!("p{p}"), duration, response)),
+    )
+    .chain(std::iter::once((
+        "max".to_owned(),
+        summary.max_duration(),
+        summary.max_response_time(),
+    )));
+
+    writeln!(html, "<h2>latency</h2>")?;
+    writeln!(html, "<table>")?;
+    writeln!(
+        html,
+        "<tr><th>percentile</th><th>duration</th><th>response time</th></tr>"
+    )?;
+    for (name, duration, response) in rows {
+        writeln!(
+            html,
+            "<tr><th>{name}</th><td class=\"number\">{}</td><td class=\"number\">{}</td></tr>",
+            millis(duration),
+            millis(response),
+        )?;
+    }
+    writeln!(html, "</table>")?;
+    Ok(())
+}
+
+/// writes a table of the options that the load-test was run with.
+fn config_table(html: &mut String, config: &[(&str, String)]) -> Result<(), Error> {
+    writeln!(html, "<h2>configuration</h2>")?;
+    writeln!(html, "<table>")?;
+    for (name, value) in config {
+        writeln!(
+            html,
+            "<tr><th>{}</th><td>{}</td></tr>",
+            escape(name),
+            escape(value)
+        )?;
+    }
+    writeln!(html, "</table>")?;
+    Ok(())
+}
+
+/// writes charts of throughput, in-flight workers, and latency over time.
+fn charts(html: &mut String, summary: &Summary) -> Result<(), Error> {
+    writeln!(html, "<h2>charts</h2>")?;
+
+    // NB: a summary with no workers has no time range.
+    let Some((start, _)) = summary.time_range() else {
+        writeln!(html, "<p>no requests were sent.</p>")?;
+        return Ok(());
+    };
+
+    let series = summary.time_series(summary.default_interval());
+    let points = |y: &dyn Fn(&Interval) -> f64| {
+        series
+            .iter()
+            .map(|interval| (interval.start().as_secs_f64(), y(interval)))
+            .collect::<Vec<_>>()
+    };
+    let latency = |percentile: f64| {
+        series
+            .iter()
+            .filter(|interval| interval.total() > 0)
+            .map(|interval| {
+                let y = interval.percentile(percentile).as_secs_f64() * 1000.0;
+                (interval.start().as_secs_f64(), y)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let throughput = [
+        Line {
+            name: "completed",
+            color: "#1f77b4",
+            points: points(&Interval::rps),
+        },
+        Line {
+            name: "unsuccessful",
+            color: "#d62728",
+            points: points(&Interval::error_rps),
+        },
+    ];
+    chart(html, "requests per second", &throughput)?;
+
+    let in_flight = [Line {
+        name: "in-flight",
+        color: "#2ca02c",
+        points: summary
+            .compute_in_flight_observations()
+            .into_iter()
+            .map(|(when, count)| (when.duration_since(start).as_secs_f64(), f64::from(count)))
+            .collect(),
+    }];
+    chart(html, "requests in flight", &in_flight)?;
+
+    let latency = [
+        Line {
+            name: "p99",
+            color: "#ff7f0e",
+            points: latency(99.0),
+        },
+        Line {
+            name: "p50",
+            color: "#1f77b4",
+            points: latency(50.0),
+        },
+    ];
+    chart(html, "latency over time (ms)", &latency)?;
+
+    Ok(())
+}
+
+/// writes a table counting the workers that failed, by the kind of failure.
+fn failure_table(html: &mut String, summary: &Summary) -> Result<(), Error> {
+    writeln!(html, "<h2>errors</h2>")?;
+    if summary.failure_count() == 0 {
+        writeln!(html, "<p>no requests failed.</p>")?;
+        return Ok(());
+    }
+
+    writeln!(html, "<table>")?;
+    writeln!(
+        html,
+        "<tr><th>failure</th><th>count</th><th>share</th></tr>"
+    )?;
+    for (failure, count) in summary.failures() {
+        let share = f64::from(count) / f64::from(summary.total()) * 100.0;
+        writeln!(
+            html,
+            "<tr><th>{}</th><td class=\"number\">{count}</td><td class=\"number\">{share:.2}%</td></tr>",
+            escape(&failure.to_string()),
+        )?;
+    }
+    writeln!(html, "</table>")?;
+    Ok(())
+}
+
+/// writes a titled svg line chart, with time in seconds along the x-axis.
+fn chart(html: &mut String, title: &str, lines: &[Line]) -> Result<(), Error> {
+    let points = || lines.iter().flat_map(|line| line.points.iter());
+    let positive = |max: f64| if max > 0.0 { max } else { 1.0 };
+    let xmax = points().map(|(x, _)| *x).fold(0.0, f64::max).pipe(positive);
+    let ymax = points().map(|(_, y)| *y).fold(0.0, f64::max).pipe(positive);
+
+    // map a point onto the plotted area, with the origin in the bottom-left corner.
+    let (left, bottom) = MARGIN;
+    let (width, height) = (WIDTH - left - bottom, HEIGHT - 2.0 * bottom);
+    let x = |x: f64| left + x / xmax * width;
+    let y = |y: f64| bottom + height - y / ymax * height;
+
+    writeln!(html, "<h3>{title}</h3>")?;
+    write!(html, "<p class=\"legend\">")?;
+    for Line { name, color, .. } in lines {
+        write!(html, "<span style=\"color: {color}\">■ {name}</span>")?;
+    }
+    writeln!(html, "</p>")?;
+    writeln!(
+        html,
+        "<svg width=\"{WIDTH}\" height=\"{HEIGHT}\" viewBox=\"0 0 {WIDTH} {HEIGHT}\" role=\"img\">"
+    )?;
+
+    // draw the axes, with evenly spaced ticks.
+    writeln!(
+        html,
+        "<path d=\"M{left} {bottom} V{} H{}\" fill=\"none\" stroke=\"#999\"/>",
+        bottom + height,
+        left + width,
+    )?;
+    for tick in 0..=TICKS {
+        let fraction = f64::from(tick) / f64::from(TICKS);
+        let (tx, ty) = (fraction * xmax, fraction * ymax);
+        writeln!(
+            html,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}s</text>",
+            x(tx),
+            HEIGHT - 6.0,
+            number(tx),
+        )?;
+        writeln!(
+            html,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+            left - 6.0,
+            y(ty) + 4.0,
+            number(ty),
+        )?;
+    }
+
+    // draw each line.
+    for Line { color, points, .. } in lines {
+        let points = points
+            .iter()
+            .map(|(px, py)| format!("{:.1},{:.1}", x(*px), y(*py)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        writeln!(
+            html,
+            "<polyline points=\"{points}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"1.5\"/>"
+        )?;
+    }
+
+    writeln!(html, "</svg>")?;
+    Ok(())
+}
+
+/// returns a number, with fewer decimal places as it grows larger.
+fn number(n: f64) -> String {
+    match n {
+        n if n >= 100.0 => format!("{n:.0}"),
+        n if n >= 10.0 => format!("{n:.1}"),
+        n => format!("{n:.2}"),
+    }
+}
+
+/// returns a duration in milliseconds.
+fn millis(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// escapes text so that it may be included in html.
+fn escape(text: &str) -> String {
+    text.chars()
+        .fold(String::with_capacity(text.len()), |mut s, c| {
+            match c {
+                '&' => s.push_str("&amp;"),
+                '<' => s.push_str("&lt;"),
+                '>' => s.push_str("&gt;"),
+                '"' => s.push_str("&quot;"),
+                '\'' => s.push_str("&#39;"),
+                c => s.push(c),
+            }
+            s
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::worker::Failure};
+
+    /// returns a summary of some successful reports, and a failed one.
+    fn summary() -> Summary {
+        let (start, ms) = (Instant::now(), Duration::from_millis);
+        let mut summary = Summary::default();
+        summary.extend([
+            Report::test(start, ms(10)),
+            Report::test(start, ms(30)),
+            Report::test(start, ms(20)).failed(Failure::ConnectRefused),
+        ]);
+        summary
+    }
+
+    /// returns an html report of the given summary.
+    fn report(summary: &Summary, config: &[(&str, String)]) -> Result<String, Error> {
+        let mut buf = Vec::new();
+        write_report_to(summary, config, &mut buf)?;
+        String::from_utf8(buf).map_err(Error::from)
+    }
+
+    #[test]
+    fn report_contains_tables_and_charts() -> Result<(), Error> {
+        let html = report(&summary(), &[("total", "3".to_owned())])?;
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<th>p99</th>"));
+        assert!(html.contains("<th>connection refused</th><td class=\"number\">1</td>"));
+        assert_eq!(html.matches("<svg").count(), 3, "each chart is inline svg");
+        assert!(html.contains("<tr><th>total</th><td>3</td></tr>"));
+        Ok(())
+    }
+
+    #[test]
+    fn report_is_self_contained() -> Result<(), Error> {
+        let html = report(&summary(), &[])?;
+        for external in ["<script", "<link", "src=", "href=", "url("] {
+            assert!(
+                !html.contains(external),
+                "report should not contain `{external}`"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn configuration_is_escaped() -> Result<(), Error> {
+        let html = report(
+            &summary(),
+            &[("body", "<script>alert(\"hi\")</script>".to_owned())],
+        )?;
+        assert!(!html.contains("<script"));
+        assert!(html.contains("&lt;script&gt;alert(&quot;hi&quot;)&lt;/script&gt;"));
+        Ok(())
+    }
+
+    #[test]
+    fn empty_summary_can_be_reported() -> Result<(), Error> {
+        let html = report(&Summary::default(), &[])?;
+        assert!(html.contains("no requests were sent."));
+        assert!(html.contains("no requests failed."));
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+//! facilities for logging each [`Report`] as it completes.
+
+use super::*;
+
+/// a log of [`Report`]s, written as newline-delimited json.
+///
+/// each report is written as a json object on its own line. timestamps are written as the number
+/// of microseconds since the log's epoch, which should precede the start of the load-test.
+pub struct RequestLog<W: Write = BufWriter<File>> {
+    epoch: Instant,
+    writer: W,
+}
+
+/// a line of a [`RequestLog`].
+#[derive(Serialize)]
+struct Line {
+    intended_us: u64,
+    start_us: u64,
+    end_us: u64,
+    duration_us: u64,
+    success: bool,
+    status: Option<u16>,
+    failure: Option<String>,
+    stage: Option<usize>,
+    /// the time spent in each phase that the worker reached, in microseconds.
+    phases: BTreeMap<String, u64>,
+}
+
+// === impl RequestLog ===
+
+impl RequestLog {
+    /// creates a log at the given path, with timestamps relative to the given epoch.
+    pub fn create(path: &Path, epoch: Instant) -> Result<Self, Error> {
+        File::create(path)?
+            .pipe(BufWriter::new)
+            .pipe(|writer| Self::new(writer, epoch))
+            .pipe(Ok)
+    }
+}
+
+impl<W: Write> RequestLog<W> {
+    /// returns a log that writes to the given writer, with timestamps relative to the given epoch.
+    pub fn new(writer: W, epoch: Instant) -> Self {
+        Self { epoch, writer }
+    }
+
+    /// writes a report to the log.
+    pub fn write(&mut self, report: &Report) -> Result<(), Error> {
+        let Self { epoch, writer } = self;
+        let since = |when: Instant| micros(when.saturating_duration_since(*epoch));
+        let line = Line {
+            intended_us: since(report.intended),
+            start_us: since(report.start),
+            end_us: since(report.end),
+            duration_us: micros(report.duration),
+            success: report.success,
+            status: report.status.map(|status| status.as_u16()),
+            failure: report.failure.as_ref().map(ToString::to_string),
+            stage: report.stage,
+            phases: report
+                .phases
+                .iter()
+                .map(|(phase, duration)| (phase.to_string(), micros(duration)))
+                .collect(),
+        };
+
+        serde_json::to_writer(&mut *writer, &line)?;
+        writeln!(writer)?;
+        Ok(())
+    }
+
+    /// flushes any buffered lines, returning the underlying writer.
+    pub fn finish(mut self) -> Result<W, Error> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::worker::{Failure, Phases},
+    };
+
+    #[test]
+    fn reports_are_logged_one_per_line() -> Result<(), Error> {
+        let epoch = Instant::now();
+        let start = epoch + Duration::from_millis(5);
+        let mut phases = Phases::default();
+        phases.record(Phase::Request, Duration::from_millis(2));
+
+        let mut log = RequestLog::new(Vec::new(), epoch);
+        log.write(&Report {
+            intended: epoch,
+            phases,
+            stage: Some(1),
+            ..Report::test(start, Duration::from_millis(3))
+        })?;
+        log.write(&Report::test(start, Duration::ZERO).failed(Failure::ConnectRefused))?;
+
+        let buf = log.finish()?;
+        let lines = std::str::from_utf8(&buf)?
+            .lines()
+            .map(serde_json::from_str::<serde_json::Value>)
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            serde_json::json!({
+                "intended_us": 0,
+                "start_us": 5_000,
+                "end_us": 8_000,
+                "duration_us": 3_000,
+                "success": true,
+                "status": 200,
+                "failure": null,
+                "stage": 1,
+                "phases": { "ttfb": 2_000 },
+            })
+        );
+        assert_eq!(lines[1]["failure"], "connection refused");
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+//! serializable records of a [`Summary`].
+
+use super::*;
+
+/// a record of a [`Summary`].
+#[derive(Serialize)]
+pub(super) struct Record {
+    total: u32,
+    successes: u32,
+    /// true if the load-test was interrupted, and this record is partial.
+    interrupted: bool,
+    /// the success rate, as a percentage. this is `null` if no workers were spawned.
+    success_rate: Option<f32>,
+    /// the number of workers that finished per second.
+    rps: f64,
+    /// the number of workers that failed, by the kind of failure.
+    failures: BTreeMap<String, u32>,
+    dispatches: Dispatches,
+    /// the distribution of worker durations, i.e. the "service time."
+    duration: Distribution,
+    /// the distribution of response times, measured from when each worker was intended to start.
+    response_time: Distribution,
+    /// the time spent in each phase that workers reached, by phase.
+    phases: BTreeMap<String, PhaseRecord>,
+    /// summaries of each stage of the load profile, by stage index.
+    stages: BTreeMap<usize, StageRecord>,
+    time_series: TimeSeries,
+}
+
+/// the number of dispatches that were affected by backpressure.
+#[derive(Serialize)]
+struct Dispatches {
+    skipped: u64,
+    delayed: u64,
+}
+
+/// a distribution of durations, in microseconds.
+#[derive(Serialize)]
+struct Distribution {
+    min_us: u64,
+    max_us: u64,
+    mean_us: u64,
+    stddev_us: u64,
+    /// the duration at each of the [`Summary::PERCENTILES`], e.g. `p99_us`.
+    percentiles: BTreeMap<String, u64>,
+}
+
+/// the time spent in a phase, in microseconds.
+#[derive(Serialize)]
+struct PhaseRecord {
+    count: u64,
+    mean_us: u64,
+    p50_us: u64,
+    p90_us: u64,
+    p99_us: u64,
+}
+
+/// a summary of one stage of a load profile.
+#[derive(Serialize)]
+struct StageRecord {
+    total: u32,
+    success_rate: f32,
+    p50_us: u64,
+    p90_us: u64,
+    p99_us: u64,
+}
+
+/// observations made throughout the load-test.
+#[derive(Serialize)]
+struct TimeSeries {
+    /// the number of workers in flight, keyed by milliseconds since the first worker started.
+    in_flight: BTreeMap<u128, u32>,
+    /// the width of each interval, in microseconds.
+    interval_us: u64,
+    /// the workers that finished during each interval, see [`Summary::time_series()`].
+    intervals: Vec<IntervalRecord>,
+}
+
+/// the workers that finished during one interval of the load-test.
+#[derive(Serialize)]
+struct IntervalRecord {
+    /// when this interval began, in milliseconds since the first worker started.
+    start_ms: u128,
+    total: u32,
+    rps: f64,
+    error_rps: f64,
+    /// the percentage of workers that finished unsuccessfully.
+    error_rate: f32,
+    /// the median duration of workers that received a response. this is `null` if none did.
+    p50_us: Option<u64>,
+    /// the 99th percentile duration of workers that received a response. this is `null` if none
+    /// did.
+    p99_us: Option<u64>,
+}
+
+// === impl Record ===
+
+impl Record {
+    /// returns a record of the given summary.
+    pub(super) fn new(summary: &Summary) -> Self {
+        Self {
+            total: summary.total(),
+            successes: summary.success_count(),
+            interrupted: summary.interrupted(),
+            success_rate: (summary.total() > 0).then(|| summary.success_rate()),
+            rps: summary.throughput(),
+            failures: summary
+                .failures()
+                .map(|(failure, count)| (failure.to_string(), count))
+                .collect(),
+            dispatches: Dispatches {
+                skipped: summary.skipped(),
+                delayed: summary.delayed(),
+            },
+            duration: Distribution {
+                min_us: micros(summary.min_duration()),
+                max_us: micros(summary.max_duration()),
+                mean_us: micros(summary.mean_duration()),
+                stddev_us: micros(summary.stddev_duration()),
+                percentiles: percentiles(summary.percentiles()),
+            },
+            response_time: Distribution {
+                min_us: micros(summary.min_response_time()),
+                max_us: micros(summary.max_response_time()),
+                mean_us: micros(summary.mean_response_time()),
+                stddev_us: micros(summary.stddev_response_time()),
+                percentiles: percentiles(summary.response_time_percentiles()),
+            },
+            phases: Phase::ALL
+                .into_iter()
+                .filter(|phase| summary.phase_count(*phase) > 0)
+                .map(|phase| (phase.to_string(), PhaseRecord::new(summary, phase)))
+                .collect(),
+            stages: summary
+                .stages()
+                .map(|(index, stage)| {
+                    let record = StageRecord {
+                        total: stage.total(),
+                        success_rate: stage.success_rate(),
+                        p50_us: micros(stage.percentile(50.0)),
+                        p90_us: micros(stage.percentile(90.0)),
+                        p99_us: micros(stage.percentile(99.0)),
+                    };
+                    (index, record)
+                })
+                .collect(),
+            time_series: TimeSeries::new(summary),
+        }
+    }
+}
+
+// === impl PhaseRecord ===
+
+impl PhaseRecord {
+    /// returns a record of the time spent in the given phase.
+    fn new(summary: &Summary, phase: Phase) -> Self {
+        Self {
+            count: summary.phase_count(phase),
+            mean_us: micros(summary.phase_mean(phase)),
+            p50_us: micros(summary.phase_percentile(phase, 50.0)),
+            p90_us: micros(summary.phase_percentile(phase, 90.0)),
+            p99_us: micros(summary.phase_percentile(phase, 99.0)),
+        }
+    }
+}
+
+// === impl TimeSeries ===
+
+impl TimeSeries {
+    /// returns the time series observed in the given summary.
+    fn new(summary: &Summary) -> Self {
+        // NB: a summary with no workers has no time range.
+        let interval = summary.default_interval();
+        let Some((min, _)) = summary.time_range() else {
+            return Self {
+                in_flight: BTreeMap::new(),
+                interval_us: micros(interval),
+                intervals: Vec::new(),
+            };
+        };
+        let in_flight = summary
+            .compute_in_flight_observations()
+            .into_iter()
+            .map(|(when, count)| (when.duration_since(min).as_millis(), count))
+            .collect();
+        let intervals = summary
+            .time_series(interval)
+            .iter()
+            .map(IntervalRecord::new)
+            .collect();
+        Self {
+            in_flight,
+            interval_us: micros(interval),
+            intervals,
+        }
+    }
+}
+
+// === impl IntervalRecord ===
+
+impl IntervalRecord {
+    /// returns a record of the workers that finished during the given interval.
+    fn new(interval: &Interval) -> Self {
+        let responded = interval.response_count() > 0;
+        let percentile = |p| responded.then(|| micros(interval.percentile(p)));
+        Self {
+            start_ms: interval.start().as_millis(),
+            total: interval.total(),
+            rps: interval.rps(),
+            error_rps: interval.error_rps(),
+            error_rate: interval.error_rate(),
+            p50_us: percentile(50.0),
+            p99_us: percentile(99.0),
+        }
+    }
+}
+
+/// returns durations keyed by their percentile, e.g. `p99_us`.
+fn percentiles(iter: impl Iterator<Item = (f64, Duration)>) -> BTreeMap<String, u64> {
+    iter.map(|(p, duration)| (format!("p{p}_us"), micros(duration)))
+        .collect()
+}
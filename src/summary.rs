@@ -1,30 +1,105 @@
 //! summaries are aggregated views of many reports.
+//!
+//! a [`Summary`] describes the observed success rate, latency percentiles, failures, and average
+//! number of in-flight requests. latencies are reported both as the service time of each request,
+//! and as the response time measured from when each request was intended to be sent. workers
+//! that fail do not stop the load-test, their failures are classified and counted instead.
+
+pub use self::{distribution::Bucket, series::Interval, stages::StageSummary};
 
 use {
-    self::snapshots::Snapshots,
-    crate::worker::Report,
-    std::{
-        collections::BTreeSet,
-        time::{Duration, Instant},
+    self::{series::Completion, snapshots::Snapshots},
+    crate::{
+        error::Error,
+        worker::{Failure, Phase, Report},
     },
+    hdrhistogram::Histogram,
+    std::collections::BTreeMap,
+    std::time::{Duration, Instant},
+    tap::Pipe,
 };
 
+mod backlog;
+mod distribution;
+mod failures;
 mod in_flight;
+mod latency;
+mod phases;
 mod progress;
+mod series;
 mod snapshots;
+mod stages;
 
 /// an aggregated summary of many reports.
-#[derive(Default)]
 pub struct Summary {
     success_count: u32,
     total: u32,
-    durations: BTreeSet<Duration>,
+    /// a histogram of worker durations, in microseconds.
+    ///
+    /// this is the "service time" of each request, measured from when the worker started. only
+    /// workers that received a response are recorded, so that fast failures, e.g. refused
+    /// connections, do not make latencies look better than they are.
+    latencies: Histogram<u64>,
+    /// a histogram of response times, in microseconds.
+    ///
+    /// this is measured from when each worker was intended to start, see [`Report::intended`].
+    /// like `latencies`, only workers that received a response are recorded.
+    response_times: Histogram<u64>,
+    /// histograms of the time spent in each phase, in microseconds, indexed by [`Phase`].
+    phases: [Histogram<u64>; Phase::ALL.len()],
+    /// the number of workers that failed, by the kind of failure.
+    failures: BTreeMap<Failure, u32>,
     timestamps: Vec<(Instant, Instant)>,
+    /// each worker that finished, used to compute a [`Summary::time_series()`].
+    completions: Vec<Completion>,
+    /// summaries of the workers spawned in each stage of a load profile, by stage index.
+    stages: BTreeMap<usize, StageSummary>,
+    /// the number of dispatches that were skipped due to backpressure.
+    skipped: u64,
+    /// the number of dispatches that were delayed due to backpressure.
+    delayed: u64,
+    /// true if the load-test was interrupted before it finished.
+    interrupted: bool,
 }
 
 // === impl Summary ===
 
+impl Default for Summary {
+    fn default() -> Self {
+        Self::with_precision(Self::DEFAULT_PRECISION).expect("default precision is valid")
+    }
+}
+
 impl Summary {
+    /// the default number of significant figures that latencies are recorded with.
+    pub const DEFAULT_PRECISION: u8 = 3;
+
+    /// returns an empty summary, recording latencies with the given number of significant figures.
+    ///
+    /// returns an error if the precision is not between 1 and 5.
+    pub fn with_precision(sigfig: u8) -> Result<Self, Error> {
+        let latencies = Histogram::new(sigfig)?;
+        Ok(Self {
+            success_count: 0,
+            total: 0,
+            response_times: Histogram::new_from(&latencies),
+            phases: std::array::from_fn(|_| Histogram::new_from(&latencies)),
+            latencies,
+            failures: BTreeMap::new(),
+            timestamps: Vec::new(),
+            completions: Vec::new(),
+            stages: BTreeMap::new(),
+            skipped: 0,
+            delayed: 0,
+            interrupted: false,
+        })
+    }
+
+    /// returns the number of workers that received a successful response.
+    pub fn success_count(&self) -> u32 {
+        self.success_count
+    }
+
     /// returns the success rate.
     pub fn success_rate(&self) -> f32 {
         let Self {
@@ -40,27 +115,27 @@ impl Summary {
         s / t * 100.0
     }
 
-    /// returns the median worker duration.
-    pub fn median_duration(&self) -> Duration {
-        let Self { durations, .. } = self;
-        let mid = durations.len() / 2;
-
-        durations
-            .iter()
-            .take(mid)
-            .next()
-            .expect("median value should exist")
-            .to_owned()
+    /// records that the load-test was interrupted, and that this summary is partial.
+    ///
+    /// see [`Interrupt`][crate::interrupt::Interrupt].
+    pub fn record_interrupt(&mut self) {
+        self.interrupted = true;
+    }
+
+    /// returns true if the load-test was interrupted before it finished.
+    pub fn interrupted(&self) -> bool {
+        self.interrupted
     }
 
     /// returns the minimum and maximum timestamps.
     ///
-    /// this reports when the first worker started, and when the last worker finished?
-    pub fn time_range(&self) -> (Instant, Instant) {
+    /// this reports when the first worker started, and when the last worker finished. this is
+    /// `None` if no workers finished, e.g. if the load-test was interrupted immediately.
+    pub fn time_range(&self) -> Option<(Instant, Instant)> {
         let iter = || self.timestamps.iter().flat_map(|(x, y)| [x, y].into_iter());
-        let min = iter().min().expect("timestamps should exist");
-        let max = iter().max().expect("timestamps should exist");
-        (*min, *max)
+        let min = iter().min()?;
+        let max = iter().max()?;
+        Some((*min, *max))
     }
 }
 
@@ -78,23 +153,71 @@ impl Summary {
         Report {
             success,
             duration,
+            status,
+            failure,
             start,
             end,
+            intended,
+            phases: report_phases,
+            stage,
         }: Report,
     ) {
         let Self {
             success_count,
             total,
-            durations,
+            latencies,
+            response_times,
+            phases,
+            failures,
             timestamps,
+            completions,
+            stages,
+            ..
         } = self;
 
         *total += 1;
-        durations.insert(duration);
+        let micros = duration.as_micros().try_into().unwrap_or(u64::MAX);
+        let responded = status.is_some();
+        if responded {
+            latencies
+                .record(micros)
+                .expect("histogram should resize to fit values");
+            response_times
+                .record(
+                    end.saturating_duration_since(intended)
+                        .as_micros()
+                        .try_into()
+                        .unwrap_or(u64::MAX),
+                )
+                .expect("histogram should resize to fit values");
+        }
         timestamps.push((start, end));
+        completions.push(Completion {
+            end,
+            micros,
+            success,
+            responded,
+        });
+
+        for (phase, duration) in report_phases.iter() {
+            phases[phase.index()]
+                .record(duration.as_micros().try_into().unwrap_or(u64::MAX))
+                .expect("histogram should resize to fit values");
+        }
+
+        if let Some(stage) = stage {
+            stages
+                .entry(stage)
+                .or_insert_with(|| StageSummary::new(latencies))
+                .record(success, responded.then_some(micros));
+        }
 
         if success {
             *success_count += 1;
         }
+
+        if let Some(failure) = failure {
+            *failures.entry(failure).or_default() += 1;
+        }
     }
 }
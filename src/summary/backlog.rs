@@ -0,0 +1,28 @@
+//! facilities for counting the dispatches that were affected by backpressure.
+
+use {super::*, crate::syndicate::Backlog};
+
+// === impl Summary ===
+
+impl Summary {
+    /// records the number of dispatches that were affected by backpressure.
+    ///
+    /// if these are nonzero, the load-tester, rather than the server, was a bottleneck.
+    pub fn record_backlog(&mut self, backlog: &Backlog) {
+        self.skipped = backlog.skipped();
+        self.delayed = backlog.delayed();
+    }
+
+    /// returns the number of dispatches that were skipped due to backpressure.
+    ///
+    /// skipped dispatches were not sent, and are not included in [`Summary::total()`], but they
+    /// do count toward the total number of requests that a load-test was given.
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    /// returns the number of dispatches that were delayed due to backpressure.
+    pub fn delayed(&self) -> u64 {
+        self.delayed
+    }
+}
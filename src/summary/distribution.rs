@@ -0,0 +1,210 @@
+//! facilities for describing the shape of the latency distribution.
+
+use {super::*, std::ops::RangeInclusive};
+
+/// a range of worker durations, and the number of workers whose duration fell within it.
+///
+/// see [`Summary::histogram()`] and [`Summary::heatmap()`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Bucket {
+    /// the shortest duration in this bucket, inclusive.
+    low: Duration,
+    /// the longest duration in this bucket, exclusive.
+    high: Duration,
+    /// the number of workers whose duration fell within this bucket.
+    count: u64,
+}
+
+// === impl Summary ===
+
+impl Summary {
+    /// the number of quantiles reported by [`Summary::percentile_distribution()`], for each halving
+    /// of the distance to 100%.
+    const TICKS_PER_HALF_DISTANCE: u32 = 5;
+
+    /// returns a histogram of worker durations, in log-scaled buckets.
+    ///
+    /// each bucket is twice as wide as the last. buckets span from the shortest to the longest
+    /// worker duration, and buckets that no workers fell within are included, so that the
+    /// histogram has no gaps.
+    pub fn histogram(&self) -> Vec<Bucket> {
+        match Self::exponents(&self.latencies) {
+            Some(exponents) => Self::bucket(&self.latencies, exponents),
+            None => Vec::new(),
+        }
+    }
+
+    /// returns a histogram of worker durations for each interval of the load-test.
+    ///
+    /// intervals are those returned by [`Summary::time_series()`], and each histogram uses the
+    /// same buckets as [`Summary::histogram()`], so that they may be compared with one another.
+    ///
+    /// # panics
+    ///
+    /// this function panics if `interval` is zero.
+    pub fn heatmap(&self, interval: Duration) -> Vec<Vec<Bucket>> {
+        let Some(exponents) = Self::exponents(&self.latencies) else {
+            return Vec::new();
+        };
+
+        self.time_series(interval)
+            .iter()
+            .map(|interval| Self::bucket(&interval.latencies, exponents.clone()))
+            .collect()
+    }
+
+    /// returns worker durations at increasingly fine percentiles.
+    ///
+    /// like HdrHistogram's percentile distribution, percentiles are reported more densely as they
+    /// approach 100, e.g. 50, 75, 87.5, and so on. the last percentile reported is 100.
+    pub fn percentile_distribution(&self) -> Vec<(f64, Duration)> {
+        if self.latencies.is_empty() {
+            return Vec::new();
+        }
+
+        self.latencies
+            .iter_quantiles(Self::TICKS_PER_HALF_DISTANCE)
+            .map(|value| {
+                let percentile = value.quantile_iterated_to() * 100.0;
+                let duration = Duration::from_micros(value.value_iterated_to());
+                (percentile, duration)
+            })
+            .collect()
+    }
+
+    /// returns the base-2 exponents of the shortest and longest values in a histogram.
+    ///
+    /// returns `None` if no values were recorded.
+    fn exponents(histogram: &Histogram<u64>) -> Option<RangeInclusive<u32>> {
+        let mut exponents = histogram
+            .iter_recorded()
+            .map(|value| Self::exponent(value.value_iterated_to()));
+        let first = exponents.next()?;
+        let last = exponents.last().unwrap_or(first);
+        Some(first..=last)
+    }
+
+    /// returns the base-2 exponent of the bucket that a value, in microseconds, falls within.
+    fn exponent(micros: u64) -> u32 {
+        micros.max(1).ilog2()
+    }
+
+    /// sorts the values in a histogram into buckets, one for each of the given exponents.
+    ///
+    /// values outside of the given exponents are ignored.
+    fn bucket(histogram: &Histogram<u64>, exponents: RangeInclusive<u32>) -> Vec<Bucket> {
+        let first = *exponents.start();
+        let mut buckets = exponents
+            .map(|exponent| Bucket {
+                low: Duration::from_micros(1 << exponent),
+                high: Duration::from_micros(1 << (exponent + 1)),
+                count: 0,
+            })
+            .collect::<Vec<_>>();
+
+        for value in histogram.iter_recorded() {
+            let index = Self::exponent(value.value_iterated_to()).checked_sub(first);
+            if let Some(bucket) = index.and_then(|i| buckets.get_mut(i as usize)) {
+                bucket.count += value.count_at_value();
+            }
+        }
+
+        buckets
+    }
+}
+
+// === impl Bucket ===
+
+impl Bucket {
+    /// returns the shortest duration in this bucket, inclusive.
+    pub fn low(&self) -> Duration {
+        self.low
+    }
+
+    /// returns the longest duration in this bucket, exclusive.
+    pub fn high(&self) -> Duration {
+        self.high
+    }
+
+    /// returns the number of workers whose duration fell within this bucket.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// returns a report for a worker that ran for `millis` milliseconds, finishing `end`
+    /// milliseconds after `epoch`.
+    fn report(epoch: Instant, end: u64, millis: u64) -> Report {
+        let duration = Duration::from_millis(millis);
+        Report::test(epoch + Duration::from_millis(end) - duration, duration)
+    }
+
+    #[test]
+    fn histograms_are_log_scaled() {
+        let epoch = Instant::now();
+        let mut summary = Summary::default();
+        summary.extend([
+            report(epoch, 100, 5),
+            report(epoch, 100, 6),
+            report(epoch, 100, 30),
+        ]);
+
+        // 5ms and 6ms fall within [4.096ms, 8.192ms), and 30ms within [16.384ms, 32.768ms).
+        let histogram = summary.histogram();
+        let counts = histogram.iter().map(Bucket::count).collect::<Vec<_>>();
+        assert_eq!(counts, [2, 0, 1], "empty buckets are included");
+        assert_eq!(histogram[0].low(), Duration::from_micros(4096));
+        assert_eq!(histogram[0].high(), Duration::from_micros(8192));
+        assert_eq!(histogram[2].high(), Duration::from_micros(32768));
+    }
+
+    #[test]
+    fn heatmaps_share_the_histogram_buckets() {
+        let epoch = Instant::now();
+        let mut summary = Summary::default();
+        summary.extend([
+            report(epoch, 50, 5),
+            report(epoch, 150, 30),
+            report(epoch, 350, 30),
+        ]);
+
+        let heatmap = summary.heatmap(Duration::from_millis(100));
+        assert_eq!(heatmap.len(), 4);
+        let counts = |column: &[Bucket]| column.iter().map(Bucket::count).collect::<Vec<_>>();
+        assert_eq!(counts(&heatmap[0]), [1, 0, 0]);
+        assert_eq!(counts(&heatmap[1]), [0, 0, 1]);
+        assert_eq!(counts(&heatmap[2]), [0, 0, 0]);
+        assert_eq!(counts(&heatmap[3]), [0, 0, 1]);
+        for column in &heatmap {
+            let lows = column.iter().map(Bucket::low);
+            assert!(lows.eq(summary.histogram().iter().map(Bucket::low)));
+        }
+    }
+
+    #[test]
+    fn percentile_distributions_approach_the_maximum() {
+        let epoch = Instant::now();
+        let mut summary = Summary::default();
+        summary.extend((1..=1000).map(|millis| report(epoch, 1000, millis)));
+
+        let distribution = summary.percentile_distribution();
+        assert!(distribution.windows(2).all(|w| w[0].0 <= w[1].0));
+        assert!(distribution.windows(2).all(|w| w[0].1 <= w[1].1));
+        assert!(distribution.iter().any(|(p, _)| *p > 99.0));
+        let (percentile, duration) = distribution.last().copied().unwrap();
+        assert_eq!(percentile, 100.0);
+        assert_eq!(duration, summary.max_duration());
+    }
+
+    #[test]
+    fn empty_summaries_have_no_distribution() {
+        let summary = Summary::default();
+        assert!(summary.histogram().is_empty());
+        assert!(summary.heatmap(Duration::from_secs(1)).is_empty());
+        assert!(summary.percentile_distribution().is_empty());
+    }
+}
@@ -0,0 +1,59 @@
+//! facilities for counting the ways in which workers failed.
+
+use super::*;
+
+// === impl Summary ===
+
+impl Summary {
+    /// returns the number of workers that failed, by the kind of failure.
+    pub fn failures(&self) -> impl Iterator<Item = (Failure, u32)> + '_ {
+        self.failures
+            .iter()
+            .map(|(failure, count)| (*failure, *count))
+    }
+
+    /// returns the total number of workers that failed.
+    ///
+    /// NB: this does not include workers that received an unsuccessful response.
+    pub fn failure_count(&self) -> u32 {
+        self.failures.values().sum()
+    }
+
+    /// returns the total number of workers.
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, h2::Reason};
+
+    /// returns a report for a worker that failed.
+    fn failed(failure: Failure) -> Report {
+        Report::test(Instant::now(), Duration::ZERO).failed(failure)
+    }
+
+    #[test]
+    fn failures_are_counted_by_kind() {
+        let mut summary = Summary::default();
+        summary.extend([
+            failed(Failure::ConnectRefused),
+            failed(Failure::Reset(Reason::CANCEL)),
+            failed(Failure::ConnectRefused),
+            failed(Failure::Reset(Reason::REFUSED_STREAM)),
+        ]);
+
+        assert_eq!(summary.total(), 4);
+        assert_eq!(summary.failure_count(), 4);
+        assert_eq!(summary.success_rate(), 0.0);
+        assert_eq!(
+            summary.failures().collect::<Vec<_>>(),
+            vec![
+                (Failure::ConnectRefused, 2),
+                (Failure::Reset(Reason::REFUSED_STREAM), 1),
+                (Failure::Reset(Reason::CANCEL), 1),
+            ]
+        );
+    }
+}
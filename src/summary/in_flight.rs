@@ -9,8 +9,13 @@ type Observations = std::collections::BTreeMap<Instant, u32>;
 
 impl Summary {
     /// computes the average number of in-flight requests.
+    ///
+    /// this is zero if no workers finished.
     pub fn average_in_flight(&self) -> f64 {
         let observations = self.compute_in_flight_observations();
+        if observations.is_empty() {
+            return 0.0;
+        }
 
         // now find the average across all of our observations.
         let sum: f64 = observations.values().sum::<u32>().into();
@@ -20,11 +25,15 @@ impl Summary {
     }
 
     /// computes the number of in-flight requests at regular intervals in time.
+    ///
+    /// this is empty if no workers finished.
     pub fn compute_in_flight_observations(&self) -> Observations {
         let Self { timestamps, .. } = self;
 
         // find how many workers were in flight at 5ms intervals during the load-test.
-        let (min, max) = self.time_range();
+        let Some((min, max)) = self.time_range() else {
+            return Observations::new();
+        };
         Snapshots::new(min, max)
             .map(|when| {
                 let num = Self::count_in_flight(timestamps, when);
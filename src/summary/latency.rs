@@ -0,0 +1,208 @@
+//! facilities for measuring the distribution of worker latencies.
+
+use super::*;
+
+// === impl Summary ===
+
+impl Summary {
+    /// the percentiles reported by [`Summary::percentiles()`].
+    pub const PERCENTILES: &'static [f64] = &[50.0, 75.0, 90.0, 95.0, 99.0, 99.9];
+
+    /// returns the number of workers that received a response.
+    ///
+    /// only these workers are included in latency percentiles. workers that failed without a
+    /// response, e.g. because the connection was refused, are not.
+    pub fn response_count(&self) -> u64 {
+        self.latencies.len()
+    }
+
+    /// returns the worker duration at the given percentile.
+    ///
+    /// `percentile` should be a number between 0 and 100. this is zero if no workers received a
+    /// response, see [`Summary::response_count()`].
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        self.latencies
+            .value_at_percentile(percentile)
+            .pipe(Duration::from_micros)
+    }
+
+    /// returns the worker duration at each of the [`Summary::PERCENTILES`].
+    pub fn percentiles(&self) -> impl Iterator<Item = (f64, Duration)> + '_ {
+        Self::PERCENTILES.iter().map(|p| (*p, self.percentile(*p)))
+    }
+
+    /// returns the median worker duration.
+    pub fn median_duration(&self) -> Duration {
+        self.percentile(50.0)
+    }
+
+    /// returns the minimum worker duration.
+    pub fn min_duration(&self) -> Duration {
+        self.latencies.min().pipe(Duration::from_micros)
+    }
+
+    /// returns the maximum worker duration.
+    pub fn max_duration(&self) -> Duration {
+        self.latencies.max().pipe(Duration::from_micros)
+    }
+
+    /// returns the mean worker duration.
+    pub fn mean_duration(&self) -> Duration {
+        Self::from_micros_f64(self.latencies.mean())
+    }
+
+    /// returns the standard deviation of worker durations.
+    pub fn stddev_duration(&self) -> Duration {
+        Self::from_micros_f64(self.latencies.stdev())
+    }
+
+    /// returns the response time at the given percentile.
+    ///
+    /// unlike [`Summary::percentile()`], response times are measured from when each worker was
+    /// intended to start, including any time it spent waiting to be sent. `percentile` should be
+    /// a number between 0 and 100.
+    pub fn response_time_percentile(&self, percentile: f64) -> Duration {
+        self.response_times
+            .value_at_percentile(percentile)
+            .pipe(Duration::from_micros)
+    }
+
+    /// returns the response time at each of the [`Summary::PERCENTILES`].
+    pub fn response_time_percentiles(&self) -> impl Iterator<Item = (f64, Duration)> + '_ {
+        Self::PERCENTILES
+            .iter()
+            .map(|p| (*p, self.response_time_percentile(*p)))
+    }
+
+    /// returns the minimum response time.
+    pub fn min_response_time(&self) -> Duration {
+        self.response_times.min().pipe(Duration::from_micros)
+    }
+
+    /// returns the maximum response time.
+    pub fn max_response_time(&self) -> Duration {
+        self.response_times.max().pipe(Duration::from_micros)
+    }
+
+    /// returns the mean response time.
+    pub fn mean_response_time(&self) -> Duration {
+        Self::from_micros_f64(self.response_times.mean())
+    }
+
+    /// returns the standard deviation of response times.
+    pub fn stddev_response_time(&self) -> Duration {
+        Self::from_micros_f64(self.response_times.stdev())
+    }
+
+    /// returns a [`Duration`] from a fractional number of microseconds.
+    pub(super) fn from_micros_f64(micros: f64) -> Duration {
+        Duration::from_secs_f64(micros / 1_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// returns a successful report, with the given duration.
+    fn report(duration: Duration) -> Report {
+        Report::test(Instant::now(), duration)
+    }
+
+    /// returns a summary of reports with the given durations, in milliseconds.
+    fn summary(millis: &[u64]) -> Summary {
+        let mut summary = Summary::default();
+        summary.extend(
+            millis
+                .iter()
+                .copied()
+                .map(Duration::from_millis)
+                .map(report),
+        );
+        summary
+    }
+
+    #[test]
+    fn identical_durations_are_not_deduplicated() {
+        let summary = summary(&[1, 1, 1, 100]);
+        assert_eq!(summary.median_duration().as_millis(), 1);
+        assert_eq!(summary.percentile(75.0).as_millis(), 1);
+    }
+
+    #[test]
+    fn median_is_the_middle_value() {
+        let summary = summary(&[1, 2, 3, 4, 5]);
+        assert_eq!(summary.median_duration().as_millis(), 3);
+    }
+
+    #[test]
+    fn min_max_and_mean_are_computed() {
+        let summary = summary(&[10, 20, 30]);
+        assert_eq!(summary.min_duration().as_millis(), 10);
+        assert_eq!(summary.max_duration().as_millis(), 30);
+        assert_eq!(summary.mean_duration().as_millis(), 20);
+    }
+
+    #[test]
+    fn percentiles_are_reported_in_order() {
+        let summary = summary(&(1..=1000).collect::<Vec<_>>());
+        let percentiles = summary.percentiles().collect::<Vec<_>>();
+        assert_eq!(percentiles.len(), Summary::PERCENTILES.len());
+        assert!(percentiles.windows(2).all(|w| w[0].1 <= w[1].1));
+        assert_eq!(percentiles[0].1.as_millis(), 500);
+        assert_eq!(percentiles[4].1.as_millis(), 990);
+    }
+
+    #[test]
+    fn failures_without_responses_are_not_latencies() {
+        let start = Instant::now();
+        let mut summary = Summary::default();
+        summary.extend((0..300).map(|_| {
+            Report::test(start, Duration::from_millis(4)).failed(Failure::ConnectRefused)
+        }));
+
+        assert_eq!(summary.total(), 300);
+        assert_eq!(summary.response_count(), 0);
+        assert_eq!(summary.percentile(99.0), Duration::ZERO);
+        assert_eq!(summary.response_time_percentile(99.0), Duration::ZERO);
+        assert!(summary.histogram().is_empty());
+    }
+
+    #[test]
+    fn unsuccessful_responses_are_latencies() {
+        let start = Instant::now();
+        let mut summary = Summary::default();
+        summary.extend([
+            Report::test(start, Duration::from_millis(30))
+                .status(http::StatusCode::SERVICE_UNAVAILABLE),
+            Report::test(start, Duration::from_millis(1)).failed(Failure::ConnectRefused),
+        ]);
+
+        assert_eq!(summary.response_count(), 1);
+        assert_eq!(summary.min_duration().as_millis(), 30);
+    }
+
+    #[test]
+    fn precision_must_be_valid() {
+        assert!(Summary::with_precision(1).is_ok());
+        assert!(Summary::with_precision(5).is_ok());
+        assert!(Summary::with_precision(6).is_err());
+    }
+
+    #[test]
+    fn response_times_include_time_spent_waiting() {
+        let delayed = |wait: u64, duration: u64| {
+            let report = report(Duration::from_millis(duration));
+            let intended = report.start - Duration::from_millis(wait);
+            Report { intended, ..report }
+        };
+        let mut summary = Summary::default();
+        summary.extend([delayed(0, 10), delayed(90, 10)]);
+
+        // the service time only measures how long the worker ran for.
+        assert_eq!(summary.max_duration().as_millis(), 10);
+        // ...while the response time includes the time spent waiting to be sent.
+        assert_eq!(summary.max_response_time().as_millis(), 100);
+        assert_eq!(summary.response_time_percentile(50.0).as_millis(), 10);
+    }
+}
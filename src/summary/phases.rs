@@ -0,0 +1,54 @@
+//! facilities for measuring the time that workers spent in each of their phases.
+
+use super::*;
+
+// === impl Summary ===
+
+impl Summary {
+    /// returns the time spent in the given phase, at the given percentile.
+    ///
+    /// `percentile` should be a number between 0 and 100.
+    pub fn phase_percentile(&self, phase: Phase, percentile: f64) -> Duration {
+        self.phases[phase.index()]
+            .value_at_percentile(percentile)
+            .pipe(Duration::from_micros)
+    }
+
+    /// returns the mean time spent in the given phase.
+    pub fn phase_mean(&self, phase: Phase) -> Duration {
+        Self::from_micros_f64(self.phases[phase.index()].mean())
+    }
+
+    /// returns the number of workers that reached the given phase.
+    pub fn phase_count(&self, phase: Phase) -> u64 {
+        self.phases[phase.index()].len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::worker::Phases};
+
+    #[test]
+    fn phases_are_summarized_separately() {
+        let start = Instant::now();
+        let mut phases = Phases::default();
+        phases.record(Phase::Request, Duration::from_millis(3));
+        phases.record(Phase::Body, Duration::from_millis(1));
+        let report = Report {
+            phases,
+            ..Report::test(start, Duration::from_millis(4))
+        };
+
+        let mut summary = Summary::default();
+        summary.extend([report]);
+
+        assert_eq!(summary.phase_count(Phase::Dns), 0);
+        assert_eq!(summary.phase_count(Phase::Request), 1);
+        assert_eq!(
+            summary.phase_percentile(Phase::Request, 50.0).as_millis(),
+            3
+        );
+        assert_eq!(summary.phase_mean(Phase::Body).as_millis(), 1);
+    }
+}
@@ -9,11 +9,15 @@ type Observations = std::collections::BTreeMap<Instant, f32>;
 
 impl Summary {
     /// computes the percentage of workers that have finished at regular intervals in time.
+    ///
+    /// this is empty if no workers finished.
     pub fn compute_progress_observations(&self) -> Observations {
         let Self { timestamps, .. } = self;
 
         // find how many workers were finished at 5ms intervals during the load-test.
-        let (min, max) = self.time_range();
+        let Some((min, max)) = self.time_range() else {
+            return Observations::new();
+        };
         Snapshots::new(min, max)
             .map(|when| {
                 let num = Self::percent_finished(timestamps, when);
@@ -22,6 +26,19 @@ impl Summary {
             .collect()
     }
 
+    /// returns the average number of workers that finished per second.
+    ///
+    /// this is measured from when the first worker started, until the last worker finished.
+    pub fn throughput(&self) -> f64 {
+        let Some((min, max)) = self.time_range() else {
+            return 0.0;
+        };
+        match max.duration_since(min).as_secs_f64() {
+            secs if secs > 0.0 => f64::from(self.total) / secs,
+            _ => 0.0,
+        }
+    }
+
     /// computes the percentage of workers that have finished at a given [`Instant`].
     fn percent_finished(timestamps: &[(Instant, Instant)], when: Instant) -> f32 {
         let total = timestamps.len() as f32;
@@ -0,0 +1,229 @@
+//! facilities for measuring how throughput and latency changed throughout a load-test.
+
+use super::*;
+
+/// a worker that finished, recorded so that it may be bucketed into an [`Interval`].
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Completion {
+    /// when the worker finished.
+    pub(super) end: Instant,
+    /// how long the worker took to run, in microseconds.
+    pub(super) micros: u64,
+    /// true if the worker received a successful response.
+    pub(super) success: bool,
+    /// true if the worker received a response, successful or not.
+    pub(super) responded: bool,
+}
+
+/// a summary of the workers that finished during one interval of a load-test.
+///
+/// see [`Summary::time_series()`].
+pub struct Interval {
+    /// when this interval began, relative to the start of the load-test.
+    start: Duration,
+    /// the length of this interval.
+    width: Duration,
+    /// the number of workers that finished during this interval.
+    total: u32,
+    /// the number of workers that finished unsuccessfully during this interval.
+    errors: u32,
+    /// a histogram of worker durations, in microseconds.
+    ///
+    /// only workers that received a response are recorded.
+    pub(super) latencies: Histogram<u64>,
+}
+
+// === impl Summary ===
+
+impl Summary {
+    /// the number of intervals that [`Summary::default_interval()`] divides a load-test into.
+    const INTERVALS: u32 = 64;
+
+    /// the shortest interval returned by [`Summary::default_interval()`].
+    const MIN_INTERVAL: Duration = Duration::from_millis(10);
+
+    /// returns the workers that finished during each interval of the load-test.
+    ///
+    /// intervals are measured from when the first worker started, and workers are bucketed by
+    /// when they finished. intervals in which no workers finished are included, so that the
+    /// series has no gaps.
+    ///
+    /// # panics
+    ///
+    /// this function panics if `interval` is zero.
+    pub fn time_series(&self, interval: Duration) -> Vec<Interval> {
+        assert!(!interval.is_zero(), "interval should not be zero");
+        let Some((min, max)) = self.time_range() else {
+            return Vec::new();
+        };
+
+        // allocate an empty bucket for each interval, up to when the last worker finished.
+        let count = max.duration_since(min).as_nanos() / interval.as_nanos() + 1;
+        let mut series = (0..count as u32)
+            .map(|i| Interval::new(interval * i, interval, &self.latencies))
+            .collect::<Vec<_>>();
+
+        for completion in &self.completions {
+            let offset = completion.end.duration_since(min).as_nanos() / interval.as_nanos();
+            series[offset as usize].record(completion);
+        }
+
+        series
+    }
+
+    /// returns an interval dividing the load-test into a fixed number of buckets.
+    ///
+    /// this is useful for charting a [`Summary::time_series()`].
+    pub fn default_interval(&self) -> Duration {
+        let Some((min, max)) = self.time_range() else {
+            return Self::MIN_INTERVAL;
+        };
+        (max.duration_since(min) / Self::INTERVALS).max(Self::MIN_INTERVAL)
+    }
+}
+
+// === impl Interval ===
+
+impl Interval {
+    /// returns an empty interval, with the same precision as the given histogram.
+    fn new(start: Duration, width: Duration, latencies: &Histogram<u64>) -> Self {
+        Self {
+            start,
+            width,
+            total: 0,
+            errors: 0,
+            latencies: Histogram::new_from(latencies),
+        }
+    }
+
+    /// records a worker that finished during this interval.
+    fn record(&mut self, completion: &Completion) {
+        self.total += 1;
+        if !completion.success {
+            self.errors += 1;
+        }
+        if completion.responded {
+            self.latencies
+                .record(completion.micros)
+                .expect("histogram should resize to fit values");
+        }
+    }
+
+    /// returns when this interval began, relative to the start of the load-test.
+    pub fn start(&self) -> Duration {
+        self.start
+    }
+
+    /// returns the number of workers that finished during this interval.
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    /// returns the number of workers that received a response during this interval.
+    pub fn response_count(&self) -> u64 {
+        self.latencies.len()
+    }
+
+    /// returns the number of workers that finished per second, during this interval.
+    pub fn rps(&self) -> f64 {
+        f64::from(self.total) / self.width.as_secs_f64()
+    }
+
+    /// returns the number of unsuccessful workers that finished per second, during this interval.
+    pub fn error_rps(&self) -> f64 {
+        f64::from(self.errors) / self.width.as_secs_f64()
+    }
+
+    /// returns the percentage of workers that finished unsuccessfully, during this interval.
+    ///
+    /// this is zero if no workers finished during this interval.
+    pub fn error_rate(&self) -> f32 {
+        match self.total {
+            0 => 0.0,
+            total => self.errors as f32 / total as f32 * 100.0,
+        }
+    }
+
+    /// returns the duration of workers that finished during this interval, at the given
+    /// percentile.
+    ///
+    /// `percentile` should be a number between 0 and 100. only workers that received a response
+    /// are included. this is zero if no such workers finished during this interval.
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        self.latencies
+            .value_at_percentile(percentile)
+            .pipe(Duration::from_micros)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// returns a report for a worker that finished `end` milliseconds after `epoch`.
+    fn report(epoch: Instant, end: u64, millis: u64, success: bool) -> Report {
+        let duration = Duration::from_millis(millis);
+        let start = epoch + Duration::from_millis(end) - duration;
+        Report {
+            success,
+            ..Report::test(start, duration)
+        }
+    }
+
+    #[test]
+    fn reports_are_bucketed_by_when_they_finished() {
+        let epoch = Instant::now();
+        let mut summary = Summary::default();
+        summary.extend([
+            report(epoch, 10, 10, true),
+            report(epoch, 50, 20, true),
+            report(epoch, 90, 30, false),
+            report(epoch, 350, 100, true),
+        ]);
+
+        let series = summary.time_series(Duration::from_millis(100));
+        assert_eq!(series.len(), 4, "intervals without workers are included");
+
+        let first = &series[0];
+        assert_eq!(first.start(), Duration::ZERO);
+        assert_eq!(first.total(), 3);
+        assert_eq!(first.rps(), 30.0);
+        assert_eq!(first.error_rps(), 10.0);
+        assert_eq!(first.error_rate(), 1.0 / 3.0 * 100.0);
+        assert_eq!(first.percentile(50.0).as_millis(), 20);
+        assert_eq!(first.percentile(99.0).as_millis(), 30);
+
+        let gap = &series[1];
+        assert_eq!(gap.total(), 0);
+        assert_eq!(gap.error_rate(), 0.0);
+        assert_eq!(gap.percentile(99.0), Duration::ZERO);
+
+        let last = &series[3];
+        assert_eq!(last.start(), Duration::from_millis(300));
+        assert_eq!(last.total(), 1);
+        assert_eq!(last.percentile(50.0).as_millis(), 100);
+    }
+
+    #[test]
+    fn empty_summaries_have_no_series() {
+        let summary = Summary::default();
+        assert_eq!(summary.time_range(), None);
+        assert!(summary.time_series(Duration::from_secs(1)).is_empty());
+        assert_eq!(summary.default_interval(), Summary::MIN_INTERVAL);
+        assert_eq!(summary.throughput(), 0.0);
+        assert_eq!(summary.average_in_flight(), 0.0);
+    }
+
+    #[test]
+    fn default_interval_divides_the_load_test() {
+        let epoch = Instant::now();
+        let mut summary = Summary::default();
+        summary.extend([report(epoch, 640, 640, true), report(epoch, 6400, 10, true)]);
+        assert_eq!(summary.default_interval(), Duration::from_millis(100));
+        assert_eq!(
+            summary.time_series(summary.default_interval()).len(),
+            65,
+            "the last worker finishes at the end of the last interval"
+        );
+    }
+}
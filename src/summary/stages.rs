@@ -0,0 +1,103 @@
+//! facilities for summarizing each stage of a load profile.
+
+use super::*;
+
+/// a summary of the workers spawned during one stage of a load profile.
+///
+/// see [`Summary::stages()`].
+pub struct StageSummary {
+    success_count: u32,
+    total: u32,
+    /// a histogram of worker durations, in microseconds.
+    ///
+    /// only workers that received a response are recorded.
+    latencies: Histogram<u64>,
+}
+
+// === impl Summary ===
+
+impl Summary {
+    /// returns a summary of each stage of the load profile, by stage index.
+    ///
+    /// stages in which no workers were spawned are omitted.
+    pub fn stages(&self) -> impl Iterator<Item = (usize, &StageSummary)> + '_ {
+        self.stages.iter().map(|(index, stage)| (*index, stage))
+    }
+}
+
+// === impl StageSummary ===
+
+impl StageSummary {
+    /// returns an empty stage summary, with the same precision as the given histogram.
+    pub(super) fn new(latencies: &Histogram<u64>) -> Self {
+        Self {
+            success_count: 0,
+            total: 0,
+            latencies: Histogram::new_from(latencies),
+        }
+    }
+
+    /// records a worker's outcome, and its duration in microseconds if it received a response.
+    pub(super) fn record(&mut self, success: bool, micros: Option<u64>) {
+        self.total += 1;
+        if success {
+            self.success_count += 1;
+        }
+        if let Some(micros) = micros {
+            self.latencies
+                .record(micros)
+                .expect("histogram should resize to fit values");
+        }
+    }
+
+    /// returns the total number of workers spawned during this stage.
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    /// returns the success rate of workers spawned during this stage.
+    pub fn success_rate(&self) -> f32 {
+        self.success_count as f32 / self.total as f32 * 100.0
+    }
+
+    /// returns the worker duration at the given percentile.
+    ///
+    /// `percentile` should be a number between 0 and 100.
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        self.latencies
+            .value_at_percentile(percentile)
+            .pipe(Duration::from_micros)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// returns a report for a worker spawned in the given stage.
+    fn report(stage: usize, success: bool, millis: u64) -> Report {
+        Report {
+            success,
+            stage: Some(stage),
+            ..Report::test(Instant::now(), Duration::from_millis(millis))
+        }
+    }
+
+    #[test]
+    fn reports_are_summarized_by_stage() {
+        let mut summary = Summary::default();
+        summary.extend([report(0, true, 1), report(0, false, 3), report(2, true, 10)]);
+
+        let stages = summary.stages().collect::<Vec<_>>();
+        assert_eq!(stages.len(), 2, "empty stages are omitted");
+
+        let (index, first) = stages[0];
+        assert_eq!((index, first.total()), (0, 2));
+        assert_eq!(first.success_rate(), 50.0);
+        assert_eq!(first.percentile(100.0).as_millis(), 3);
+
+        let (index, last) = stages[1];
+        assert_eq!((index, last.total()), (2, 1));
+        assert_eq!(last.percentile(50.0).as_millis(), 10);
+    }
+}
@@ -1,26 +1,48 @@
 //! defines a collective [`Syndicate`] of workers.
 //!
-//! this is used for composing groups of workers to perform a load-test.
+//! this is used for composing groups of workers to perform a load-test. each worker connects to
+//! the server, sends it a request, and reads the response. workers may also share a pool of
+//! long-lived connections, multiplexing their requests. a fixed number of virtual users may be
+//! given, in which case each user awaits its response before sending another.
+//!
+//! requests are sent until the total has been reached or the duration has elapsed, after which
+//! in-flight requests may drain for a grace period. a load profile may also be given, ramping the
+//! rate of requests up and down in stages. if the load-tester cannot keep up, or too many
+//! requests are in flight, requests are delayed or skipped according to the [`Backpressure`]
+//! policy.
 
 use {
-    self::gen::Generator,
+    self::gen::{Dispatch, Generator},
     crate::{
         error::Error,
-        worker::{WorkerHandle, WorkerResult},
+        worker::{Pool, Report, Target, Worker, WorkerHandle},
     },
-    futures::{FutureExt, Stream},
+    futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, Stream, StreamExt},
     pin_project::pin_project,
-    std::{collections::VecDeque, marker::PhantomData, pin::Pin, task::Poll},
-    tap::TapFallible,
+    std::{
+        future::Future,
+        marker::PhantomData,
+        pin::Pin,
+        sync::Arc,
+        task::Poll,
+        time::{Duration, Instant},
+    },
+    tap::Pipe,
     tokio::{
         sync::mpsc::{self, Receiver, Sender},
-        task::JoinHandle,
+        task::{AbortHandle, JoinHandle},
+        time::Sleep,
     },
+    tokio_util::sync::CancellationToken,
     tracing::{debug, info},
     url::Host,
 };
 
-pub use self::builder::Builder;
+pub use self::{
+    backpressure::{Backlog, Backpressure},
+    builder::Builder,
+    profile::{Profile, Stage},
+};
 
 /// provides a builder to start a syndicate.
 mod builder;
@@ -28,6 +50,12 @@ mod builder;
 /// provides a generator for spawning workers at regular intervals.
 mod gen;
 
+/// provides load profiles, describing how the rate of requests changes over time.
+mod profile;
+
+/// provides policies for dispatching work when the load-tester cannot keep up.
+mod backpressure;
+
 /// a syndicate is a group of [`Worker`][crate::worker::Worker]s.
 ///
 /// create a new syndicate using [`Syndicate::builder()`].
@@ -41,9 +69,35 @@ pub struct Syndicate {
     /// this will be dropped and set to `None` once the stream is closed.
     rx: Option<Receiver<WorkerHandle>>,
     /// the running workers currently in-flight.
-    workers: VecDeque<WorkerHandle>,
+    ///
+    /// workers are polled concurrently, and their reports are yielded in the order that they
+    /// finish.
+    workers: FuturesUnordered<InFlight>,
+    /// counts the dispatches that were affected by backpressure.
+    backlog: Backlog,
+    /// a token used to cancel in-flight workers.
+    cancel: CancellationToken,
+    /// how long in-flight workers may run once the generator has stopped.
+    grace: Option<Duration>,
+    /// a timer marking the end of the grace period.
+    ///
+    /// this is set once the receiver has been closed.
+    #[pin]
+    drain: Option<Sleep>,
 }
 
+/// a handle used to stop a [`Syndicate`] early, see [`Syndicate::stopper()`].
+#[derive(Clone, Debug)]
+pub struct Stop {
+    /// a handle to the generator spawning work for the syndicate.
+    gen: Arc<AbortHandle>,
+    /// a token used to cancel in-flight workers.
+    cancel: CancellationToken,
+}
+
+/// a worker in flight, which reports a failure if its task could not be joined.
+type InFlight = BoxFuture<'static, Report>;
+
 // === impl Builder ===
 
 impl Builder {
@@ -60,11 +114,55 @@ impl Builder {
             port,
             total,
             rps,
+            connections,
+            tls,
+            timeouts,
+            request,
+            concurrency,
+            think_time,
+            duration,
+            grace,
+            profile,
+            backpressure,
+            max_in_flight,
         } = self;
 
-        let make_fn = move || crate::worker::Worker::spawn(host.clone(), port);
+        // workers either share a pool of connections, or each open their own connection.
+        let target = Target::new(host, port).tls(tls).timeouts(timeouts);
+        let request = Arc::new(request);
+        let run: Box<dyn Fn() -> BoxFuture<'static, Report> + Send> = match connections {
+            Some(size) => {
+                let pool = Pool::new(target, size);
+                Box::new(move || Worker::run_pooled(pool.clone(), Arc::clone(&request)).boxed())
+            }
+            None => Box::new(move || Worker::run(target.clone(), Arc::clone(&request)).boxed()),
+        };
+
+        // spawn a worker each time the generator dispatches work.
+        let cancel = CancellationToken::new();
+        let backlog = Backlog::default();
+        let make_fn = {
+            let cancel = cancel.clone();
+            move |dispatch: Dispatch| -> WorkerHandle {
+                let (stage, intended) = (dispatch.stage(), dispatch.intended());
+                Self::cancellable(run(), cancel.clone())
+                    .map(move |report| Report {
+                        stage,
+                        intended,
+                        ..report
+                    })
+                    .pipe(|worker| dispatch.run(worker, think_time))
+                    .pipe(tokio::spawn)
+            }
+        };
         let (rx, gen) = Generator::new()
             .with_total(total)
+            .with_duration(duration)
+            .with_profile(profile)
+            .with_backpressure(backpressure)
+            .with_max_in_flight(max_in_flight)
+            .with_backlog(backlog.clone())
+            .with_concurrency(concurrency)
             .at_rate_per_second(rps)
             .start(make_fn);
 
@@ -72,29 +170,116 @@ impl Builder {
             gen,
             rx: Some(rx),
             workers: Default::default(),
+            backlog,
+            cancel,
+            grace,
+            drain: None,
         })
     }
+
+    /// wraps a worker, so that it reports a failure if it is cancelled before finishing.
+    fn cancellable(
+        worker: impl Future<Output = Report>,
+        cancel: CancellationToken,
+    ) -> impl Future<Output = Report> {
+        let start = Instant::now();
+        async move {
+            tokio::select! {
+                report = worker => report,
+                () = cancel.cancelled() => Report::cancelled(start),
+            }
+        }
+    }
 }
 
 // === impl Syndicate ===
 
+impl Syndicate {
+    /// returns the [`Backlog`], counting the dispatches that were affected by backpressure.
+    pub fn backlog(&self) -> Backlog {
+        self.backlog.clone()
+    }
+
+    /// returns a [`Stop`] handle, used to stop this syndicate before it has finished.
+    pub fn stopper(&self) -> Stop {
+        Stop {
+            gen: self.gen.abort_handle().pipe(Arc::new),
+            cancel: self.cancel.clone(),
+        }
+    }
+
+    /// returns a future awaiting a worker's report.
+    ///
+    /// if the worker's task panicked or was aborted, a report describing the failure is returned.
+    fn in_flight(worker: WorkerHandle) -> InFlight {
+        let start = Instant::now();
+        worker
+            .map(move |res| res.unwrap_or_else(|err| Report::join_error(start, err)))
+            .boxed()
+    }
+}
+
+// === impl Stop ===
+
+impl Stop {
+    /// stops the syndicate from spawning new workers.
+    ///
+    /// workers that are already in flight may finish within the grace period, see
+    /// [`Builder::grace()`]. the syndicate's stream ends once each of them has been reported.
+    pub fn stop(&self) {
+        info!("stopping syndicate");
+        self.gen.abort();
+    }
+
+    /// stops the syndicate from spawning new workers, cancelling those still in flight after the
+    /// given grace period.
+    ///
+    /// unlike [`Stop::stop()`], this bounds how long in-flight workers may run, even if the
+    /// syndicate was not given a grace period of its own.
+    ///
+    /// # panics
+    ///
+    /// this function panics if called outside of a tokio runtime.
+    pub fn stop_within(&self, grace: Duration) {
+        self.stop();
+        let cancel = self.cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(grace).await;
+            if !cancel.is_cancelled() {
+                info!(?grace, "cancelling in-flight workers");
+                cancel.cancel();
+            }
+        });
+    }
+
+    /// stops the syndicate from spawning new workers, and cancels those still in flight.
+    pub fn cancel(&self) {
+        self.stop();
+        info!("cancelling in-flight workers");
+        self.cancel.cancel();
+    }
+}
+
 /// a syndicate may be treated as an asynchronous stream of worker output.
 impl Stream for Syndicate {
-    type Item = WorkerResult;
+    type Item = Report;
     fn poll_next(
         self: Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
         debug!("polling for next item from workers");
-        let syndicate = self.project();
+        let mut syndicate = self.project();
 
         // first, poll the channel to see if we have received any new jobs.
-        if let Some(rx) = syndicate.rx {
+        //
+        // NB: the channel is polled until it is pending, so that we are woken for new jobs, and
+        // notice when the channel has been closed.
+        while let Some(rx) = syndicate.rx.as_mut() {
             match rx.poll_recv(cx) {
-                Poll::Pending => {}
+                Poll::Pending => break,
                 Poll::Ready(Some(worker)) => {
                     debug!("a new worker has joined");
-                    syndicate.workers.push_back(worker);
+                    syndicate.workers.push(Syndicate::in_flight(worker));
                 }
                 Poll::Ready(None) => {
                     // the channel is closed. we can drop it now.
@@ -104,17 +289,30 @@ impl Stream for Syndicate {
             }
         }
 
-        // next, poll our queue of workers.
-        if let Some(worker) = syndicate.workers.front_mut() {
-            match worker.poll_unpin(cx) {
-                Poll::Ready(res) => {
-                    // the worker is finished! be sure to remove it from the queue.
+        // once the receiver is closed, in-flight workers may drain for the grace period.
+        if let (None, Some(grace)) = (syndicate.rx.as_ref(), syndicate.grace) {
+            if syndicate.drain.is_none() {
+                debug!(?grace, "waiting for in-flight workers to drain");
+                syndicate.drain.set(Some(tokio::time::sleep(*grace)));
+            }
+            if let Some(drain) = syndicate.drain.as_mut().as_pin_mut() {
+                if drain.poll(cx).is_ready() && !syndicate.cancel.is_cancelled() {
+                    info!(in_flight = %syndicate.workers.len(), "cancelling in-flight workers");
+                    syndicate.cancel.cancel();
+                }
+            }
+        }
+
+        // next, poll each of our in-flight workers, yielding the first to finish.
+        //
+        // NB: an empty set of workers yields `None`, so it is only polled if it holds workers.
+        if !syndicate.workers.is_empty() {
+            match syndicate.workers.poll_next_unpin(cx) {
+                Poll::Ready(Some(report)) => {
                     debug!("a worker has finished");
-                    let _ = syndicate.workers.pop_front();
-                    let res = res.map(Some).unwrap(/*TODO handle JoinError*/);
-                    return Poll::Ready(res);
+                    return Poll::Ready(Some(report));
                 }
-                Poll::Pending => return Poll::Pending,
+                Poll::Ready(None) | Poll::Pending => return Poll::Pending,
             }
         }
 
@@ -128,3 +326,24 @@ impl Stream for Syndicate {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::worker::Failure};
+
+    #[tokio::test]
+    async fn panicked_workers_are_reported() {
+        let worker = tokio::spawn(async { panic!("a worker panicked") });
+        let report = Syndicate::in_flight(worker).await;
+        assert!(!report.success);
+        assert_eq!(report.failure, Some(Failure::Panicked));
+    }
+
+    #[tokio::test]
+    async fn aborted_workers_are_reported() {
+        let worker = tokio::spawn(futures::future::pending());
+        worker.abort();
+        let report = Syndicate::in_flight(worker).await;
+        assert_eq!(report.failure, Some(Failure::Cancelled));
+    }
+}
@@ -0,0 +1,68 @@
+use {
+    super::*,
+    std::sync::atomic::{AtomicU64, Ordering},
+};
+
+/// a policy for dispatching work when the load-tester cannot keep up.
+///
+/// backpressure applies when the maximum number of workers are already in flight, see
+/// [`Builder::max_in_flight()`], or when the [`Syndicate`] has fallen behind on collecting newly
+/// spawned workers.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum Backpressure {
+    /// wait until the work can be dispatched, delaying it.
+    #[default]
+    Block,
+    /// skip the work, and continue on schedule.
+    Drop,
+}
+
+/// counts the dispatches, including those that were affected by [`Backpressure`].
+///
+/// this may be cheaply cloned, see [`Syndicate::backlog()`].
+#[derive(Clone, Debug, Default)]
+pub struct Backlog {
+    /// the number of workers that were dispatched.
+    dispatched: Arc<AtomicU64>,
+    /// the number of dispatches that were skipped.
+    skipped: Arc<AtomicU64>,
+    /// the number of dispatches that were delayed.
+    delayed: Arc<AtomicU64>,
+}
+
+// === impl Backlog ===
+
+impl Backlog {
+    /// returns the number of workers that were dispatched.
+    ///
+    /// workers that have been dispatched, but have not yet been yielded by the [`Syndicate`], are
+    /// in flight.
+    pub fn dispatched(&self) -> u64 {
+        self.dispatched.load(Ordering::Relaxed)
+    }
+
+    /// returns the number of dispatches that were skipped.
+    pub fn skipped(&self) -> u64 {
+        self.skipped.load(Ordering::Relaxed)
+    }
+
+    /// returns the number of dispatches that were delayed.
+    pub fn delayed(&self) -> u64 {
+        self.delayed.load(Ordering::Relaxed)
+    }
+
+    /// records that a worker was dispatched.
+    pub(super) fn dispatch(&self) {
+        self.dispatched.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// records that a dispatch was skipped.
+    pub(super) fn skip(&self) {
+        self.skipped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// records that a dispatch was delayed.
+    pub(super) fn delay(&self) {
+        self.delayed.fetch_add(1, Ordering::Relaxed);
+    }
+}
@@ -1,4 +1,11 @@
-use super::*;
+use {
+    super::*,
+    crate::{
+        tls,
+        worker::{Template, Timeouts},
+    },
+    std::{num::NonZeroU32, time::Duration},
+};
 
 /// a [`Syndicate`] builder.
 #[allow(unused)]
@@ -7,6 +14,17 @@ pub struct Builder {
     pub(super) port: u16,
     pub(super) total: Option<u32>,
     pub(super) rps: Option<u32>,
+    pub(super) connections: Option<NonZeroU32>,
+    pub(super) tls: Option<tls::Connector>,
+    pub(super) timeouts: Timeouts,
+    pub(super) request: Template,
+    pub(super) concurrency: Option<NonZeroU32>,
+    pub(super) think_time: Option<Duration>,
+    pub(super) duration: Option<Duration>,
+    pub(super) grace: Option<Duration>,
+    pub(super) profile: Option<Profile>,
+    pub(super) backpressure: Backpressure,
+    pub(super) max_in_flight: Option<NonZeroU32>,
 }
 
 // === impl Syndicate ===
@@ -19,6 +37,17 @@ impl Syndicate {
             port,
             total: None,
             rps: None,
+            connections: None,
+            tls: None,
+            timeouts: Timeouts::default(),
+            request: Template::default(),
+            concurrency: None,
+            think_time: None,
+            duration: None,
+            grace: None,
+            profile: None,
+            backpressure: Backpressure::default(),
+            max_in_flight: None,
         }
     }
 
@@ -40,4 +69,100 @@ impl Builder {
     pub fn rps(self, rps: Option<u32>) -> Self {
         Self { rps, ..self }
     }
+
+    /// sets the number of connections to share among requests.
+    ///
+    /// if this is `None`, each request will open its own connection to the server.
+    pub fn connections(self, connections: Option<NonZeroU32>) -> Self {
+        Self {
+            connections,
+            ..self
+        }
+    }
+
+    /// sets the tls connector used to connect to the server.
+    ///
+    /// if this is `None`, requests will be sent over cleartext http/2.
+    pub fn tls(self, tls: Option<tls::Connector>) -> Self {
+        Self { tls, ..self }
+    }
+
+    /// sets the timeouts bounding each phase of a worker.
+    ///
+    /// workers that time out report a
+    /// [`Failure::Timeout`][crate::worker::Failure::Timeout] failure, marked with the phase that
+    /// timed out, or a [`Failure::TotalTimeout`][crate::worker::Failure::TotalTimeout] failure.
+    /// see [`Timeouts`] for more information.
+    pub fn timeouts(self, timeouts: Timeouts) -> Self {
+        Self { timeouts, ..self }
+    }
+
+    /// sets the request that each worker sends to the server.
+    pub fn request(self, request: Template) -> Self {
+        Self { request, ..self }
+    }
+
+    /// sets the number of virtual users sending requests to the server.
+    ///
+    /// if this is `Some(n)`, the load-test follows a closed model: each of the `n` users sends a
+    /// request, awaits the response, and then sends another. otherwise, requests are sent at a
+    /// fixed rate, regardless of how long the server takes to respond.
+    pub fn concurrency(self, concurrency: Option<NonZeroU32>) -> Self {
+        Self {
+            concurrency,
+            ..self
+        }
+    }
+
+    /// sets how long each virtual user waits after receiving a response.
+    ///
+    /// this has no effect unless [`Builder::concurrency()`] is set.
+    pub fn think_time(self, think_time: Option<Duration>) -> Self {
+        Self { think_time, ..self }
+    }
+
+    /// sets how long to send requests for.
+    ///
+    /// if a total is also set, the syndicate stops sending requests once either limit is reached.
+    pub fn duration(self, duration: Option<Duration>) -> Self {
+        Self { duration, ..self }
+    }
+
+    /// sets how long in-flight workers may run once the syndicate has stopped sending requests.
+    ///
+    /// workers that have not finished by the end of the grace period are cancelled, and report a
+    /// [`Failure::Cancelled`][crate::worker::Failure::Cancelled] failure. if this is `None`,
+    /// in-flight workers are always allowed to finish.
+    pub fn grace(self, grace: Option<Duration>) -> Self {
+        Self { grace, ..self }
+    }
+
+    /// sets the load profile, describing how the rate of requests changes over time.
+    ///
+    /// if this is `Some(_)`, the profile takes precedence over [`Builder::rps()`]. the syndicate
+    /// stops sending requests once the last stage of the profile has finished.
+    pub fn profile(self, profile: Option<Profile>) -> Self {
+        Self { profile, ..self }
+    }
+
+    /// sets the policy applied when a worker cannot be spawned immediately.
+    ///
+    /// see [`Backpressure`] for more information.
+    pub fn backpressure(self, backpressure: Backpressure) -> Self {
+        Self {
+            backpressure,
+            ..self
+        }
+    }
+
+    /// sets the maximum number of workers that may be in flight at once.
+    ///
+    /// once this many workers are in flight, the [`Backpressure`] policy decides whether to wait
+    /// for one to finish, or to skip spawning another worker.
+    pub fn max_in_flight(self, max_in_flight: Option<NonZeroU32>) -> Self {
+        Self {
+            max_in_flight,
+            ..self
+        }
+    }
 }
@@ -1,4 +1,13 @@
-use {super::*, std::time::Duration, tracing::instrument};
+use {
+    super::*,
+    std::{num::NonZeroU32, time::Duration},
+    tap::{Pipe, Tap},
+    tokio::{
+        sync::{mpsc::error::TrySendError, OwnedSemaphorePermit, Semaphore},
+        time::Instant,
+    },
+    tracing::{instrument, trace},
+};
 
 /// a generator periodically yields values of type `T`.
 pub(super) struct Generator<T> {
@@ -6,10 +15,55 @@ pub(super) struct Generator<T> {
     total: Option<u32>,
     /// the amount of time to pause between yielding values.
     pause: Option<Duration>,
+    /// the number of dispatches that may be outstanding at once.
+    ///
+    /// if this is `Some(n)`, the generator models `n` virtual users. see [`Dispatch`].
+    concurrency: Option<NonZeroU32>,
+    /// how long to yield values for.
+    duration: Option<Duration>,
+    /// a load profile, scheduling when values are yielded.
+    ///
+    /// if this is `Some(_)`, it takes precedence over `pause`.
+    profile: Option<Profile>,
+    /// the policy applied when a value cannot be dispatched immediately.
+    backpressure: Backpressure,
+    /// the number of dispatches that may be in flight at once.
+    ///
+    /// unlike `concurrency`, a dispatch is no longer in flight once its future has completed.
+    max_in_flight: Option<NonZeroU32>,
+    /// counts the dispatches that were affected by backpressure.
+    backlog: Backlog,
     /// the kinds of values that this generator yields.
     _yields: PhantomData<T>,
 }
 
+/// a dispatch, given to a generator's function each time it yields a value.
+///
+/// if the generator is limiting its concurrency, this holds a permit belonging to one of its
+/// virtual users. the generator will not dispatch more work for that user until it is dropped.
+/// similarly, if the generator is limiting the number of dispatches in flight, this holds a slot
+/// until it has been run, see [`Dispatch::run()`].
+pub(super) struct Dispatch {
+    /// a permit held by a virtual user.
+    permit: Option<OwnedSemaphorePermit>,
+    /// a permit held while this dispatch is in flight.
+    slot: Option<OwnedSemaphorePermit>,
+    /// the index of the load profile stage this was dispatched in.
+    stage: Option<usize>,
+    /// when this was intended to be dispatched.
+    intended: std::time::Instant,
+}
+
+/// the outcome of attempting to dispatch a value.
+enum Outcome {
+    /// the value was dispatched.
+    Dispatched,
+    /// the value was skipped, due to backpressure.
+    Skipped,
+    /// the channel was closed.
+    Closed,
+}
+
 // === impl Generator ===
 
 impl<T> Generator<T>
@@ -23,6 +77,12 @@ where
         Self {
             total: None,
             pause: None,
+            concurrency: None,
+            duration: None,
+            profile: None,
+            backpressure: Backpressure::default(),
+            max_in_flight: None,
+            backlog: Backlog::default(),
             _yields: PhantomData,
         }
     }
@@ -37,6 +97,50 @@ where
         Self { pause, ..self }
     }
 
+    /// sets the number of dispatches that may be outstanding at once.
+    pub fn with_concurrency(self, concurrency: Option<NonZeroU32>) -> Self {
+        Self {
+            concurrency,
+            ..self
+        }
+    }
+
+    /// sets how long to yield values for.
+    ///
+    /// the generator stops once this much time has elapsed, or it has yielded its total number
+    /// of values, whichever comes first.
+    pub fn with_duration(self, duration: Option<Duration>) -> Self {
+        Self { duration, ..self }
+    }
+
+    /// yields values according to the given load profile.
+    ///
+    /// the generator stops once the last stage of the profile has finished.
+    pub fn with_profile(self, profile: Option<Profile>) -> Self {
+        Self { profile, ..self }
+    }
+
+    /// sets the policy applied when a value cannot be dispatched immediately.
+    pub fn with_backpressure(self, backpressure: Backpressure) -> Self {
+        Self {
+            backpressure,
+            ..self
+        }
+    }
+
+    /// sets the number of dispatches that may be in flight at once.
+    pub fn with_max_in_flight(self, max_in_flight: Option<NonZeroU32>) -> Self {
+        Self {
+            max_in_flight,
+            ..self
+        }
+    }
+
+    /// sets the [`Backlog`] used to count dispatches affected by backpressure.
+    pub fn with_backlog(self, backlog: Backlog) -> Self {
+        Self { backlog, ..self }
+    }
+
     /// generates values at `rate`-per-second.
     ///
     /// this is a convenience method abstracting over `with_pause()`.
@@ -57,7 +161,7 @@ where
     #[instrument(skip_all)]
     pub fn start<F>(self, f: F) -> (Receiver<T>, JoinHandle<()>)
     where
-        F: Fn() -> T,
+        F: Fn(Dispatch) -> T,
         F: Send + 'static,
     {
         debug!("spawning generator worker");
@@ -71,58 +175,202 @@ where
     #[instrument(skip_all)]
     async fn run<F>(self, f: F, tx: Sender<T>)
     where
-        F: Fn() -> T,
+        F: Fn(Dispatch) -> T,
     {
-        let Self { total, pause, .. } = self;
-        let mut remaining = total; // how many items are remaining?
-        let mut yielded = 0; // how many items have we yielded?
-        debug!("generator is running");
-
-        loop {
-            if let Some(rem) = remaining.as_mut() {
-                if *rem > 0 {
-                    // decrement how many items are remaining, if applicable.
-                    debug!(%yielded, ?remaining, "generator is going to yield a value");
-                    if let Some(rem) = remaining.as_mut() {
-                        *rem -= 1;
+        let Self {
+            total,
+            pause,
+            concurrency,
+            duration,
+            profile,
+            backpressure,
+            max_in_flight,
+            backlog,
+            ..
+        } = self;
+        let generate = async move {
+            let mut remaining = total; // how many items are remaining?
+            let mut yielded = 0; // how many items have we yielded?
+            let mut attempts = 0; // how many items have we attempted to yield?
+            let slots = max_in_flight.map(|n| Semaphore::new(n.get() as usize).pipe(Arc::new));
+            let users = concurrency.map(|n| Semaphore::new(n.get() as usize).pipe(Arc::new));
+            let start = Instant::now();
+            debug!("generator is running");
+
+            loop {
+                if let Some(rem) = remaining.as_mut() {
+                    if *rem > 0 {
+                        // decrement how many items are remaining, if applicable.
+                        debug!(%yielded, ?remaining, "generator is going to yield a value");
+                        if let Some(rem) = remaining.as_mut() {
+                            *rem -= 1;
+                        }
+                    } else {
+                        // stop yielding items when we reach the total.
+                        debug!("generator has finished yielding items");
+                        break;
                     }
-                } else {
-                    // stop yielding items when we reach the total.
-                    debug!("generator has finished yielding items");
-                    break;
                 }
-            }
 
-            // yield a value and send it through the channel.
-            //
-            // TODO: backpressure / timeouts would be nice to have here.
-            let t = f();
-            match tx
-                .try_send(t)
-                .tap_ok(|_| debug!(%yielded, ?remaining, "generator yielded a value"))
-            {
-                Ok(()) => yielded += 1,
-                Err(mpsc::error::TrySendError::Full(_)) => {
-                    // TODO: gracefully handle this condition.
-                    panic!("generator channel is full");
+                // if we are following a load profile or a fixed rate, wait until the next value
+                // is scheduled. values are scheduled relative to when the generator started, so
+                // that delays in yielding one value do not postpone the rest.
+                let (stage, scheduled) = match (profile.as_ref(), pause) {
+                    (Some(profile), _) => match profile.schedule(attempts) {
+                        Some((stage, offset)) => (Some(stage), Some(start + offset)),
+                        None => {
+                            debug!("generator has finished its load profile");
+                            break;
+                        }
+                    },
+                    (None, Some(pause)) => (None, Some(start + pause.mul_f64(attempts as f64))),
+                    (None, None) => (None, None),
+                };
+                if let Some(scheduled) = scheduled {
+                    trace!(?stage, "generator is waiting for its schedule");
+                    tokio::time::sleep_until(scheduled).await;
                 }
-                Err(mpsc::error::TrySendError::Closed(_)) => {
-                    debug!("generator channel has been closed. stopping...");
-                    break;
+                attempts += 1;
+
+                // if we are modeling virtual users, wait for one of them to become idle.
+                let permit = match users.as_ref() {
+                    Some(users) => Arc::clone(users)
+                        .acquire_owned()
+                        .tap(|_| trace!("generator is waiting for an idle user"))
+                        .await
+                        .expect("semaphore is never closed")
+                        .pipe(Some),
+                    None => None,
+                };
+
+                // values without a schedule are intended to be yielded as soon as possible.
+                let intended = scheduled.unwrap_or_else(Instant::now).into_std();
+
+                // yield a value and send it through the channel, applying backpressure if the
+                // maximum number of values are in flight, or the channel is full.
+                let outcome = 'dispatch: {
+                    let mut delayed = false;
+                    let slot = match slots.as_ref().map(|s| Arc::clone(s).try_acquire_owned()) {
+                        None => None,
+                        Some(Ok(slot)) => Some(slot),
+                        Some(Err(_)) if backpressure == Backpressure::Drop => {
+                            break 'dispatch Outcome::Skipped;
+                        }
+                        Some(Err(_)) => {
+                            trace!("generator is waiting for an in-flight value to finish");
+                            delayed = true;
+                            slots
+                                .as_ref()
+                                .map(Arc::clone)
+                                .expect("slots exist")
+                                .acquire_owned()
+                                .await
+                                .expect("semaphore is never closed")
+                                .pipe(Some)
+                        }
+                    };
+                    let reserved = match tx.try_reserve() {
+                        Ok(reserved) => reserved,
+                        Err(TrySendError::Full(())) if backpressure == Backpressure::Drop => {
+                            break 'dispatch Outcome::Skipped;
+                        }
+                        Err(TrySendError::Full(())) => {
+                            trace!("generator is waiting for the channel to have capacity");
+                            delayed = true;
+                            match tx.reserve().await {
+                                Ok(reserved) => reserved,
+                                Err(_) => break 'dispatch Outcome::Closed,
+                            }
+                        }
+                        Err(TrySendError::Closed(())) => break 'dispatch Outcome::Closed,
+                    };
+                    if delayed {
+                        backlog.delay();
+                    }
+                    reserved.send(f(Dispatch {
+                        permit,
+                        slot,
+                        stage,
+                        intended,
+                    }));
+                    Outcome::Dispatched
+                };
+
+                match outcome {
+                    Outcome::Dispatched => {
+                        yielded += 1;
+                        backlog.dispatch();
+                        debug!(%yielded, ?remaining, "generator yielded a value");
+                    }
+                    Outcome::Skipped => {
+                        backlog.skip();
+                        debug!(?remaining, "generator skipped a value");
+                    }
+                    Outcome::Closed => {
+                        debug!("generator channel has been closed. stopping...");
+                        break;
+                    }
                 }
             }
+        };
 
-            // wait for the proscribed amount of time before continuing.
-            if let Some(pause) = pause {
-                debug!("generator is pausing");
-                tokio::time::sleep(pause).await;
+        // stop generating values once the deadline has passed, if there is one.
+        match duration {
+            Some(duration) => {
+                if tokio::time::timeout(duration, generate).await.is_err() {
+                    debug!(?duration, "generator has reached its deadline");
+                }
             }
+            None => generate.await,
         }
 
         debug!("generator has finished yielding values");
     }
 }
 
+// === impl Dispatch ===
+
+impl Dispatch {
+    /// returns the index of the load profile stage this was dispatched in.
+    pub fn stage(&self) -> Option<usize> {
+        self.stage
+    }
+
+    /// returns when this was intended to be dispatched.
+    ///
+    /// this may be earlier than when it was actually dispatched, if the generator was delayed
+    /// by backpressure, or by the scheduler.
+    pub fn intended(&self) -> std::time::Instant {
+        self.intended
+    }
+
+    /// runs the given future, returning its output.
+    ///
+    /// the dispatch is no longer in flight once the future completes. if this dispatch belongs to
+    /// a virtual user, the user will remain busy for the given think time afterwards.
+    pub async fn run<F>(self, fut: F, think_time: Option<Duration>) -> F::Output
+    where
+        F: std::future::Future,
+    {
+        let Self { permit, slot, .. } = self;
+        let output = fut.await;
+        drop(slot);
+
+        // release the permit once the user has finished thinking.
+        match (permit, think_time) {
+            (Some(permit), Some(think_time)) => {
+                tokio::spawn(async move {
+                    tokio::time::sleep(think_time).await;
+                    drop(permit);
+                });
+            }
+            (permit, _) => drop(permit),
+        }
+
+        output
+    }
+}
+
 #[cfg(test)]
 mod generator_total_unit_tests {
     use std::ops::RangeInclusive;
@@ -130,7 +378,7 @@ mod generator_total_unit_tests {
     use super::*;
 
     /// for tests, we yield nothing.
-    fn job() {}
+    fn job(_: Dispatch) {}
 
     #[tokio::test]
     async fn generator_can_yield_a_value() {
@@ -186,4 +434,171 @@ mod generator_total_unit_tests {
             found: {cnt}\n"
         );
     }
+
+    /// show that a generator stops yielding work once its duration has elapsed.
+    #[tokio::test]
+    async fn generator_stops_at_deadline() {
+        const DURATION: Duration = Duration::from_millis(100);
+        let start = tokio::time::Instant::now();
+        let (mut rx, _gen) = Generator::new()
+            .with_duration(Some(DURATION))
+            .at_rate_per_second(Some(100))
+            .start(job);
+
+        // drain the channel, which is closed once the generator stops.
+        let mut cnt = 0;
+        while rx.recv().await.is_some() {
+            cnt += 1;
+        }
+
+        assert!(start.elapsed() >= DURATION, "generator stopped early");
+        assert!(cnt > 0, "generator should have yielded values");
+    }
+
+    /// show that a generator follows its load profile, and stops once it has finished.
+    #[tokio::test]
+    async fn generator_follows_profile() {
+        let profile = ["0s:100", "200ms:100", "0s:0", "100ms:0"]
+            .into_iter()
+            .map(|stage| stage.parse().expect("stage is valid"))
+            .collect::<Profile>();
+        let start = tokio::time::Instant::now();
+        let (mut rx, _gen) = Generator::new()
+            .with_profile(Some(profile))
+            .start(|dispatch| dispatch.stage());
+
+        let mut stages = Vec::new();
+        while let Some(stage) = rx.recv().await {
+            stages.push(stage);
+        }
+
+        assert_eq!(
+            stages.len(),
+            20,
+            "100 rps over 200ms should yield 20 values"
+        );
+        assert!(stages.iter().all(|stage| *stage == Some(1)));
+        assert!(
+            start.elapsed() < Duration::from_millis(300),
+            "idle stages yield nothing"
+        );
+    }
+
+    /// show that a generator stops at whichever limit it reaches first.
+    #[tokio::test]
+    async fn generator_stops_at_total_before_deadline() {
+        let (mut rx, _gen) = Generator::new()
+            .with_total(Some(2))
+            .with_duration(Some(Duration::from_secs(60)))
+            .start(job);
+        assert!(rx.recv().await.is_some());
+        assert!(rx.recv().await.is_some());
+        assert!(rx.recv().await.is_none());
+    }
+}
+
+#[cfg(test)]
+mod generator_concurrency_unit_tests {
+    use super::*;
+
+    /// show that a generator does not dispatch more work than it has virtual users.
+    #[tokio::test]
+    async fn generator_waits_for_idle_users() {
+        const USERS: NonZeroU32 = NonZeroU32::new(2).unwrap();
+        let (mut rx, _gen) = Generator::new()
+            .with_total(Some(4))
+            .with_concurrency(Some(USERS))
+            .start(|dispatch| dispatch);
+
+        // the first two dispatches are yielded, but the third must wait for a user.
+        let first = rx.recv().await.expect("a dispatch is yielded");
+        let _second = rx.recv().await.expect("a dispatch is yielded");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(rx.is_empty(), "generator should wait for an idle user");
+
+        // once a user is idle, more work is dispatched.
+        drop(first);
+        assert!(rx.recv().await.is_some());
+    }
+
+    /// show that a virtual user remains busy while thinking.
+    #[tokio::test]
+    async fn users_remain_busy_while_thinking() {
+        const USERS: NonZeroU32 = NonZeroU32::new(1).unwrap();
+        const THINK: Duration = Duration::from_millis(100);
+        let (mut rx, _gen) = Generator::new()
+            .with_total(Some(2))
+            .with_concurrency(Some(USERS))
+            .start(|dispatch| dispatch);
+
+        let first = rx.recv().await.expect("a dispatch is yielded");
+        let start = tokio::time::Instant::now();
+        first.run(std::future::ready(()), Some(THINK)).await;
+        assert!(rx.recv().await.is_some());
+        assert!(
+            start.elapsed() >= THINK,
+            "user should think before more work"
+        );
+    }
+}
+
+#[cfg(test)]
+mod generator_backpressure_unit_tests {
+    use super::*;
+
+    /// show that a generator skips values when too many are in flight, if told to drop them.
+    #[tokio::test]
+    async fn generator_drops_values_beyond_max_in_flight() {
+        let backlog = Backlog::default();
+        let (mut rx, _gen) = Generator::new()
+            .with_total(Some(3))
+            .with_max_in_flight(NonZeroU32::new(1))
+            .with_backpressure(Backpressure::Drop)
+            .with_backlog(backlog.clone())
+            .start(|dispatch| dispatch);
+
+        // the first dispatch remains in flight, so the others are skipped.
+        let _first = rx.recv().await.expect("a dispatch is yielded");
+        assert!(rx.recv().await.is_none());
+        assert_eq!((backlog.skipped(), backlog.delayed()), (2, 0));
+        assert_eq!(backlog.dispatched(), 1);
+    }
+
+    /// show that a generator waits for a value to finish when too many are in flight.
+    #[tokio::test]
+    async fn generator_blocks_values_beyond_max_in_flight() {
+        let backlog = Backlog::default();
+        let (mut rx, _gen) = Generator::new()
+            .with_total(Some(2))
+            .with_max_in_flight(NonZeroU32::new(1))
+            .with_backlog(backlog.clone())
+            .start(|dispatch| dispatch);
+
+        let first = rx.recv().await.expect("a dispatch is yielded");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(
+            rx.is_empty(),
+            "generator should wait for a dispatch to finish"
+        );
+
+        first.run(std::future::ready(()), None).await;
+        assert!(rx.recv().await.is_some());
+        assert_eq!((backlog.skipped(), backlog.delayed()), (0, 1));
+    }
+
+    /// show that a generator does not panic when its channel is full.
+    #[tokio::test]
+    async fn generator_drops_values_when_channel_is_full() {
+        const TOTAL: usize = Generator::<()>::CHANNEL_SIZE + 2;
+        let backlog = Backlog::default();
+        let (rx, gen) = Generator::new()
+            .with_total(Some(TOTAL as u32))
+            .with_backpressure(Backpressure::Drop)
+            .with_backlog(backlog.clone())
+            .start(|_| ());
+
+        gen.await.expect("generator should not panic");
+        assert_eq!(rx.len(), Generator::<()>::CHANNEL_SIZE);
+        assert_eq!(backlog.skipped(), 2);
+    }
 }
@@ -0,0 +1,201 @@
+use {
+    super::*,
+    std::{path::Path, str::FromStr},
+};
+
+/// a load profile, describing how the rate of requests changes over time.
+///
+/// a profile is a sequence of [`Stage`]s. each stage ramps the rate linearly, from the target of
+/// the previous stage to its own target, over the course of its duration. the first stage ramps
+/// up from zero. the load-test ends once the last stage has finished.
+///
+/// for example, `30s:500, 2m:500, 0s:2000, 10s:2000, 30s:0` ramps up to 500 requests per
+/// second over 30 seconds, holds that rate for 2 minutes, spikes to 2000 requests per second for
+/// 10 seconds, and then ramps down.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Profile {
+    stages: Vec<Stage>,
+}
+
+/// a stage of a load [`Profile`].
+///
+/// stages are written as `DURATION:RATE`, e.g. `30s:500`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Stage {
+    /// how long this stage lasts.
+    pub duration: Duration,
+    /// the rate of requests per-second reached by the end of this stage.
+    pub target: u32,
+}
+
+// === impl Profile ===
+
+impl Profile {
+    /// returns a new profile, consisting of the given stages.
+    pub fn new(stages: Vec<Stage>) -> Self {
+        Self { stages }
+    }
+
+    /// reads a profile from the given path.
+    ///
+    /// the file should contain one stage per line. blank lines, and lines beginning with `#`, are
+    /// ignored.
+    pub fn read(path: &Path) -> Result<Self, Error> {
+        std::fs::read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(Stage::from_str)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self::new)
+    }
+
+    /// returns the stages of this profile.
+    pub fn stages(&self) -> &[Stage] {
+        &self.stages
+    }
+
+    /// returns the total duration of this profile.
+    pub fn duration(&self) -> Duration {
+        self.stages.iter().map(|stage| stage.duration).sum()
+    }
+
+    /// returns the rate of requests per-second at the given offset, and the index of its stage.
+    ///
+    /// returns `None` if the profile has finished.
+    pub fn rate_at(&self, offset: Duration) -> Option<(usize, f64)> {
+        let mut start = Duration::ZERO;
+        let mut from = 0.0;
+        for (index, &Stage { duration, target }) in self.stages.iter().enumerate() {
+            let to = f64::from(target);
+            let end = start + duration;
+            if offset < end {
+                let progress = (offset - start).as_secs_f64() / duration.as_secs_f64();
+                return Some((index, from + (to - from) * progress));
+            }
+            (start, from) = (end, to);
+        }
+
+        None
+    }
+
+    /// returns when the `n`-th request should be sent, and the index of its stage.
+    ///
+    /// the offset is relative to the start of the profile. requests are scheduled so that the
+    /// number sent by any point in time matches the area beneath the profile's rate curve.
+    /// returns `None` if the request falls after the end of the profile.
+    pub(super) fn schedule(&self, n: u64) -> Option<(usize, Duration)> {
+        let mut start = Duration::ZERO;
+        let mut from = 0.0;
+        let mut sent = 0.0; // how many requests were sent before this stage?
+        for (index, &Stage { duration, target }) in self.stages.iter().enumerate() {
+            let (secs, to) = (duration.as_secs_f64(), f64::from(target));
+            let count = (from + to) / 2.0 * secs; // how many requests are sent in this stage?
+            let remaining = n as f64 - sent;
+            if remaining < count {
+                // solve `a·t² + b·t = remaining` for `t`, where the rate is `b + 2a·t`.
+                let (a, b) = ((to - from) / (2.0 * secs), from);
+                let t = if a.abs() < f64::EPSILON {
+                    remaining / b
+                } else {
+                    (-b + (b * b + 4.0 * a * remaining).sqrt()) / (2.0 * a)
+                };
+                let offset = start + Duration::from_secs_f64(t.clamp(0.0, secs));
+                return Some((index, offset));
+            }
+            (start, from, sent) = (start + duration, to, sent + count);
+        }
+
+        None
+    }
+}
+
+impl FromIterator<Stage> for Profile {
+    fn from_iter<I: IntoIterator<Item = Stage>>(iter: I) -> Self {
+        iter.into_iter().collect::<Vec<_>>().pipe(Self::new)
+    }
+}
+
+// === impl Stage ===
+
+impl FromStr for Stage {
+    type Err = Error;
+    fn from_str(stage: &str) -> Result<Self, Self::Err> {
+        let (duration, target) = stage
+            .split_once(':')
+            .ok_or("stage must be written as `DURATION:RATE`, e.g. `30s:500`")?;
+        let duration = humantime::parse_duration(duration.trim())?;
+        let target = target.trim().parse()?;
+        Ok(Self { duration, target })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ramp up to 10 rps over 2s, and then hold that rate for 1s.
+    fn ramp_and_hold() -> Profile {
+        ["2s:10", "1s:10"]
+            .into_iter()
+            .map(Stage::from_str)
+            .collect::<Result<_, _>>()
+            .expect("stages are valid")
+    }
+
+    #[test]
+    fn stages_can_be_parsed() -> Result<(), Error> {
+        assert_eq!(
+            "30s:500".parse::<Stage>()?,
+            Stage {
+                duration: Duration::from_secs(30),
+                target: 500
+            }
+        );
+        "30s".parse::<Stage>().unwrap_err();
+        "30s:fast".parse::<Stage>().unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn rate_is_interpolated() {
+        let profile = ramp_and_hold();
+        assert_eq!(profile.duration(), Duration::from_secs(3));
+        assert_eq!(profile.rate_at(Duration::ZERO), Some((0, 0.0)));
+        assert_eq!(profile.rate_at(Duration::from_secs(1)), Some((0, 5.0)));
+        assert_eq!(
+            profile.rate_at(Duration::from_millis(2500)),
+            Some((1, 10.0))
+        );
+        assert_eq!(profile.rate_at(Duration::from_secs(3)), None);
+    }
+
+    #[test]
+    fn requests_are_scheduled_beneath_the_rate_curve() {
+        let profile = ramp_and_hold();
+
+        // the ramp sends 10 requests, and the plateau sends another 10.
+        let schedule = (0..).map_while(|n| profile.schedule(n)).collect::<Vec<_>>();
+        assert_eq!(schedule.len(), 20);
+        assert_eq!(schedule.iter().filter(|(stage, _)| *stage == 0).count(), 10);
+
+        // during the ramp, the area beneath the curve is `2.5·t²`, so 10 requests precede 2s.
+        assert_eq!(schedule[0], (0, Duration::ZERO));
+        assert_eq!(schedule[10], (1, Duration::from_secs(2)));
+        assert_eq!(schedule[15], (1, Duration::from_millis(2500)));
+
+        // requests are scheduled in order.
+        assert!(schedule.windows(2).all(|w| w[0].1 <= w[1].1));
+    }
+
+    #[test]
+    fn instantaneous_stages_jump_to_their_target() {
+        let profile: Profile = ["0s:100", "1s:100"]
+            .into_iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        let schedule = (0..).map_while(|n| profile.schedule(n)).collect::<Vec<_>>();
+        assert_eq!(schedule.len(), 100);
+        assert_eq!(schedule[50], (1, Duration::from_millis(500)));
+    }
+}
@@ -0,0 +1,383 @@
+//! thresholds that a load-test's results are checked against.
+//!
+//! a [`Threshold`] is written as a metric, a comparison, and a limit, e.g. `p99<250ms`,
+//! `success_rate>=99.9`, or `rps>=1000`. see [`Threshold::check()`].
+//!
+//! latency thresholds cannot be evaluated if no successful responses were received, e.g. if every
+//! connection was refused. such thresholds fail, rather than passing vacuously.
+
+use {
+    crate::{error::Error, summary::Summary},
+    std::{fmt, str::FromStr, time::Duration},
+    tap::Pipe,
+};
+
+/// a threshold that a [`Summary`] is checked against.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Threshold {
+    metric: Metric,
+    comparison: Comparison,
+    limit: Value,
+}
+
+/// the outcome of checking a [`Summary`] against a [`Threshold`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Outcome {
+    /// the threshold that was checked.
+    pub threshold: Threshold,
+    /// the value that was observed.
+    ///
+    /// this is `None` if the metric could not be evaluated, in which case the threshold failed.
+    pub observed: Option<Value>,
+    /// true if the observed value satisfied the threshold.
+    pub passed: bool,
+}
+
+/// a metric of a [`Summary`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Metric {
+    /// the worker duration at a percentile, written as e.g. `p99` or `p99.9`.
+    Percentile(f64),
+    /// the mean worker duration, written as `mean`.
+    Mean,
+    /// the maximum worker duration, written as `max`.
+    Max,
+    /// the success rate, as a percentage, written as `success_rate`.
+    SuccessRate,
+    /// the number of workers that finished per second, written as `rps`.
+    Rps,
+    /// the number of workers that failed, written as `failures`.
+    Failures,
+}
+
+/// a comparison between an observed value and a limit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Comparison {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// a value that may be compared against a [`Threshold`].
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Value {
+    /// a duration, e.g. a latency percentile.
+    Duration(Duration),
+    /// a number, e.g. a success rate.
+    Number(f64),
+}
+
+// === impl Threshold ===
+
+impl Threshold {
+    /// checks a summary against this threshold.
+    pub fn check(&self, summary: &Summary) -> Outcome {
+        let observed = self.metric.observe(summary);
+        let passed = observed.is_some_and(|observed| self.comparison.compare(observed, self.limit));
+        Outcome {
+            threshold: *self,
+            observed,
+            passed,
+        }
+    }
+}
+
+/// NB: thresholds may not contain `NaN`, see [`Threshold::from_str()`].
+impl Eq for Threshold {}
+
+impl FromStr for Threshold {
+    type Err = Error;
+    fn from_str(threshold: &str) -> Result<Self, Self::Err> {
+        const MALFORMED: &str = "threshold must be written as `METRIC<LIMIT`, e.g. `p99<250ms`";
+
+        let split = threshold.find(['<', '>']).ok_or(MALFORMED)?;
+        let (metric, rest) = threshold.split_at(split);
+        let (comparison, limit) = [
+            ("<=", Comparison::Le),
+            (">=", Comparison::Ge),
+            ("<", Comparison::Lt),
+            (">", Comparison::Gt),
+        ]
+        .into_iter()
+        .find_map(|(op, comparison)| rest.strip_prefix(op).map(|limit| (comparison, limit)))
+        .ok_or(MALFORMED)?;
+
+        let metric = metric.trim().parse::<Metric>()?;
+        let limit = metric.parse_limit(limit.trim())?;
+        Ok(Self {
+            metric,
+            comparison,
+            limit,
+        })
+    }
+}
+
+impl fmt::Display for Threshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            metric,
+            comparison,
+            limit,
+        } = self;
+        match limit {
+            Value::Duration(limit) => {
+                let limit = humantime::format_duration(*limit);
+                write!(f, "{metric}{comparison}{limit}")
+            }
+            Value::Number(limit) => write!(f, "{metric}{comparison}{limit}"),
+        }
+    }
+}
+
+// === impl Outcome ===
+
+impl Outcome {
+    /// returns true if every outcome passed.
+    pub fn all_passed<'a>(outcomes: impl IntoIterator<Item = &'a Self>) -> bool {
+        outcomes.into_iter().all(|outcome| outcome.passed)
+    }
+}
+
+// === impl Metric ===
+
+impl Metric {
+    /// returns the observed value of this metric.
+    ///
+    /// returns `None` for latency metrics if no successful responses were received.
+    fn observe(&self, summary: &Summary) -> Option<Value> {
+        let latency = summary.success_count() > 0;
+        match self {
+            Self::Percentile(p) if latency => Value::Duration(summary.percentile(*p)),
+            Self::Mean if latency => Value::Duration(summary.mean_duration()),
+            Self::Max if latency => Value::Duration(summary.max_duration()),
+            Self::Percentile(_) | Self::Mean | Self::Max => return None,
+            Self::SuccessRate if summary.total() == 0 => Value::Number(0.0),
+            Self::SuccessRate => {
+                let rate = f64::from(summary.success_count()) / f64::from(summary.total());
+                Value::Number(rate * 100.0)
+            }
+            Self::Rps => Value::Number(summary.throughput()),
+            Self::Failures => Value::Number(summary.failure_count().into()),
+        }
+        .pipe(Some)
+    }
+
+    /// parses a limit for this metric.
+    ///
+    /// durations must be written with a unit, e.g. `250ms`.
+    fn parse_limit(&self, limit: &str) -> Result<Value, Error> {
+        match self {
+            Self::Percentile(_) | Self::Mean | Self::Max => {
+                humantime::parse_duration(limit)?.pipe(Value::Duration)
+            }
+            Self::SuccessRate | Self::Rps | Self::Failures => {
+                finite(limit.parse()?)?.pipe(Value::Number)
+            }
+        }
+        .pipe(Ok)
+    }
+}
+
+impl FromStr for Metric {
+    type Err = Error;
+    fn from_str(metric: &str) -> Result<Self, Self::Err> {
+        match metric {
+            "mean" => Ok(Self::Mean),
+            "max" => Ok(Self::Max),
+            "success_rate" => Ok(Self::SuccessRate),
+            "rps" => Ok(Self::Rps),
+            "failures" => Ok(Self::Failures),
+            _ => match metric.strip_prefix('p').map(str::parse::<f64>) {
+                Some(Ok(p)) if (0.0..=100.0).contains(&p) => Ok(Self::Percentile(p)),
+                Some(_) => Err("percentile must be between 0 and 100".into()),
+                None => Err(format!(
+                    "unknown metric `{metric}`, expected one of `pNN`, `mean`, `max`, \
+                     `success_rate`, `rps`, or `failures`"
+                )
+                .into()),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Percentile(p) => write!(f, "p{p}"),
+            Self::Mean => f.write_str("mean"),
+            Self::Max => f.write_str("max"),
+            Self::SuccessRate => f.write_str("success_rate"),
+            Self::Rps => f.write_str("rps"),
+            Self::Failures => f.write_str("failures"),
+        }
+    }
+}
+
+// === impl Comparison ===
+
+impl Comparison {
+    /// returns true if the observed value satisfies this comparison with the limit.
+    fn compare(&self, observed: Value, limit: Value) -> bool {
+        match self {
+            Self::Lt => observed < limit,
+            Self::Le => observed <= limit,
+            Self::Gt => observed > limit,
+            Self::Ge => observed >= limit,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+        })
+    }
+}
+
+// === impl Value ===
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duration(duration) => write!(f, "{}µs", duration.as_micros()),
+            Self::Number(number) if number.fract() == 0.0 => write!(f, "{number}"),
+            Self::Number(number) => write!(f, "{number:.2}"),
+        }
+    }
+}
+
+/// returns an error if the given number is `NaN` or infinite.
+fn finite(number: f64) -> Result<f64, Error> {
+    number
+        .is_finite()
+        .then_some(number)
+        .ok_or_else(|| "threshold limit must be a finite number".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::worker::{Failure, Report},
+        std::time::Instant,
+    };
+
+    /// returns a summary of 9 successful reports, and a failed one, finishing over one second.
+    fn summary() -> Summary {
+        let start = Instant::now();
+        let report = |n: u64| {
+            let report = Report::test(
+                start + Duration::from_millis(100 * n),
+                Duration::from_millis(10 * n),
+            );
+            Report {
+                intended: start,
+                ..report
+            }
+        };
+        let mut summary = Summary::default();
+        summary.extend((1..=9).map(report));
+        summary.extend([report(10).failed(Failure::ConnectRefused)]);
+        summary
+    }
+
+    #[test]
+    fn thresholds_can_be_parsed() -> Result<(), Error> {
+        for threshold in ["p99<250ms", "p99.9<=1s", "success_rate>=99.9", "rps>1000"] {
+            assert_eq!(threshold.parse::<Threshold>()?.to_string(), threshold);
+        }
+        assert_eq!(
+            "p50 < 10ms".parse::<Threshold>()?.to_string(),
+            "p50<10ms",
+            "whitespace is ignored"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_thresholds_are_rejected() {
+        for threshold in [
+            "p99",
+            "p99=250ms",
+            "p99<250",
+            "p101<1s",
+            "latency<1s",
+            "rps>fast",
+            "rps>NaN",
+        ] {
+            threshold.parse::<Threshold>().unwrap_err();
+        }
+    }
+
+    #[test]
+    fn summaries_are_checked_against_thresholds() -> Result<(), Error> {
+        let summary = summary();
+        let check = |threshold: &str| -> Result<bool, Error> {
+            Ok(threshold.parse::<Threshold>()?.check(&summary).passed)
+        };
+
+        assert!(check("p50<60ms")?);
+        assert!(!check("p99<60ms")?);
+        assert!(check("max<=101ms")?);
+        assert!(check("success_rate>=90")?);
+        assert!(!check("success_rate>90")?);
+        assert!(check("failures<2")?);
+        assert!(check("rps>=9")?, "10 reports finish within 1s");
+        assert!(!check("rps>10")?);
+        Ok(())
+    }
+
+    #[test]
+    fn latency_thresholds_fail_without_successful_responses() -> Result<(), Error> {
+        let start = Instant::now();
+        let mut refused = Summary::default();
+        refused.extend((0..300).map(|_| {
+            Report::test(start, Duration::from_millis(4)).failed(Failure::ConnectRefused)
+        }));
+
+        for summary in [refused, Summary::default()] {
+            for threshold in ["p99<250ms", "mean<1s", "max<1s"] {
+                let outcome = threshold.parse::<Threshold>()?.check(&summary);
+                assert_eq!(outcome.observed, None, "{threshold} cannot be evaluated");
+                assert!(!outcome.passed, "{threshold} should not pass vacuously");
+            }
+            assert!("failures<1000".parse::<Threshold>()?.check(&summary).passed);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn success_rates_are_displayed_legibly() -> Result<(), Error> {
+        let start = Instant::now();
+        let mut summary = Summary::default();
+        summary.extend((0..300).map(|n| match n {
+            0..=265 => Report::test(start, Duration::from_millis(1)),
+            _ => Report::test(start, Duration::from_millis(1)).failed(Failure::ConnectRefused),
+        }));
+
+        let outcome = "success_rate>=99".parse::<Threshold>()?.check(&summary);
+        assert_eq!(
+            outcome.observed.map(|v| v.to_string()).as_deref(),
+            Some("88.67")
+        );
+        assert_eq!(Value::Number(3.0).to_string(), "3");
+        Ok(())
+    }
+
+    #[test]
+    fn outcomes_pass_if_every_threshold_passed() -> Result<(), Error> {
+        let summary = summary();
+        let outcomes = ["p50<1s", "failures<1"]
+            .into_iter()
+            .map(|t| t.parse::<Threshold>().map(|t| t.check(&summary)))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert!(!Outcome::all_passed(&outcomes));
+        assert!(Outcome::all_passed(&outcomes[..1]));
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+//! tls facilities.
+//!
+//! defines a [`Connector`] used by workers to establish tls sessions with a server. http/2 is
+//! negotiated with the server via ALPN.
+
+use {
+    crate::{cli, error::Error},
+    rustls::{
+        client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+        crypto::CryptoProvider,
+        pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime},
+        ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+    },
+    std::{fmt, path::Path, sync::Arc},
+    tap::{Pipe, Tap},
+    tokio::net::TcpStream,
+    tokio_rustls::{client::TlsStream, TlsConnector},
+    tracing::{debug, trace, warn},
+    url::Host,
+};
+
+/// the ALPN protocol identifier for http/2.
+pub const ALPN_H2: &[u8] = b"h2";
+
+/// a tls connector.
+///
+/// this may be cheaply cloned, and shared among many workers.
+#[derive(Clone)]
+pub struct Connector {
+    /// the underlying tls connector.
+    inner: TlsConnector,
+    /// the server name to send, overriding the server's host name.
+    sni: Option<ServerName<'static>>,
+}
+
+/// a [`ServerCertVerifier`] that accepts any certificate.
+///
+/// this is used when tls verification has been disabled.
+#[derive(Debug)]
+struct SkipVerification(Arc<CryptoProvider>);
+
+// === impl Connector ===
+
+impl Connector {
+    /// returns a new connector, configured by the given command-line options.
+    pub fn new(
+        cli::Tls {
+            ca_cert,
+            client_cert,
+            client_key,
+            sni,
+            insecure,
+        }: &cli::Tls,
+    ) -> Result<Self, Error> {
+        let provider = rustls::crypto::ring::default_provider().pipe(Arc::new);
+        let builder = ClientConfig::builder_with_provider(Arc::clone(&provider))
+            .with_safe_default_protocol_versions()?;
+
+        // decide how to verify the server's certificate.
+        let builder = if *insecure {
+            warn!("tls certificate verification is disabled");
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(SkipVerification(provider)))
+        } else {
+            let mut roots = RootCertStore::empty();
+            match ca_cert {
+                Some(path) => {
+                    let (added, ignored) = Self::read_certs(path)?
+                        .pipe(|certs| roots.add_parsable_certificates(certs));
+                    debug!(%added, %ignored, ?path, "loaded custom ca bundle");
+                    if added == 0 {
+                        return Err(format!("no certificates found in {}", path.display()).into());
+                    }
+                }
+                None => roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+            }
+            builder.with_root_certificates(roots)
+        };
+
+        // present a client certificate, if one was provided.
+        let mut config = match (client_cert, client_key) {
+            (Some(cert), Some(key)) => {
+                let cert = Self::read_certs(cert)?;
+                let key = Self::read_key(key)?;
+                builder.with_client_auth_cert(cert, key)?
+            }
+            (None, None) => builder.with_no_client_auth(),
+            _ => return Err("a client certificate and key must be provided together".into()),
+        };
+
+        // negotiate http/2 via ALPN.
+        config.alpn_protocols = vec![ALPN_H2.to_vec()];
+
+        let sni = sni
+            .as_deref()
+            .map(ServerName::try_from)
+            .transpose()?
+            .map(|name| name.to_owned());
+
+        Ok(Self {
+            inner: config.pipe(Arc::new).pipe(TlsConnector::from),
+            sni,
+        })
+    }
+
+    /// performs a tls handshake with the server, over the given tcp stream.
+    ///
+    /// returns an error if the server did not agree to speak http/2.
+    pub async fn connect(
+        &self,
+        host: &Host,
+        tcp: TcpStream,
+    ) -> Result<TlsStream<TcpStream>, Error> {
+        let Self { inner, sni } = self;
+
+        let name = match sni {
+            Some(sni) => sni.clone(),
+            None => Self::server_name(host)?,
+        };
+
+        let stream = inner
+            .connect(name, tcp)
+            .tap(|_| trace!("beginning tls handshake"))
+            .await
+            .tap(|_| debug!("finished tls handshake"))?;
+
+        match stream.get_ref().1.alpn_protocol() {
+            Some(ALPN_H2) => Ok(stream),
+            _ => Err("server did not negotiate http/2 via alpn".into()),
+        }
+    }
+
+    /// returns the server name for the given host.
+    fn server_name(host: &Host) -> Result<ServerName<'static>, Error> {
+        match host {
+            Host::Domain(domain) => ServerName::try_from(domain.to_owned())?,
+            Host::Ipv4(addr) => ServerName::IpAddress((*addr).into()),
+            Host::Ipv6(addr) => ServerName::IpAddress((*addr).into()),
+        }
+        .pipe(Ok)
+    }
+
+    /// reads a PEM-encoded list of certificates from the given path.
+    fn read_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, Error> {
+        let pem = std::fs::read(path)?;
+        rustls_pemfile::certs(&mut pem.as_slice())
+            .collect::<Result<_, _>>()
+            .map_err(Into::into)
+    }
+
+    /// reads a PEM-encoded private key from the given path.
+    fn read_key(path: &Path) -> Result<PrivateKeyDer<'static>, Error> {
+        let pem = std::fs::read(path)?;
+        rustls_pemfile::private_key(&mut pem.as_slice())?
+            .ok_or_else(|| format!("no private key found in {}", path.display()).into())
+    }
+}
+
+impl fmt::Debug for Connector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Connector")
+            .field("sni", &self.sni)
+            .finish_non_exhaustive()
+    }
+}
+
+// === impl SkipVerification ===
+
+impl ServerCertVerifier for SkipVerification {
+    fn verify_server_cert(
+        &self,
+        _: &CertificateDer<'_>,
+        _: &[CertificateDer<'_>],
+        _: &ServerName<'_>,
+        _: &[u8],
+        _: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        let algs = &self.0.signature_verification_algorithms;
+        rustls::crypto::verify_tls12_signature(message, cert, dss, algs)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        let algs = &self.0.signature_verification_algorithms;
+        rustls::crypto::verify_tls13_signature(message, cert, dss, algs)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
@@ -1,18 +1,35 @@
 //! defines a single worker used for load-testing.
 
+pub use self::{
+    failure::Failure,
+    phases::{Phase, Phases},
+    pool::Pool,
+    template::Template,
+    timeouts::Timeouts,
+    validation::{
+        BodyRule, HeaderRule, JsonRule, Pattern, Sha256, StatusRange, Validation, Violation,
+    },
+};
+
 use {
-    self::connect::ConnectionHandle,
-    crate::error::Error,
-    http::{response::Parts, Request, Response},
+    self::{connect::ConnectionHandle, validation::Verdict},
+    crate::{error::Error, tls},
+    http::{response::Parts, uri::Scheme, Request, Response, StatusCode},
     http_body_util::Full,
     hyper::{
         body::{Body, Bytes, Incoming},
         client::conn::http2::{self, Connection, SendRequest},
     },
     hyper_util::rt::{TokioExecutor, TokioIo},
-    std::time::{Duration, Instant},
+    std::{
+        sync::Arc,
+        time::{Duration, Instant},
+    },
     tap::{Pipe, Tap},
-    tokio::{net::TcpStream, task::JoinHandle},
+    tokio::{
+        net::TcpStream,
+        task::{JoinError, JoinHandle},
+    },
     tracing::{debug, info_span, instrument, trace, Instrument},
     url::Host,
 };
@@ -23,41 +40,94 @@ mod connect;
 /// workers can [`tx()`][Worker::tx] a request, awaiting a response.
 mod tx;
 
+/// workers can share long-lived connections via a [`Pool`].
+mod pool;
+
+/// workers send requests described by a [`Template`].
+mod template;
+
+/// workers classify the ways in which they fail, see [`Failure`].
+mod failure;
+
+/// workers time each of their phases, see [`Phases`].
+mod phases;
+
+/// workers may bound how long each phase takes, see [`Timeouts`].
+mod timeouts;
+
+/// workers check that responses satisfy a set of rules, see [`Validation`].
+mod validation;
+
 /// a load-test worker.
 ///
 /// a worker represents a single "job", responsible for connecting to an http/2 server, sending
 /// a request, and receiving a response.
 ///
 /// most callers should use [`Worker::run()`]. use [`Worker::run_local()`] to run the worker
-/// against a server that is running on a local port.
+/// against a server that is running on a local port. use [`Worker::run_pooled()`] to send the
+/// request upon a connection shared via a [`Pool`].
 pub struct Worker<B = Full<Bytes>> {
     /// the host to send requests to.
     pub host: Host,
     /// the port to send requests to.
     pub port: u16,
+    /// the scheme of the connection, either `http` or `https`.
+    scheme: Scheme,
     /// the sender-side of the connection.
     tx: SendRequest<B>,
     /// the background task responsible for http state.
-    conn: ConnectionHandle,
+    ///
+    /// this is `None` if the connection is owned by a [`Pool`].
+    conn: Option<ConnectionHandle>,
+}
+
+/// the server that a [`Worker`] sends requests to.
+#[derive(Clone, Debug)]
+pub struct Target {
+    /// the host to send requests to.
+    pub host: Host,
+    /// the port to send requests to.
+    pub port: u16,
+    /// a tls connector, if requests should be sent over tls.
+    pub tls: Option<tls::Connector>,
+    /// bounds on how long each phase of a worker may take.
+    pub timeouts: Timeouts,
 }
 
 /// a report, containing information about the outcome of a [`Worker`].
+///
+/// workers that fail still produce a report, see [`Report::failure`].
 pub struct Report {
     /// how long the worker took to run.
     pub duration: Duration,
     /// true if the response was a success.
+    ///
+    /// see [`Validation`] for the rules that a successful response must satisfy.
     pub success: bool,
+    /// the status code of the response, if one was received.
+    pub status: Option<StatusCode>,
+    /// the reason the worker failed, if it did not receive a valid response.
+    pub failure: Option<Failure>,
     /// the timestamp marking when the worker started running.
     pub start: Instant,
+    /// the timestamp marking when the worker was intended to start running.
+    ///
+    /// this may be earlier than [`Report::start`], if the worker was delayed. latencies measured
+    /// from this timestamp account for time spent waiting to be sent, avoiding "coordinated
+    /// omission".
+    pub intended: Instant,
     /// the timestamp marking when the worker finished running.
     pub end: Instant,
+    /// the time that the worker spent in each of its phases.
+    pub phases: Phases,
+    /// the index of the load profile stage that the worker was spawned in, if any.
+    ///
+    /// see [`Profile`][crate::syndicate::Profile].
+    pub stage: Option<usize>,
 }
 
-/// the result of [`Worker::run()`].
-pub type WorkerResult = Result<Report, Error>;
-
 /// a handle to a [`Worker`] running in the background.
-pub type WorkerHandle = JoinHandle<WorkerResult>;
+pub type WorkerHandle = JoinHandle<Report>;
 
 // === impl Worker ===
 
@@ -67,49 +137,242 @@ impl Worker {
     /// # panics
     ///
     /// this will panic if called outside of a tokio runtime.
-    #[instrument]
-    pub fn spawn(host: Host, port: u16) -> WorkerHandle {
-        let fut = Self::run(host, port);
+    #[instrument(skip(request))]
+    pub fn spawn(target: Target, request: Arc<Template>) -> WorkerHandle {
+        let fut = Self::run(target, request);
         tokio::spawn(fut)
     }
 
-    /// runs a worker.
-    #[instrument]
-    pub async fn run(host: Host, port: u16) -> WorkerResult {
-        use http_body_util::BodyExt;
+    /// spawns a worker that sends its request upon a pooled connection.
+    ///
+    /// # panics
+    ///
+    /// this will panic if called outside of a tokio runtime.
+    #[instrument(skip_all)]
+    pub fn spawn_pooled(pool: Pool, request: Arc<Template>) -> WorkerHandle {
+        let fut = Self::run_pooled(pool, request);
+        tokio::spawn(fut)
+    }
 
+    /// runs a worker.
+    #[instrument(skip(request))]
+    pub async fn run(target: Target, request: Arc<Template>) -> Report {
         let start = std::time::Instant::now();
-        let resp: Parts = {
+        let mut phases = Phases::default();
+        let timeouts = target.timeouts;
+        let resp: Result<(Parts, Verdict), Failure> = async {
             // === /!\ critical section /!\ ===
             // this is where the worker will connect, send a request, and read the response.
-            // NB: even though it is unused, we should be sure to read the contents of the body.
-            let mut worker = Self::connect(host, port).await?;
-            let (resp, body) = worker.tx().await?.into_parts();
-            let _body = body.collect().await?.to_bytes();
-            resp
-        };
+            Self::connect(target, &mut phases)
+                .await?
+                .send(&request, timeouts.request, &mut phases)
+                .await
+        }
+        .pipe(|worker| Timeouts::bound(timeouts.total, worker))
+        .await
+        .unwrap_or_else(|| Err(phases.total_timed_out()));
         let end = std::time::Instant::now();
 
-        // build a report about what the worker observed.
-        let report = Report {
+        Report::new(resp, phases, start, end)
+    }
+
+    /// runs a worker, sending its request upon a connection from the given [`Pool`].
+    #[instrument(skip_all)]
+    pub async fn run_pooled(pool: Pool, request: Arc<Template>) -> Report {
+        let start = std::time::Instant::now();
+        let mut phases = Phases::default();
+        let timeouts = pool.timeouts();
+        let resp: Result<(Parts, Verdict), Failure> = async {
+            // === /!\ critical section /!\ ===
+            // this is where the worker will check out a connection, send a request, and read the
+            // response. a new connection is only established if the pool does not have one open.
+            pool.checkout(&mut phases)
+                .await?
+                .send(&request, timeouts.request, &mut phases)
+                .await
+        }
+        .pipe(|worker| Timeouts::bound(timeouts.total, worker))
+        .await
+        .unwrap_or_else(|| Err(phases.total_timed_out()));
+        let end = std::time::Instant::now();
+
+        Report::new(resp, phases, start, end)
+    }
+
+    /// runs a worker against a `localhost` port, sending a default [`Template`] request.
+    pub async fn run_local(port: u16) -> Report {
+        Self::run(Target::local(port), Default::default()).await
+    }
+
+    /// sends a request to the server, reads the response, and checks that it is valid.
+    ///
+    /// if a timeout is given, it bounds both sending the request and reading the response body.
+    async fn send(
+        mut self,
+        request: &Template,
+        timeout: Option<Duration>,
+        phases: &mut Phases,
+    ) -> Result<(Parts, Verdict), Failure> {
+        let validation = &request.validation;
+        let send = async {
+            // NB: the body should always be read, even if no rules need to inspect it.
+            let (resp, body) = self.tx(request, phases).await?.into_parts();
+            let verdict = match phases.time(Phase::Body, validation.read(body)).await {
+                Ok(body) => validation.check(&resp, &body),
+                Err(err) => {
+                    Validation::reject(&err).ok_or_else(|| Failure::classify(Phase::Body, err))?
+                }
+            };
+            Ok((resp, verdict))
+        };
+
+        Timeouts::bound(timeout, send)
+            .await
+            .unwrap_or_else(|| Err(phases.timed_out(Phase::Request)))
+    }
+}
+
+// === impl Target ===
+
+impl Target {
+    /// returns a new target, reached over cleartext http/2.
+    pub fn new(host: Host, port: u16) -> Self {
+        Self {
+            host,
+            port,
+            tls: None,
+            timeouts: Timeouts::default(),
+        }
+    }
+
+    /// returns a new target, oriented at `localhost`.
+    pub fn local(port: u16) -> Self {
+        Self::new(crate::LOCALHOST, port)
+    }
+
+    /// sets the tls connector used to reach this target.
+    pub fn tls(self, tls: Option<tls::Connector>) -> Self {
+        Self { tls, ..self }
+    }
+
+    /// sets the timeouts bounding each phase of a worker.
+    pub fn timeouts(self, timeouts: Timeouts) -> Self {
+        Self { timeouts, ..self }
+    }
+
+    /// returns the scheme used to reach this target.
+    pub fn scheme(&self) -> Scheme {
+        match self.tls {
+            Some(_) => Scheme::HTTPS,
+            None => Scheme::HTTP,
+        }
+    }
+}
+
+// === impl Report ===
+
+impl Report {
+    /// builds a report about what a worker observed.
+    fn new(
+        resp: Result<(Parts, Verdict), Failure>,
+        phases: Phases,
+        start: Instant,
+        end: Instant,
+    ) -> Self {
+        let (status, success, failure) = match resp {
+            Ok((Parts { status, .. }, Verdict::Accepted)) => (Some(status), true, None),
+            Ok((Parts { status, .. }, Verdict::Rejected)) => (Some(status), false, None),
+            Ok((Parts { status, .. }, Verdict::Invalid(violation))) => {
+                (Some(status), false, Some(Failure::Invalid(violation)))
+            }
+            Err(failure) => (None, false, Some(failure)),
+        };
+
+        Self {
             duration: end.duration_since(start),
-            success: resp.status.is_success(),
+            success,
+            status,
+            failure,
             start,
             end,
+            intended: start,
+            phases,
+            stage: None,
+        }
+    }
+
+    /// builds a report about a worker that was cancelled before it finished.
+    pub(crate) fn cancelled(start: Instant) -> Self {
+        Self::new(
+            Err(Failure::Cancelled),
+            Phases::default(),
+            start,
+            Instant::now(),
+        )
+    }
+
+    /// builds a report about a worker whose task could not be joined.
+    ///
+    /// this happens if the worker panicked, or if its task was aborted.
+    pub(crate) fn join_error(start: Instant, err: JoinError) -> Self {
+        let failure = if err.is_panic() {
+            Failure::Panicked
+        } else {
+            Failure::Cancelled
         };
+        debug!(?failure, %err, "worker task could not be joined");
 
-        Ok(report)
+        Self::new(Err(failure), Phases::default(), start, Instant::now())
+    }
+}
+
+#[cfg(test)]
+impl Report {
+    /// returns a report for a worker that started at `start`, and received a `200 OK` after
+    /// running for `duration`.
+    ///
+    /// other fields may be set with struct update syntax, e.g. `Report { stage, ..report }`.
+    pub(crate) fn test(start: Instant, duration: Duration) -> Self {
+        Self {
+            duration,
+            success: true,
+            status: Some(StatusCode::OK),
+            failure: None,
+            start,
+            end: start + duration,
+            intended: start,
+            phases: Phases::default(),
+            stage: None,
+        }
+    }
+
+    /// returns this report, with the given response status.
+    ///
+    /// the report is successful if the status is.
+    pub(crate) fn status(self, status: StatusCode) -> Self {
+        Self {
+            success: status.is_success(),
+            status: Some(status),
+            ..self
+        }
     }
 
-    /// runs a worker against a `localhost` port.
-    pub async fn run_local(port: u16) -> WorkerResult {
-        Self::run(crate::LOCALHOST, port).await
+    /// returns this report, failing for the given reason without receiving a response.
+    pub(crate) fn failed(self, failure: Failure) -> Self {
+        Self {
+            success: false,
+            status: None,
+            failure: Some(failure),
+            ..self
+        }
     }
 }
 
 impl<B> Drop for Worker<B> {
     /// the background task driving http state should be aborted when the worker is dropped.
     fn drop(&mut self) {
-        self.conn.abort();
+        if let Some(conn) = self.conn.as_ref() {
+            conn.abort();
+        }
     }
 }
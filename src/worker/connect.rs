@@ -1,11 +1,28 @@
-use super::*;
+use {
+    super::*,
+    std::{
+        io,
+        pin::Pin,
+        task::{Context, Poll},
+    },
+    tokio::io::{AsyncRead, AsyncWrite, ReadBuf},
+    tokio_rustls::client::TlsStream,
+};
 
 /// an http/2 connection driven by tokio i/o and a tokio executor.
-pub type TokioConnection<B> = Connection<TokioIo<TcpStream>, B, TokioExecutor>;
+pub type TokioConnection<B> = Connection<TokioIo<Io>, B, TokioExecutor>;
 
 /// a handle to a [`TokioConnection`] task running in the background.
 pub type ConnectionHandle = JoinHandle<Result<(), hyper::Error>>;
 
+/// a transport connected to the server, which may be secured with tls.
+pub enum Io {
+    /// a cleartext tcp stream.
+    Plain(TcpStream),
+    /// a tcp stream secured with tls.
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
 // === impl Worker ===
 
 impl<B> Worker<B>
@@ -15,28 +32,72 @@ where
     B::Error: Into<Error>,
 {
     /// creates a new worker, connecting to the server.
-    #[instrument]
-    pub(super) async fn connect(host: Host, port: u16) -> Result<Self, Error> {
-        // establish a connection to the server.
-        let conn = format!("{host}:{port}")
-            .pipe(TcpStream::connect)
-            .tap(|_| trace!("establishing tcp connection"))
+    ///
+    /// the time spent in each phase of connecting is recorded in `phases`.
+    #[instrument(skip(phases))]
+    pub(super) async fn connect(target: Target, phases: &mut Phases) -> Result<Self, Failure> {
+        let scheme = target.scheme();
+        let Target {
+            host,
+            port,
+            tls,
+            timeouts,
+        } = target;
+
+        let tcp = async {
+            // resolve the server's host name.
+            let addrs = format!("{host}:{port}")
+                .pipe(tokio::net::lookup_host)
+                .pipe(|lookup| phases.time(Phase::Dns, lookup))
+                .tap(|_| trace!("resolving host name"))
+                .await
+                .map_err(Failure::dns)?
+                .collect::<Vec<_>>();
+
+            // establish a connection to the server.
+            TcpStream::connect(addrs.as_slice())
+                .pipe(|connect| phases.time(Phase::Connect, connect))
+                .tap(|_| trace!("establishing tcp connection"))
+                .await
+                .tap(|_| debug!("established tcp connection"))
+                .map_err(Failure::connect)
+        };
+        let tcp = Timeouts::bound(timeouts.connect, tcp)
             .await
-            .map(TokioIo::new) // use the tokio/hyper compatibility wrapper
-            .tap(|_| debug!("established tcp connection"))?;
-
-        // then perform the handshake with the server.
-        let (tx, conn) = TokioExecutor::new()
-            .pipe(http2::Builder::new)
-            .handshake::<_, B>(conn)
-            .tap(|_| trace!("beginning http/2 handshake"))
+            .unwrap_or_else(|| Err(phases.timed_out(Phase::Connect)))?;
+
+        let handshake = async {
+            // secure the connection with tls, if applicable.
+            let conn = match tls {
+                Some(tls) => phases
+                    .time(Phase::Tls, tls.connect(&host, tcp))
+                    .await
+                    .map(Box::new)
+                    .map(Io::Tls)
+                    .map_err(Failure::tls)?,
+                None => Io::Plain(tcp),
+            }
+            .pipe(TokioIo::new); // use the tokio/hyper compatibility wrapper
+
+            // then perform the handshake with the server.
+            TokioExecutor::new()
+                .pipe(http2::Builder::new)
+                .handshake::<_, B>(conn)
+                .pipe(|handshake| phases.time(Phase::Handshake, handshake))
+                .tap(|_| trace!("beginning http/2 handshake"))
+                .await
+                .tap(|_| debug!("finished http/2 handshake"))
+                .map_err(|err| Failure::classify(Phase::Handshake, err))
+        };
+        let (tx, conn) = Timeouts::bound(timeouts.handshake, handshake)
             .await
-            .tap(|_| debug!("finished http/2 handshake"))?;
-        let conn = Self::spawn_conn(conn);
+            .unwrap_or_else(|| Err(phases.timed_out(Phase::Handshake)))?;
+        let conn = Self::spawn_conn(conn).pipe(Some);
 
         Ok(Self {
             tx,
             conn,
+            scheme,
             host,
             port,
         })
@@ -58,3 +119,63 @@ where
         conn.instrument(span).pipe(tokio::spawn)
     }
 }
+
+// === impl Io ===
+
+impl AsyncRead for Io {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(io) => Pin::new(io).poll_read(cx, buf),
+            Self::Tls(io) => Pin::new(io).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for Io {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Self::Plain(io) => Pin::new(io).poll_write(cx, buf),
+            Self::Tls(io) => Pin::new(io).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(io) => Pin::new(io).poll_flush(cx),
+            Self::Tls(io) => Pin::new(io).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(io) => Pin::new(io).poll_shutdown(cx),
+            Self::Tls(io) => Pin::new(io).poll_shutdown(cx),
+        }
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Self::Plain(io) => Pin::new(io).poll_write_vectored(cx, bufs),
+            Self::Tls(io) => Pin::new(io).poll_write_vectored(cx, bufs),
+        }
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        match self {
+            Self::Plain(io) => io.is_write_vectored(),
+            Self::Tls(io) => io.is_write_vectored(),
+        }
+    }
+}
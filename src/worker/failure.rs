@@ -0,0 +1,272 @@
+use {
+    super::*,
+    h2::Reason,
+    std::{cmp::Ordering, error::Error as StdError, fmt, hash::Hash, io},
+};
+
+/// a classification of the ways in which a [`Worker`] may fail.
+///
+/// a failed worker still produces a [`Report`], see [`Report::failure`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Failure {
+    /// the server's host name could not be resolved.
+    Dns,
+    /// the server refused the tcp connection.
+    ConnectRefused,
+    /// the tcp connection could not be established.
+    Connect,
+    /// the worker timed out during the given phase.
+    ///
+    /// see [`Timeouts`].
+    Timeout(Phase),
+    /// the worker did not finish before its total timeout elapsed.
+    ///
+    /// see [`Timeouts::total`].
+    TotalTimeout,
+    /// the tls handshake failed.
+    Tls,
+    /// the http/2 handshake failed.
+    Handshake,
+    /// the connection encountered an http/2 protocol error.
+    Protocol(Reason),
+    /// the stream was reset.
+    Reset(Reason),
+    /// the connection was closed with a GOAWAY frame.
+    GoAway(Reason),
+    /// the connection was closed before the response was finished.
+    Closed,
+    /// the request could not be sent.
+    Request,
+    /// the response body could not be read.
+    Body,
+    /// the response broke a [`Validation`] rule.
+    Invalid(Violation),
+    /// the worker was cancelled before it finished, see [`Builder::grace()`].
+    ///
+    /// [`Builder::grace()`]: crate::syndicate::Builder::grace
+    Cancelled,
+    /// the worker panicked before it finished.
+    Panicked,
+}
+
+// === impl Failure ===
+
+impl Failure {
+    /// classifies an error resolving the server's host name.
+    pub(super) fn dns(err: io::Error) -> Self {
+        Self::Dns.tap(|failure| debug!(?failure, %err, "worker failed to resolve host name"))
+    }
+
+    /// classifies an error establishing a tcp connection.
+    pub(super) fn connect(err: io::Error) -> Self {
+        let failure = match err.kind() {
+            io::ErrorKind::ConnectionRefused => Self::ConnectRefused,
+            io::ErrorKind::TimedOut => Self::Timeout(Phase::Connect),
+            _ => Self::Connect,
+        };
+
+        failure.tap(|failure| debug!(?failure, %err, "worker failed to connect"))
+    }
+
+    /// classifies an error performing a tls handshake.
+    pub(super) fn tls(err: Error) -> Self {
+        Self::Tls.tap(|failure| debug!(?failure, %err, "worker failed tls handshake"))
+    }
+
+    /// classifies an error encountered during the given phase.
+    ///
+    /// the error's chain of sources are inspected for http/2 and i/o errors. if a more specific
+    /// cause is not found, the failure is attributed to the given phase.
+    pub(super) fn classify<E>(phase: Phase, err: E) -> Self
+    where
+        E: Into<Error>,
+    {
+        let err: Error = err.into();
+        let failure = Self::from_sources(phase, &*err).unwrap_or(match phase {
+            Phase::Dns => Self::Dns,
+            Phase::Connect => Self::Connect,
+            Phase::Tls => Self::Tls,
+            Phase::Handshake => Self::Handshake,
+            Phase::Request => Self::Request,
+            Phase::Body => Self::Body,
+        });
+
+        failure.tap(|failure| debug!(?failure, ?phase, %err, "worker failed"))
+    }
+
+    /// walks an error's chain of sources, searching for a specific cause.
+    fn from_sources(phase: Phase, err: &(dyn StdError + 'static)) -> Option<Self> {
+        let mut source = Some(err);
+        while let Some(err) = source {
+            if let Some(err) = err.downcast_ref::<h2::Error>() {
+                if let Some(failure) = Self::from_h2(err) {
+                    return Some(failure);
+                }
+            } else if let Some(err) = err.downcast_ref::<hyper::Error>() {
+                if err.is_timeout() {
+                    return Some(Self::Timeout(phase));
+                } else if err.is_closed() || err.is_canceled() {
+                    return Some(Self::Closed);
+                }
+            } else if let Some(err) = err.downcast_ref::<io::Error>() {
+                if err.kind() == io::ErrorKind::TimedOut {
+                    return Some(Self::Timeout(phase));
+                } else if Self::is_closed(err.kind()) {
+                    return Some(Self::Closed);
+                } else if let Some(inner) = err.get_ref() {
+                    // NB: an i/o error's `source()` skips over the error that it wraps.
+                    return Self::from_sources(phase, inner);
+                }
+            }
+            source = err.source();
+        }
+
+        None
+    }
+
+    /// classifies an http/2 error.
+    fn from_h2(err: &h2::Error) -> Option<Self> {
+        if let Some(err) = err.get_io() {
+            return Self::is_closed(err.kind()).then_some(Self::Closed);
+        }
+        let reason = err.reason()?;
+        if err.is_go_away() {
+            Some(Self::GoAway(reason))
+        } else if err.is_reset() {
+            Some(Self::Reset(reason))
+        } else {
+            Some(Self::Protocol(reason))
+        }
+    }
+
+    /// returns true if an i/o error of this kind indicates that the connection was closed.
+    fn is_closed(kind: io::ErrorKind) -> bool {
+        use io::ErrorKind::*;
+        matches!(
+            kind,
+            BrokenPipe | ConnectionReset | ConnectionAborted | UnexpectedEof
+        )
+    }
+
+    /// returns a key used to order and hash failures.
+    fn key(&self) -> (u8, u32) {
+        match self {
+            Self::Dns => (0, 0),
+            Self::ConnectRefused => (1, 0),
+            Self::Connect => (2, 0),
+            Self::Timeout(phase) => (3, phase.index() as u32),
+            Self::TotalTimeout => (3, Phase::ALL.len() as u32),
+            Self::Tls => (4, 0),
+            Self::Handshake => (5, 0),
+            Self::Protocol(reason) => (6, (*reason).into()),
+            Self::Reset(reason) => (7, (*reason).into()),
+            Self::GoAway(reason) => (8, (*reason).into()),
+            Self::Closed => (9, 0),
+            Self::Request => (10, 0),
+            Self::Body => (11, 0),
+            Self::Invalid(violation) => (12, *violation as u32),
+            Self::Cancelled => (13, 0),
+            Self::Panicked => (14, 0),
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dns => f.write_str("dns error"),
+            Self::ConnectRefused => f.write_str("connection refused"),
+            Self::Connect => f.write_str("connect error"),
+            Self::Timeout(phase) => write!(f, "timeout ({phase})"),
+            Self::TotalTimeout => f.write_str("timeout (total)"),
+            Self::Tls => f.write_str("tls error"),
+            Self::Handshake => f.write_str("http/2 handshake error"),
+            Self::Protocol(reason) => write!(f, "http/2 protocol error ({reason:?})"),
+            Self::Reset(reason) => write!(f, "stream reset ({reason:?})"),
+            Self::GoAway(reason) => write!(f, "goaway ({reason:?})"),
+            Self::Closed => f.write_str("connection closed"),
+            Self::Request => f.write_str("request error"),
+            Self::Body => f.write_str("body read error"),
+            Self::Invalid(violation) => write!(f, "invalid response ({violation})"),
+            Self::Cancelled => f.write_str("cancelled"),
+            Self::Panicked => f.write_str("worker panicked"),
+        }
+    }
+}
+
+/// NB: [`Reason`] is not [`Ord`], so failures are ordered by their variant and reason code.
+impl Ord for Failure {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl PartialOrd for Failure {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Hash for Failure {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refused_connections_are_classified() {
+        let err = io::Error::from(io::ErrorKind::ConnectionRefused);
+        assert_eq!(Failure::connect(err), Failure::ConnectRefused);
+    }
+
+    #[test]
+    fn h2_errors_are_classified() {
+        let err = h2::Error::from(Reason::ENHANCE_YOUR_CALM);
+        assert_eq!(
+            Failure::classify(Phase::Request, err),
+            Failure::Protocol(Reason::ENHANCE_YOUR_CALM)
+        );
+    }
+
+    #[test]
+    fn nested_io_timeouts_are_classified() {
+        let err = io::Error::other(io::Error::from(io::ErrorKind::TimedOut));
+        assert_eq!(
+            Failure::classify(Phase::Body, err),
+            Failure::Timeout(Phase::Body)
+        );
+    }
+
+    #[test]
+    fn broken_connections_are_classified() {
+        let err = io::Error::new(io::ErrorKind::BrokenPipe, "connection closed");
+        assert_eq!(Failure::classify(Phase::Request, err), Failure::Closed);
+        let err = io::Error::other(io::Error::from(io::ErrorKind::ConnectionReset));
+        assert_eq!(Failure::classify(Phase::Body, err), Failure::Closed);
+    }
+
+    #[test]
+    fn unknown_errors_are_attributed_to_their_phase() {
+        assert_eq!(Failure::classify(Phase::Body, "oh no"), Failure::Body);
+        assert_eq!(Failure::classify(Phase::Request, "oh no"), Failure::Request);
+    }
+
+    #[test]
+    fn failures_are_displayed() {
+        assert_eq!(
+            Failure::Reset(Reason::REFUSED_STREAM).to_string(),
+            "stream reset (REFUSED_STREAM)"
+        );
+        assert_eq!(Failure::TotalTimeout.to_string(), "timeout (total)");
+    }
+
+    #[test]
+    fn total_timeouts_follow_phase_timeouts() {
+        assert!(Failure::Timeout(Phase::Body) < Failure::TotalTimeout);
+        assert!(Failure::TotalTimeout < Failure::Tls);
+    }
+}
@@ -0,0 +1,156 @@
+use {super::*, std::fmt};
+
+/// the phases of a [`Worker`].
+///
+/// phases are timed separately, see [`Phases`]. failures that do not carry a more specific cause
+/// are also attributed to the phase in which they occurred, see [`Failure`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Phase {
+    /// resolving the server's host name.
+    Dns,
+    /// establishing a tcp connection.
+    Connect,
+    /// performing the tls handshake.
+    Tls,
+    /// performing the http/2 handshake.
+    Handshake,
+    /// sending the request, and awaiting the response, i.e. the "time to first byte."
+    Request,
+    /// reading the response body.
+    Body,
+}
+
+/// the time that a [`Worker`] spent in each of its [`Phase`]s.
+///
+/// a phase has no duration if the worker did not reach it, or skipped it. for example, workers
+/// that send their request upon a pooled connection do not resolve the server's host name,
+/// connect, or perform any handshakes.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Phases {
+    durations: [Option<Duration>; Phase::ALL.len()],
+    /// the phase in progress, and when it began.
+    ///
+    /// this is set while a phase is being [timed][Phases::time()], so that a worker interrupted by
+    /// a timeout can tell which phase it was in.
+    pending: Option<(Phase, Instant)>,
+}
+
+// === impl Phase ===
+
+impl Phase {
+    /// all of the phases, in the order that a worker proceeds through them.
+    pub const ALL: [Phase; 6] = [
+        Self::Dns,
+        Self::Connect,
+        Self::Tls,
+        Self::Handshake,
+        Self::Request,
+        Self::Body,
+    ];
+
+    /// returns the index of this phase, within [`Phase::ALL`].
+    pub(crate) fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Dns => "dns",
+            Self::Connect => "connect",
+            Self::Tls => "tls",
+            Self::Handshake => "handshake",
+            Self::Request => "ttfb",
+            Self::Body => "body",
+        })
+    }
+}
+
+// === impl Phases ===
+
+impl Phases {
+    /// returns the time spent in the given phase, if the worker reached it.
+    pub fn get(&self, phase: Phase) -> Option<Duration> {
+        self.durations[phase.index()]
+    }
+
+    /// returns the time spent in each phase that the worker reached.
+    pub fn iter(&self) -> impl Iterator<Item = (Phase, Duration)> + '_ {
+        Phase::ALL
+            .into_iter()
+            .filter_map(|phase| self.get(phase).map(|duration| (phase, duration)))
+    }
+
+    /// records the time spent in the given phase.
+    pub(crate) fn record(&mut self, phase: Phase, duration: Duration) {
+        self.durations[phase.index()] = Some(duration);
+    }
+
+    /// awaits the given future, recording the time spent in the given phase.
+    pub(super) async fn time<F>(&mut self, phase: Phase, fut: F) -> F::Output
+    where
+        F: std::future::Future,
+    {
+        let start = Instant::now();
+        self.pending = Some((phase, start));
+        let output = fut.await;
+        self.pending = None;
+        self.record(phase, start.elapsed());
+        output
+    }
+
+    /// returns a timeout failure, attributed to the phase that was in progress.
+    ///
+    /// the time spent in the interrupted phase is recorded. if no phase was in progress, the
+    /// failure is attributed to the given phase.
+    pub(super) fn timed_out(&mut self, fallback: Phase) -> Failure {
+        let phase = self.interrupt().unwrap_or(fallback);
+        Failure::Timeout(phase)
+    }
+
+    /// returns a timeout failure, for a worker whose total timeout elapsed.
+    ///
+    /// the time spent in the interrupted phase, if any, is recorded.
+    pub(super) fn total_timed_out(&mut self) -> Failure {
+        self.interrupt();
+        Failure::TotalTimeout
+    }
+
+    /// records the time spent in the phase that was in progress, returning it.
+    fn interrupt(&mut self) -> Option<Phase> {
+        let (phase, start) = self.pending.take()?;
+        self.record(phase, start.elapsed());
+        Some(phase)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn phases_are_timed() {
+        let mut phases = Phases::default();
+        phases
+            .time(
+                Phase::Request,
+                tokio::time::sleep(Duration::from_millis(10)),
+            )
+            .await;
+
+        assert!(phases.get(Phase::Dns).is_none());
+        assert!(phases.get(Phase::Request) >= Some(Duration::from_millis(10)));
+        assert_eq!(
+            phases.iter().map(|(phase, _)| phase).collect::<Vec<_>>(),
+            vec![Phase::Request]
+        );
+    }
+
+    #[test]
+    fn phases_are_indexed_in_order() {
+        for (index, phase) in Phase::ALL.into_iter().enumerate() {
+            assert_eq!(phase.index(), index);
+        }
+    }
+}
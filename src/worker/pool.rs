@@ -0,0 +1,103 @@
+use {
+    super::*,
+    std::{
+        num::NonZeroU32,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+    },
+    tokio::sync::OnceCell,
+};
+
+/// a pool of long-lived http/2 connections.
+///
+/// a pool may be cheaply cloned and shared among many workers. each request is assigned to one of
+/// the pool's connections in a round-robin fashion, and many requests may be multiplexed onto a
+/// single connection at once.
+///
+/// connections are established lazily, when a slot in the pool is first used. if a connection is
+/// closed, the next request assigned to that slot will reconnect to the server.
+#[derive(Clone)]
+pub struct Pool {
+    /// the server to send requests to.
+    target: Target,
+    /// the connections in this pool.
+    slots: Arc<[Slot]>,
+    /// the index of the next slot to use.
+    next: Arc<AtomicUsize>,
+}
+
+/// a slot in a [`Pool`], holding a connection once it has been established.
+///
+/// the lock is only held while the cell is swapped, so that workers do not wait upon it while a
+/// connection is established. workers share the cell, so that only one of them connects.
+///
+/// NB: the worker held in a slot owns the background task driving the connection.
+type Slot = Mutex<Arc<OnceCell<Worker>>>;
+
+// === impl Pool ===
+
+impl Pool {
+    /// returns a new pool of connections to the given server.
+    pub fn new(target: Target, size: NonZeroU32) -> Self {
+        let slots = (0..size.get()).map(|_| Slot::default()).collect();
+
+        Self {
+            target,
+            slots,
+            next: Default::default(),
+        }
+    }
+
+    /// returns the timeouts bounding each phase of a worker.
+    pub fn timeouts(&self) -> Timeouts {
+        self.target.timeouts
+    }
+
+    /// returns the number of connections in this pool.
+    pub fn size(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// checks out a worker that will send its request upon one of the pool's connections.
+    ///
+    /// if a new connection is established, the time spent connecting is recorded in `phases`.
+    #[instrument(skip_all, fields(host = %self.target.host, port = %self.target.port))]
+    pub(super) async fn checkout(&self, phases: &mut Phases) -> Result<Worker, Failure> {
+        let Self {
+            target,
+            slots,
+            next,
+        } = self;
+
+        // pick the next slot, replacing its connection if it has been closed.
+        let idx = next.fetch_add(1, Ordering::Relaxed) % slots.len();
+        let cell = {
+            let mut slot = slots[idx].lock().expect("pool slot should not be poisoned");
+            if slot.get().is_some_and(|Worker { tx, .. }| tx.is_closed()) {
+                debug!(%idx, "pooled connection was closed");
+                *slot = Arc::default();
+            }
+            Arc::clone(&slot)
+        };
+
+        // connect to the server, if no other worker has done so already.
+        let Worker { tx, scheme, .. } = cell
+            .get_or_try_init(|| {
+                debug!(%idx, "opening pooled connection");
+                Worker::connect(target.clone(), phases)
+            })
+            .tap(|_| trace!(%idx, "waiting for pooled connection"))
+            .await?;
+
+        // return a worker, sharing the sender-side of this connection.
+        Ok(Worker {
+            host: target.host.clone(),
+            port: target.port,
+            scheme: scheme.clone(),
+            tx: tx.clone(),
+            conn: None,
+        })
+    }
+}
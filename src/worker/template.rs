@@ -0,0 +1,121 @@
+use {
+    super::*,
+    http::{
+        uri::{Authority, PathAndQuery, Scheme},
+        HeaderMap, Method, Uri,
+    },
+};
+
+/// a template describing the request that a [`Worker`] sends to the server.
+///
+/// by default, this is a `GET` request to `/` with an empty body.
+#[derive(Clone, Debug)]
+pub struct Template {
+    /// the request method.
+    pub method: Method,
+    /// the path and query to send the request to.
+    pub path: PathAndQuery,
+    /// additional headers to include in the request.
+    pub headers: HeaderMap,
+    /// the request body.
+    pub body: Bytes,
+    /// the rules that the response must satisfy.
+    pub validation: Validation,
+}
+
+// === impl Template ===
+
+impl Default for Template {
+    fn default() -> Self {
+        Self {
+            method: Method::GET,
+            path: PathAndQuery::from_static("/"),
+            headers: HeaderMap::new(),
+            body: Bytes::new(),
+            validation: Validation::default(),
+        }
+    }
+}
+
+impl Template {
+    /// returns a new template, sending a request with the given method to the given path.
+    pub fn new(method: Method, path: PathAndQuery) -> Self {
+        Self {
+            method,
+            path,
+            ..Default::default()
+        }
+    }
+
+    /// sets the headers included in the request.
+    pub fn headers(self, headers: HeaderMap) -> Self {
+        Self { headers, ..self }
+    }
+
+    /// sets the request body.
+    pub fn body(self, body: Bytes) -> Self {
+        Self { body, ..self }
+    }
+
+    /// sets the rules that the response must satisfy.
+    pub fn validation(self, validation: Validation) -> Self {
+        Self { validation, ..self }
+    }
+
+    /// builds a request to send to the given server.
+    pub(super) fn build(
+        &self,
+        scheme: Scheme,
+        host: &Host,
+        port: u16,
+    ) -> Result<Request<Full<Bytes>>, Error> {
+        let Self {
+            method,
+            path,
+            headers,
+            body,
+            validation: _,
+        } = self;
+
+        let uri = Uri::builder()
+            .scheme(scheme)
+            .authority(format!("{host}:{port}").parse::<Authority>()?)
+            .path_and_query(path.clone())
+            .build()?;
+
+        let mut req = Request::builder()
+            .method(method.clone())
+            .uri(uri)
+            .body(Full::new(body.clone()))?;
+        req.headers_mut().extend(headers.clone());
+
+        Ok(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_template_sends_a_get_request_to_root() -> Result<(), Error> {
+        let req = Template::default().build(Scheme::HTTP, &crate::LOCALHOST, 8080)?;
+        assert_eq!(req.method(), Method::GET);
+        assert_eq!(req.uri(), "http://[::1]:8080/");
+        Ok(())
+    }
+
+    #[test]
+    fn template_builds_a_request() -> Result<(), Error> {
+        let headers = HeaderMap::from_iter([("x-hello".parse()?, "world".parse()?)]);
+        let req = Template::new(Method::POST, PathAndQuery::from_static("/items?x=1"))
+            .headers(headers)
+            .body(Bytes::from_static(b"hello"))
+            .build(Scheme::HTTPS, &Host::Domain("example.com".to_owned()), 443)?;
+
+        assert_eq!(req.method(), Method::POST);
+        assert_eq!(req.uri(), "https://example.com:443/items?x=1");
+        assert_eq!(req.headers()["x-hello"], "world");
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use {super::*, std::future::Future};
+
+/// bounds on how long a [`Worker`] may spend in each of its phases.
+///
+/// a worker that exceeds a timeout fails with a [`Failure::Timeout`], marked with the [`Phase`]
+/// that it was in. a worker that exceeds its total timeout fails with a
+/// [`Failure::TotalTimeout`] instead. a phase with no timeout may take as long as the server
+/// allows.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Timeouts {
+    /// bounds resolving the server's host name, and establishing a tcp connection.
+    pub connect: Option<Duration>,
+    /// bounds the tls and http/2 handshakes.
+    pub handshake: Option<Duration>,
+    /// bounds sending the request and reading the response, including its body.
+    pub request: Option<Duration>,
+    /// bounds the worker from start to finish.
+    pub total: Option<Duration>,
+}
+
+// === impl Timeouts ===
+
+impl Timeouts {
+    /// awaits the given future, unless the timeout elapses first.
+    ///
+    /// returns `None` if the timeout elapsed, see [`Phases::timed_out()`].
+    pub(super) async fn bound<F>(timeout: Option<Duration>, fut: F) -> Option<F::Output>
+    where
+        F: Future,
+    {
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, fut).await.ok(),
+            None => fut.await.pipe(Some),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn timeouts_bound_futures() {
+        let sleep = || tokio::time::sleep(Duration::from_millis(50));
+        assert_eq!(Timeouts::bound(None, sleep()).await, Some(()));
+        assert_eq!(
+            Timeouts::bound(Some(Duration::from_secs(5)), sleep()).await,
+            Some(())
+        );
+        assert_eq!(
+            Timeouts::bound(Some(Duration::from_millis(1)), sleep()).await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn timeouts_are_attributed_to_the_interrupted_phase() {
+        let mut phases = Phases::default();
+        let fut = async {
+            phases.time(Phase::Connect, async {}).await;
+            phases
+                .time(Phase::Handshake, futures::future::pending::<()>())
+                .await;
+        };
+        assert_eq!(
+            Timeouts::bound(Some(Duration::from_millis(10)), fut).await,
+            None
+        );
+
+        assert_eq!(
+            phases.timed_out(Phase::Dns),
+            Failure::Timeout(Phase::Handshake)
+        );
+        assert!(phases.get(Phase::Handshake) >= Some(Duration::from_millis(10)));
+        assert_eq!(
+            phases.timed_out(Phase::Dns),
+            Failure::Timeout(Phase::Dns),
+            "no phase is in progress"
+        );
+    }
+
+    #[tokio::test]
+    async fn total_timeouts_are_not_attributed_to_a_phase() {
+        let mut phases = Phases::default();
+        let fut = phases.time(Phase::Handshake, futures::future::pending::<()>());
+        assert_eq!(
+            Timeouts::bound(Some(Duration::from_millis(10)), fut).await,
+            None
+        );
+
+        assert_eq!(phases.total_timed_out(), Failure::TotalTimeout);
+        assert!(phases.get(Phase::Handshake) >= Some(Duration::from_millis(10)));
+        assert_eq!(phases.total_timed_out(), Failure::TotalTimeout);
+    }
+}
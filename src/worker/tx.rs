@@ -4,26 +4,28 @@ use super::*;
 
 impl Worker {
     /// sends a request to the server.
-    #[instrument(skip(self), fields(host = %self.host, port = %self.port))]
-    pub(super) async fn tx(&mut self) -> Result<Response<Incoming>, hyper::Error> {
-        let Self { tx, host, .. } = self;
+    #[instrument(skip_all, fields(host = %self.host, port = %self.port))]
+    pub(super) async fn tx(
+        &mut self,
+        request: &Template,
+        phases: &mut Phases,
+    ) -> Result<Response<Incoming>, Failure> {
+        let Self {
+            tx,
+            scheme,
+            host,
+            port,
+            ..
+        } = self;
 
-        let req = Self::req(host);
-        tx.send_request(req)
+        let req = request
+            .build(scheme.clone(), host, *port)
+            .map_err(|err| Failure::classify(Phase::Request, err))?;
+        phases
+            .time(Phase::Request, tx.send_request(req))
             .tap(|_| trace!("sending request"))
             .await
             .tap(|_| debug!("received response"))
+            .map_err(|err| Failure::classify(Phase::Request, err))
     }
-
-    /// returns a request to send to the server.
-    fn req(host: &Host) -> Request<Full<Bytes>> {
-        let body = Full::new(Self::BODY);
-        Request::builder()
-            .header("host", host.to_string())
-            .body(body)
-            .unwrap()
-    }
-
-    /// a request body.
-    const BODY: Bytes = Bytes::from_static(b"request body");
 }
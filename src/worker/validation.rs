@@ -0,0 +1,461 @@
+use {
+    super::*,
+    http::{HeaderMap, HeaderName, HeaderValue},
+    http_body_util::{BodyExt, LengthLimitError, Limited},
+    serde_json::Value,
+    std::{fmt, str::FromStr},
+};
+
+/// rules that a response must satisfy, for a [`Worker`] to succeed.
+///
+/// by default, a response succeeds if its status code is `2xx`. responses with other status codes
+/// are unsuccessful, but are not counted as a [`Failure`]. if any status codes are given, a
+/// response with any other status code fails with [`Violation::Status`].
+///
+/// the remaining rules are only checked for responses with an acceptable status code. a response
+/// that breaks a rule fails with a [`Failure::Invalid`], marked with the kind of rule it broke.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Validation {
+    /// the status codes that a response may have.
+    pub statuses: Vec<StatusRange>,
+    /// headers that a response must include.
+    pub headers: Vec<HeaderRule>,
+    /// rules that the response body must match.
+    pub body: Vec<BodyRule>,
+    /// the largest response body that is permitted, in bytes.
+    pub max_body_size: Option<usize>,
+    /// the sha-256 digest that the response body must have.
+    pub sha256: Option<Sha256>,
+}
+
+/// an inclusive range of status codes, e.g. `200`, `200-299` or `2xx`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StatusRange {
+    /// the lowest status code in this range.
+    pub min: StatusCode,
+    /// the highest status code in this range.
+    pub max: StatusCode,
+}
+
+/// a header that a response must include, given as `name` or a `name:value` pair.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HeaderRule {
+    /// the header name.
+    pub name: HeaderName,
+    /// the header value, if the header must have a particular value.
+    pub value: Option<HeaderValue>,
+}
+
+/// a rule that the response body must match.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BodyRule {
+    /// the body must be exactly these bytes.
+    Exact(Bytes),
+    /// the body must match this regular expression.
+    Regex(Pattern),
+    /// the body must be json, with this value at the given pointer.
+    Json(JsonRule),
+}
+
+/// a regular expression, matched against response bodies.
+#[derive(Clone, Debug)]
+pub struct Pattern(regex::bytes::Regex);
+
+/// a json value that a response body must contain, given as a `POINTER=VALUE` pair.
+///
+/// the pointer follows rfc 6901, e.g. `/data/0/status`. the value is parsed as json, or treated as
+/// a string if it is not valid json, e.g. `/status=ok` or `/items/0/count=3`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JsonRule {
+    /// a json pointer, identifying a value within the body.
+    pub pointer: String,
+    /// the expected value.
+    pub value: Value,
+}
+
+/// a sha-256 digest, written in hexadecimal.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Sha256([u8; 32]);
+
+/// the kind of [`Validation`] rule that a response broke.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Violation {
+    /// the response had an unexpected status code.
+    Status,
+    /// the response was missing a header, or a header had an unexpected value.
+    Header,
+    /// the response body did not match.
+    Body,
+    /// the response body was too large.
+    BodySize,
+    /// the response body had an unexpected digest.
+    Hash,
+}
+
+/// the outcome of validating a response.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum Verdict {
+    /// the response satisfied each rule.
+    Accepted,
+    /// the response had an unsuccessful status code, and no status codes were expected.
+    Rejected,
+    /// the response broke a rule.
+    Invalid(Violation),
+}
+
+// === impl Validation ===
+
+impl Validation {
+    /// reads a response body.
+    ///
+    /// if a maximum body size was given, reading stops once the body grows larger than it.
+    pub(super) async fn read(&self, body: Incoming) -> Result<Bytes, Error> {
+        match self.max_body_size {
+            Some(limit) => Limited::new(body, limit).collect().await?,
+            None => body.collect().await?,
+        }
+        .to_bytes()
+        .pipe(Ok)
+    }
+
+    /// returns a verdict for a response body that could not be read.
+    ///
+    /// this is `None` if the error was not caused by breaking a rule.
+    pub(super) fn reject(err: &Error) -> Option<Verdict> {
+        err.is::<LengthLimitError>()
+            .then_some(Verdict::Invalid(Violation::BodySize))
+    }
+
+    /// checks a response against each rule.
+    pub(super) fn check(&self, resp: &Parts, body: &Bytes) -> Verdict {
+        let Self {
+            statuses,
+            headers,
+            body: rules,
+            max_body_size: _,
+            sha256,
+        } = self;
+
+        let violation = if statuses.is_empty() && !resp.status.is_success() {
+            return Verdict::Rejected;
+        } else if !statuses.is_empty() && !statuses.iter().any(|r| r.contains(resp.status)) {
+            Violation::Status
+        } else if !headers.iter().all(|rule| rule.matches(&resp.headers)) {
+            Violation::Header
+        } else if !BodyRule::all_match(rules, body) {
+            Violation::Body
+        } else if sha256.is_some_and(|digest| digest != Sha256::digest(body)) {
+            Violation::Hash
+        } else {
+            return Verdict::Accepted;
+        };
+
+        debug!(?violation, status = %resp.status, "response broke a validation rule");
+        Verdict::Invalid(violation)
+    }
+}
+
+// === impl StatusRange ===
+
+impl StatusRange {
+    /// returns true if the given status code is within this range.
+    pub fn contains(&self, status: StatusCode) -> bool {
+        (self.min..=self.max).contains(&status)
+    }
+}
+
+impl FromStr for StatusRange {
+    type Err = Error;
+    fn from_str(range: &str) -> Result<Self, Self::Err> {
+        let parse = |code: &str| code.trim().parse::<StatusCode>();
+        let (min, max) = if let Some((min, max)) = range.split_once('-') {
+            (parse(min)?, parse(max)?)
+        } else if let Some(class) = range.strip_suffix("xx").and_then(|c| c.parse::<u16>().ok()) {
+            let min = StatusCode::from_u16(class * 100)?;
+            let max = StatusCode::from_u16(class * 100 + 99)?;
+            (min, max)
+        } else {
+            let code = parse(range)?;
+            (code, code)
+        };
+
+        if min > max {
+            return Err(format!("status range `{range}` is empty").into());
+        }
+
+        Ok(Self { min, max })
+    }
+}
+
+// === impl HeaderRule ===
+
+impl HeaderRule {
+    /// returns true if the given headers satisfy this rule.
+    fn matches(&self, headers: &HeaderMap) -> bool {
+        let mut values = headers.get_all(&self.name).iter();
+        match &self.value {
+            Some(expected) => values.any(|value| value == expected),
+            None => values.next().is_some(),
+        }
+    }
+}
+
+impl FromStr for HeaderRule {
+    type Err = Error;
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let (name, value) = match rule.split_once(':') {
+            Some((name, value)) => (name, Some(value.trim().parse::<HeaderValue>()?)),
+            None => (rule, None),
+        };
+        let name = name.trim().parse::<HeaderName>()?;
+
+        Ok(Self { name, value })
+    }
+}
+
+// === impl BodyRule ===
+
+impl BodyRule {
+    /// returns true if the given body matches each rule.
+    ///
+    /// NB: the body is only parsed as json once, and only if a json rule is given.
+    fn all_match(rules: &[Self], body: &Bytes) -> bool {
+        let mut json = None;
+        rules.iter().all(|rule| match rule {
+            Self::Exact(expected) => body == expected,
+            Self::Regex(Pattern(regex)) => regex.is_match(body),
+            Self::Json(JsonRule { pointer, value }) => json
+                .get_or_insert_with(|| serde_json::from_slice::<Value>(body).ok())
+                .as_ref()
+                .and_then(|json| json.pointer(pointer))
+                .is_some_and(|found| found == value),
+        })
+    }
+}
+
+// === impl Pattern ===
+
+impl FromStr for Pattern {
+    type Err = Error;
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        regex::bytes::Regex::new(pattern)?.pipe(Self).pipe(Ok)
+    }
+}
+
+/// NB: [`Regex`][regex::bytes::Regex] is not [`Eq`], so patterns are compared by their source.
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Eq for Pattern {}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.as_str())
+    }
+}
+
+// === impl JsonRule ===
+
+impl FromStr for JsonRule {
+    type Err = Error;
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let (pointer, value) = rule
+            .split_once('=')
+            .ok_or("json rule must be given as `POINTER=VALUE`")?;
+        if !(pointer.is_empty() || pointer.starts_with('/')) {
+            return Err(format!("json pointer `{pointer}` must begin with `/`").into());
+        }
+        let value = serde_json::from_str(value).unwrap_or_else(|_| Value::from(value));
+
+        Ok(Self {
+            pointer: pointer.to_owned(),
+            value,
+        })
+    }
+}
+
+// === impl Sha256 ===
+
+impl Sha256 {
+    /// returns the sha-256 digest of the given bytes.
+    pub fn digest(bytes: &[u8]) -> Self {
+        let digest = ring::digest::digest(&ring::digest::SHA256, bytes);
+        digest
+            .as_ref()
+            .try_into()
+            .map(Self)
+            .expect("sha-256 digests are 32 bytes")
+    }
+}
+
+impl FromStr for Sha256 {
+    type Err = Error;
+    fn from_str(hex: &str) -> Result<Self, Self::Err> {
+        let hex = hex.trim();
+        if hex.len() != 64 || !hex.is_ascii() {
+            return Err("sha-256 digest must be 64 hexadecimal digits".into());
+        }
+        let mut digest = [0; 32];
+        for (byte, pair) in digest.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            let pair = std::str::from_utf8(pair)?;
+            *byte = u8::from_str_radix(pair, 16)?;
+        }
+
+        Ok(Self(digest))
+    }
+}
+
+impl fmt::Display for Sha256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+// === impl Violation ===
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status => f.write_str("status"),
+            Self::Header => f.write_str("header"),
+            Self::Body => f.write_str("body"),
+            Self::BodySize => f.write_str("body size"),
+            Self::Hash => f.write_str("hash"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, http::Response};
+
+    /// returns the parts of a response with the given status and headers.
+    fn parts(status: u16, headers: &[(&'static str, &'static str)]) -> Parts {
+        let mut resp = Response::builder().status(status);
+        for (name, value) in headers {
+            resp = resp.header(*name, *value);
+        }
+        resp.body(()).unwrap().into_parts().0
+    }
+
+    #[test]
+    fn status_ranges_can_be_parsed() -> Result<(), Error> {
+        let range = |min, max| StatusRange {
+            min: StatusCode::from_u16(min).unwrap(),
+            max: StatusCode::from_u16(max).unwrap(),
+        };
+        assert_eq!("204".parse::<StatusRange>()?, range(204, 204));
+        assert_eq!("200-299".parse::<StatusRange>()?, range(200, 299));
+        assert_eq!("4xx".parse::<StatusRange>()?, range(400, 499));
+        "299-200".parse::<StatusRange>().unwrap_err();
+        "ok".parse::<StatusRange>().unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn unsuccessful_statuses_are_rejected_by_default() {
+        let validation = Validation::default();
+        let body = Bytes::new();
+        assert_eq!(validation.check(&parts(200, &[]), &body), Verdict::Accepted);
+        assert_eq!(validation.check(&parts(503, &[]), &body), Verdict::Rejected);
+    }
+
+    #[test]
+    fn expected_statuses_are_checked() -> Result<(), Error> {
+        let validation = Validation {
+            statuses: vec!["201".parse()?, "3xx".parse()?],
+            ..Default::default()
+        };
+        let body = Bytes::new();
+        assert_eq!(validation.check(&parts(201, &[]), &body), Verdict::Accepted);
+        assert_eq!(validation.check(&parts(302, &[]), &body), Verdict::Accepted);
+        assert_eq!(
+            validation.check(&parts(200, &[]), &body),
+            Verdict::Invalid(Violation::Status)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn headers_are_checked() -> Result<(), Error> {
+        let validation = Validation {
+            headers: vec!["x-request-id".parse()?, "content-type: text/plain".parse()?],
+            ..Default::default()
+        };
+        let body = Bytes::new();
+        let valid = parts(
+            200,
+            &[("x-request-id", "1"), ("content-type", "text/plain")],
+        );
+        assert_eq!(validation.check(&valid, &body), Verdict::Accepted);
+        let wrong_value = parts(200, &[("x-request-id", "1"), ("content-type", "text/html")]);
+        assert_eq!(
+            validation.check(&wrong_value, &body),
+            Verdict::Invalid(Violation::Header)
+        );
+        let missing = parts(200, &[("content-type", "text/plain")]);
+        assert_eq!(
+            validation.check(&missing, &body),
+            Verdict::Invalid(Violation::Header)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bodies_are_checked() -> Result<(), Error> {
+        let validation = Validation {
+            body: vec![
+                BodyRule::Regex(r#""status":\s*"ok""#.parse()?),
+                BodyRule::Json("/items/1/count=3".parse()?),
+                BodyRule::Json("/status=ok".parse()?),
+            ],
+            ..Default::default()
+        };
+        let check = |body: &'static str| validation.check(&parts(200, &[]), &Bytes::from(body));
+        assert_eq!(
+            check(r#"{"status": "ok", "items": [{}, {"count": 3}]}"#),
+            Verdict::Accepted
+        );
+        assert_eq!(
+            check(r#"{"status": "ok", "items": [{}, {"count": 4}]}"#),
+            Verdict::Invalid(Violation::Body)
+        );
+        assert_eq!(
+            check("<html>an error page</html>"),
+            Verdict::Invalid(Violation::Body)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn digests_are_checked() -> Result<(), Error> {
+        const HELLO: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        let digest = HELLO.parse::<Sha256>()?;
+        assert_eq!(digest, Sha256::digest(b"hello"));
+        assert_eq!(digest.to_string(), HELLO);
+
+        let validation = Validation {
+            sha256: Some(digest),
+            ..Default::default()
+        };
+        let check = |body: &'static str| validation.check(&parts(200, &[]), &Bytes::from(body));
+        assert_eq!(check("hello"), Verdict::Accepted);
+        assert_eq!(check("goodbye"), Verdict::Invalid(Violation::Hash));
+        "abc".parse::<Sha256>().unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn json_rules_can_be_parsed() -> Result<(), Error> {
+        let rule = "/a/b=[1, 2]".parse::<JsonRule>()?;
+        assert_eq!(rule.pointer, "/a/b");
+        assert_eq!(rule.value, serde_json::json!([1, 2]));
+        assert_eq!("/a=ok".parse::<JsonRule>()?.value, Value::from("ok"));
+        "a=ok".parse::<JsonRule>().unwrap_err();
+        "/a".parse::<JsonRule>().unwrap_err();
+        Ok(())
+    }
+}
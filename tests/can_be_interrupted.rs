@@ -0,0 +1,110 @@
+//! run the `aquarius` binary, and interrupt it before its load-test has finished.
+
+use {
+    aquarius::interrupt::Interrupt,
+    aquarius_test_server::{Latency, TestServer},
+    std::{
+        process::{Child, Command, Output, Stdio},
+        time::{Duration, Instant},
+    },
+    tap::Tap,
+};
+
+/// spawns the `aquarius` binary, writing a json summary to stdout.
+fn spawn(port: u16, args: &[&str]) -> std::io::Result<Child> {
+    Command::new(env!("CARGO_BIN_EXE_aquarius"))
+        .args(["--rate", "64", "--duration", "60s", "--output", "json"])
+        .args(args)
+        .arg(format!("[::1]:{port}"))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+}
+
+/// sends a `SIGINT` to a child process.
+fn interrupt(child: &Child) -> std::io::Result<()> {
+    let status = Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()?;
+    assert!(status.success(), "signal should be sent");
+    Ok(())
+}
+
+/// waits until the server has received a request, and the load-test is listening for interrupts.
+async fn started(server: &TestServer) {
+    while server.reqs_received() == 0 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    tokio::time::sleep(Duration::from_millis(250)).await;
+}
+
+/// waits for a child process to exit, returning its output.
+async fn wait(child: Child) -> Result<Output, aquarius::error::Error> {
+    tokio::task::spawn_blocking(|| child.wait_with_output())
+        .await?
+        .map_err(Into::into)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn interrupted_load_tests_cancel_slow_workers() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::builder()
+        .latency(Latency::Fixed(Duration::from_secs(60)))
+        .start()
+        .await?;
+    let child = spawn(server.port, &[])?;
+    started(&server).await;
+
+    // without a `--grace` period, in-flight workers are cancelled after the default grace period.
+    let start = Instant::now();
+    interrupt(&child)?;
+    let output = wait(child).await?;
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Interrupt::DEFAULT_GRACE, "workers may drain");
+    assert!(
+        elapsed < Interrupt::DEFAULT_GRACE * 2,
+        "workers are cancelled"
+    );
+
+    assert_eq!(output.status.code(), Some(Interrupt::EXIT_CODE.into()));
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(summary["interrupted"], true);
+    assert!(summary["total"].as_u64().is_some_and(|total| total > 0));
+    assert_eq!(summary["failures"]["cancelled"], summary["total"]);
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn second_interrupts_cancel_workers_immediately() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::builder()
+        .latency(Latency::Fixed(Duration::from_secs(60)))
+        .start()
+        .await?;
+    let child = spawn(server.port, &["--grace", "60s"])?;
+    started(&server).await;
+
+    let start = Instant::now();
+    interrupt(&child)?;
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    interrupt(&child)?;
+    let output = wait(child).await?;
+    assert!(
+        start.elapsed() < Duration::from_secs(5),
+        "workers should be cancelled"
+    );
+
+    assert_eq!(output.status.code(), Some(Interrupt::EXIT_CODE.into()));
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(summary["interrupted"], true);
+    assert_eq!(summary["failures"]["cancelled"], summary["total"]);
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
@@ -0,0 +1,59 @@
+//! spawn a test server, and send requests to it from a fixed number of virtual users.
+
+use {
+    aquarius::{syndicate::Syndicate, worker::Report},
+    aquarius_test_server::TestServer,
+    futures::StreamExt,
+    std::{num::NonZeroU32, time::Duration},
+    tap::Tap,
+    tracing::info,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 64)]
+async fn can_bound_concurrency() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    const COUNT: u32 = 32;
+    const USERS: u32 = 4;
+
+    let server = TestServer::start().await?;
+    info!("test server is running");
+
+    let syndicate = Syndicate::local(server.port)
+        .total(Some(COUNT))
+        .connections(NonZeroU32::new(1))
+        .concurrency(NonZeroU32::new(USERS))
+        .think_time(Some(Duration::from_millis(1)))
+        .start()?;
+    info!("workers are running");
+
+    let reports = syndicate.collect::<Vec<Report>>().await;
+    info!("collected reports");
+
+    assert_eq!(reports.len(), COUNT as usize);
+    assert!(
+        reports.iter().all(|r| r.success),
+        "all requests should succeed"
+    );
+    assert_eq!(server.reqs_received(), COUNT);
+
+    // no more requests than there are users should ever be in flight at once.
+    let max_in_flight = reports
+        .iter()
+        .map(|r| {
+            reports
+                .iter()
+                .filter(|other| other.start <= r.start && r.start < other.end)
+                .count()
+        })
+        .max()
+        .unwrap_or_default();
+    assert!(
+        max_in_flight <= USERS as usize,
+        "at most {USERS} requests should be in flight, but {max_in_flight} were observed"
+    );
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
@@ -0,0 +1,199 @@
+//! spawn a test server that misbehaves at the http/2 layer, and observe how failures are
+//! classified.
+
+use {
+    aquarius::{
+        summary::Summary,
+        syndicate::Syndicate,
+        worker::{Failure, Report, Template},
+    },
+    aquarius_test_server::{Latency, TestServer},
+    futures::StreamExt,
+    h2::Reason,
+    std::{
+        num::NonZeroU32,
+        time::{Duration, Instant},
+    },
+    tap::Tap,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn reset_streams_are_classified() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    const COUNT: u32 = 8;
+
+    let server = TestServer::builder()
+        .reset_rate(100.0)
+        .reset_reasons(vec![Reason::ENHANCE_YOUR_CALM])
+        .start()
+        .await?;
+
+    let summary = Syndicate::local(server.port)
+        .total(Some(COUNT))
+        .rps(Some(64))
+        .start()?
+        .collect::<Summary>()
+        .await;
+
+    assert_eq!(
+        summary.failures().collect::<Vec<_>>(),
+        vec![(Failure::Reset(Reason::ENHANCE_YOUR_CALM), COUNT)]
+    );
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn streams_are_refused_by_default() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    const COUNT: u32 = 8;
+
+    // without any reset reasons, streams are reset with `REFUSED_STREAM`, and are not retried.
+    let server = TestServer::builder().reset_rate(100.0).start().await?;
+
+    let summary = Syndicate::local(server.port)
+        .total(Some(COUNT))
+        .rps(Some(64))
+        .start()?
+        .collect::<Summary>()
+        .await;
+
+    assert_eq!(
+        summary.failures().collect::<Vec<_>>(),
+        vec![(Failure::Reset(Reason::REFUSED_STREAM), COUNT)]
+    );
+    assert_eq!(server.reqs_received(), COUNT);
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn abrupt_closes_are_classified() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    const COUNT: u32 = 8;
+
+    let server = TestServer::builder().close_rate(100.0).start().await?;
+
+    let summary = Syndicate::local(server.port)
+        .total(Some(COUNT))
+        .rps(Some(64))
+        .start()?
+        .collect::<Summary>()
+        .await;
+
+    assert_eq!(
+        summary.failures().collect::<Vec<_>>(),
+        vec![(Failure::Closed, COUNT)]
+    );
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn goaway_frames_cause_reconnects() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    const COUNT: u32 = 8;
+
+    // each connection is gracefully shut down after it has received two streams.
+    let server = TestServer::builder()
+        .goaway_after(NonZeroU32::new(2))
+        .start()
+        .await?;
+
+    let reports = Syndicate::local(server.port)
+        .total(Some(COUNT))
+        .rps(Some(32))
+        .connections(NonZeroU32::new(1))
+        .start()?
+        .collect::<Vec<Report>>()
+        .await;
+
+    // streams that the server processed may finish, and the client opens a new connection.
+    assert_eq!(reports.len(), COUNT as usize);
+    for report in reports {
+        assert!(
+            report.success,
+            "request should succeed: {:?}",
+            report.failure
+        );
+    }
+    assert!(server.conns_accepted() > 1, "client should reconnect");
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn max_concurrent_streams_are_respected() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    const COUNT: u32 = 4;
+    const LATENCY: Duration = Duration::from_millis(50);
+
+    // one stream may be open at once, so requests sharing a connection are served in turn.
+    //
+    // NB: requests are spaced apart so that the server's SETTINGS frame arrives before a second
+    // stream is opened. otherwise, streams exceeding the limit are refused.
+    let server = TestServer::builder()
+        .max_concurrent_streams(Some(1))
+        .latency(Latency::Fixed(LATENCY))
+        .start()
+        .await?;
+
+    let start = Instant::now();
+    let summary = Syndicate::local(server.port)
+        .total(Some(COUNT))
+        .rps(Some(100))
+        .connections(NonZeroU32::new(1))
+        .start()?
+        .collect::<Summary>()
+        .await;
+
+    assert_eq!(summary.success_count(), COUNT);
+    assert!(start.elapsed() >= LATENCY * COUNT, "{:?}", start.elapsed());
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn stalled_request_bodies_are_cancelled() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::builder().stall(true).start().await?;
+
+    // the server never grants the capacity to send this body.
+    //
+    // NB: clients may send up to the default window of 64KiB before the server's settings arrive.
+    let request = Template::default().body(vec![b'a'; 128 * 1024].into());
+    let summary = Syndicate::local(server.port)
+        .total(Some(4))
+        .rps(Some(64))
+        .duration(Some(Duration::from_millis(100)))
+        .grace(Some(Duration::from_millis(100)))
+        .request(request)
+        .start()?
+        .collect::<Summary>()
+        .await;
+
+    assert_eq!(summary.success_count(), 0);
+    assert_eq!(
+        summary.failures().collect::<Vec<_>>(),
+        vec![(Failure::Cancelled, summary.total())]
+    );
+
+    Ok(()).tap(|_| drop(guard))
+}
@@ -0,0 +1,48 @@
+//! spawn a test server, and send requests to it following a staged load profile.
+
+use {
+    aquarius::{
+        summary::Summary,
+        syndicate::{Profile, Syndicate},
+    },
+    aquarius_test_server::TestServer,
+    futures::StreamExt,
+    tap::Tap,
+    tracing::info,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn can_follow_a_profile() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::start().await?;
+    info!("test server is running");
+
+    // ramp up to 100 rps, and then hold that rate.
+    let profile = ["200ms:100", "200ms:100"]
+        .into_iter()
+        .map(str::parse)
+        .collect::<Result<Profile, _>>()?;
+
+    let summary = Syndicate::local(server.port)
+        .profile(Some(profile))
+        .start()?
+        .collect::<Summary>()
+        .await;
+    info!("collected reports");
+
+    assert_eq!(summary.total(), 30);
+    assert_eq!(summary.success_rate(), 100.0);
+    assert_eq!(
+        summary
+            .stages()
+            .map(|(index, stage)| (index, stage.total()))
+            .collect::<Vec<_>>(),
+        vec![(0, 10), (1, 20)],
+        "the ramp sends half as many requests as the plateau"
+    );
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
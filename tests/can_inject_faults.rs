@@ -0,0 +1,82 @@
+//! spawn a test server that injects latency and errors, and observe them in a load-test.
+
+use {
+    aquarius::{syndicate::Syndicate, worker::Report},
+    aquarius_test_server::{Drip, Latency, TestServer},
+    futures::StreamExt,
+    http::StatusCode,
+    std::time::Duration,
+    tap::Tap,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn can_inject_errors() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    const COUNT: u32 = 8;
+
+    let server = TestServer::builder()
+        .error_rate(100.0)
+        .error_statuses(vec![StatusCode::TOO_MANY_REQUESTS])
+        .start()
+        .await?;
+
+    let reports = Syndicate::local(server.port)
+        .total(Some(COUNT))
+        .rps(Some(64))
+        .start()?
+        .collect::<Vec<Report>>()
+        .await;
+
+    assert_eq!(reports.len(), COUNT as usize);
+    for report in reports {
+        assert!(!report.success, "injected errors should not succeed");
+        assert_eq!(report.status, Some(StatusCode::TOO_MANY_REQUESTS));
+        assert_eq!(report.failure, None, "a response was received");
+    }
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn can_inject_latency_and_slow_bodies() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    const COUNT: u32 = 4;
+    const LATENCY: Duration = Duration::from_millis(50);
+    const INTERVAL: Duration = Duration::from_millis(20);
+
+    // respond after a fixed delay, then send a body of 4 chunks, waiting between each.
+    let server = TestServer::builder()
+        .latency(Latency::Fixed(LATENCY))
+        .body_size(Some(1024))
+        .drip(Some(Drip {
+            chunk_size: 256,
+            interval: INTERVAL,
+        }))
+        .start()
+        .await?;
+
+    let reports = Syndicate::local(server.port)
+        .total(Some(COUNT))
+        .rps(Some(64))
+        .start()?
+        .collect::<Vec<Report>>()
+        .await;
+
+    assert_eq!(reports.len(), COUNT as usize);
+    for report in reports {
+        assert!(report.success, "request should succeed");
+        assert!(
+            report.duration >= LATENCY + INTERVAL * 3,
+            "{:?}",
+            report.duration
+        );
+    }
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
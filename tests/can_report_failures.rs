@@ -0,0 +1,58 @@
+//! run a load-test against a server that is not listening, and observe its failures.
+
+use {
+    aquarius::{
+        summary::Summary,
+        syndicate::Syndicate,
+        worker::{Failure, Worker},
+    },
+    futures::StreamExt,
+    std::net::Ipv6Addr,
+    tap::Tap,
+    tokio::net::TcpListener,
+};
+
+/// returns a local port that no server is listening on.
+async fn unused_port() -> Result<u16, aquarius::error::Error> {
+    let listener = TcpListener::bind((Ipv6Addr::LOCALHOST, 0)).await?;
+    let port = listener.local_addr()?.port();
+    drop(listener);
+    Ok(port)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn worker_reports_a_refused_connection() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let report = Worker::run_local(unused_port().await?).await;
+    assert!(!report.success, "request should fail");
+    assert_eq!(report.status, None);
+    assert_eq!(report.failure, Some(Failure::ConnectRefused));
+
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn failures_do_not_abort_a_load_test() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    const COUNT: u32 = 8;
+
+    let summary = Syndicate::local(unused_port().await?)
+        .total(Some(COUNT))
+        .rps(Some(64))
+        .start()?
+        .collect::<Summary>()
+        .await;
+
+    assert_eq!(summary.total(), COUNT);
+    assert_eq!(summary.failure_count(), COUNT);
+    assert_eq!(
+        summary.failures().collect::<Vec<_>>(),
+        vec![(Failure::ConnectRefused, COUNT)]
+    );
+
+    Ok(()).tap(|_| drop(guard))
+}
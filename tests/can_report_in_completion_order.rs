@@ -0,0 +1,50 @@
+//! spawn a test server with varying latencies, and check that reports arrive as workers finish.
+
+use {
+    aquarius::{syndicate::Syndicate, worker::Report},
+    aquarius_test_server::{Latency, TestServer},
+    futures::StreamExt,
+    std::time::Duration,
+    tap::Tap,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn reports_are_yielded_in_completion_order() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    const COUNT: u32 = 16;
+    // NB: workers finishing at nearly the same moment may be yielded in either order.
+    const TOLERANCE: Duration = Duration::from_millis(20);
+
+    let server = TestServer::builder()
+        .latency(Latency::Uniform {
+            min: Duration::from_millis(10),
+            max: Duration::from_millis(300),
+        })
+        .start()
+        .await?;
+
+    let reports = Syndicate::local(server.port)
+        .total(Some(COUNT))
+        .rps(Some(1000))
+        .start()?
+        .collect::<Vec<Report>>()
+        .await;
+
+    assert_eq!(reports.len(), COUNT as usize);
+    for pair in reports.windows(2) {
+        let [earlier, later] = pair else {
+            unreachable!()
+        };
+        assert!(
+            later.end + TOLERANCE >= earlier.end,
+            "a report was held back by a slower worker: {:?} after {:?}",
+            later.duration,
+            earlier.duration,
+        );
+    }
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
@@ -0,0 +1,71 @@
+//! run load-tests bounded by a duration, and observe how in-flight workers are drained.
+
+use {
+    aquarius::{
+        summary::Summary,
+        syndicate::Syndicate,
+        worker::{Failure, Report},
+    },
+    aquarius_test_server::TestServer,
+    futures::StreamExt,
+    std::{net::Ipv6Addr, time::Duration},
+    tap::Tap,
+    tokio::net::TcpListener,
+    tracing::info,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn can_run_for_a_duration() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::start().await?;
+    info!("test server is running");
+
+    // with no total, the load-test would otherwise run forever.
+    let reports = Syndicate::local(server.port)
+        .rps(Some(64))
+        .duration(Some(Duration::from_millis(250)))
+        .start()?
+        .collect::<Vec<Report>>()
+        .await;
+    info!("collected reports");
+
+    assert!(!reports.is_empty(), "some requests should be sent");
+    assert!(
+        reports.iter().all(|r| r.success),
+        "all requests should succeed"
+    );
+    assert_eq!(server.reqs_received(), reports.len() as u32);
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn workers_are_cancelled_after_grace_period() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    // bind a listener that accepts connections, but never responds to them.
+    let listener = TcpListener::bind((Ipv6Addr::LOCALHOST, 0)).await?;
+    let port = listener.local_addr()?.port();
+
+    let summary = Syndicate::local(port)
+        .total(Some(4))
+        .rps(Some(64))
+        .grace(Some(Duration::from_millis(100)))
+        .start()?
+        .collect::<Summary>()
+        .await;
+
+    assert_eq!(summary.total(), 4, "cancelled workers should be reported");
+    assert_eq!(summary.success_rate(), 0.0);
+    assert_eq!(
+        summary.failures().collect::<Vec<_>>(),
+        vec![(Failure::Cancelled, 4)]
+    );
+
+    drop(listener);
+    Ok(()).tap(|_| drop(guard))
+}
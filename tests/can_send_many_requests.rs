@@ -3,7 +3,7 @@
 use {
     aquarius::{syndicate::Syndicate, worker::Report},
     aquarius_test_server::TestServer,
-    futures::TryStreamExt,
+    futures::StreamExt,
     tap::Tap,
     tracing::info,
 };
@@ -24,7 +24,7 @@ async fn can_send_many_requests() -> Result<(), aquarius::error::Error> {
         .start()?;
     info!("workers are running");
 
-    let reports = syndicate.try_collect::<Vec<Report>>().await?;
+    let reports = syndicate.collect::<Vec<Report>>().await;
     info!("collected reports");
 
     assert_eq!(reports.len(), COUNT as usize);
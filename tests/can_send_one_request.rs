@@ -8,7 +8,8 @@ async fn worker_can_send_one_request_to_a_server() -> Result<(), aquarius::error
     aquarius_test_timeout::spawn();
 
     let server = TestServer::start().await?;
-    Worker::run_local(server.port).await?;
+    let report = Worker::run_local(server.port).await;
+    assert!(report.success, "request should succeed");
     server.finish().await?;
 
     Ok(()).tap(|_| drop(guard))
@@ -0,0 +1,154 @@
+//! spawn a tls test server, and send requests to it over tls.
+
+use {
+    aquarius::{
+        cli,
+        tls::Connector,
+        worker::{Failure, Target, Worker},
+    },
+    aquarius_test_server::TestServer,
+    std::path::PathBuf,
+    tap::Tap,
+};
+
+/// writes a PEM-encoded file for the server listening on the given port, returning its path.
+fn write_pem(port: u16, name: &str, pem: Option<&str>) -> std::io::Result<PathBuf> {
+    let path = std::env::temp_dir().join(format!("aquarius-test-{name}-{port}.pem"));
+    std::fs::write(&path, pem.expect("server uses tls"))?;
+    Ok(path)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn worker_can_send_a_request_over_tls() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::start_tls().await?;
+
+    // write the server's certificate authority to a file, and trust it.
+    let ca_cert = write_pem(server.port, "ca", server.ca_cert())?;
+    let tls = Connector::new(&cli::Tls {
+        ca_cert: Some(ca_cert.clone()),
+        ..Default::default()
+    })?;
+
+    let report = Worker::run(
+        Target::local(server.port).tls(Some(tls)),
+        Default::default(),
+    )
+    .await;
+    assert!(report.success, "request should succeed");
+
+    std::fs::remove_file(ca_cert)?;
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn worker_can_skip_tls_verification() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::start_tls().await?;
+    let tls = Connector::new(&cli::Tls {
+        insecure: true,
+        ..Default::default()
+    })?;
+
+    let report = Worker::run(
+        Target::local(server.port).tls(Some(tls)),
+        Default::default(),
+    )
+    .await;
+    assert!(report.success, "request should succeed");
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn worker_rejects_an_untrusted_certificate() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::start_tls().await?;
+    let tls = Connector::new(&cli::Tls::default())?;
+
+    let report = Worker::run(
+        Target::local(server.port).tls(Some(tls)),
+        Default::default(),
+    )
+    .await;
+    assert_eq!(
+        report.failure,
+        Some(Failure::Tls),
+        "the server's certificate should not be trusted"
+    );
+
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn worker_can_present_a_client_certificate() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::builder().client_auth(true).start_tls().await?;
+    let ca_cert = write_pem(server.port, "ca", server.ca_cert())?;
+    let client_cert = write_pem(server.port, "client", server.client_cert())?;
+    let client_key = write_pem(server.port, "client-key", server.client_key())?;
+    let tls = Connector::new(&cli::Tls {
+        ca_cert: Some(ca_cert.clone()),
+        client_cert: Some(client_cert.clone()),
+        client_key: Some(client_key.clone()),
+        ..Default::default()
+    })?;
+
+    let report = Worker::run(
+        Target::local(server.port).tls(Some(tls)),
+        Default::default(),
+    )
+    .await;
+    assert!(
+        report.success,
+        "request should succeed: {:?}",
+        report.failure
+    );
+    assert_eq!(server.reqs_received(), 1);
+
+    for path in [ca_cert, client_cert, client_key] {
+        std::fs::remove_file(path)?;
+    }
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn worker_without_a_client_certificate_is_rejected() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::builder().client_auth(true).start_tls().await?;
+    let ca_cert = write_pem(server.port, "ca", server.ca_cert())?;
+    let tls = Connector::new(&cli::Tls {
+        ca_cert: Some(ca_cert.clone()),
+        ..Default::default()
+    })?;
+
+    let report = Worker::run(
+        Target::local(server.port).tls(Some(tls)),
+        Default::default(),
+    )
+    .await;
+    // NB: in tls 1.3, the server verifies the client after the client's handshake has finished,
+    // so the rejection is observed while the request is being sent, and may be classified as a
+    // closed connection or a failed request.
+    assert!(!report.success, "server should reject the client");
+    assert_eq!(report.status, None, "server should not respond");
+    assert!(report.failure.is_some());
+    assert_eq!(server.reqs_received(), 0);
+
+    std::fs::remove_file(ca_cert)?;
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
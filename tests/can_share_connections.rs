@@ -0,0 +1,47 @@
+//! spawn a test server, and send many requests to it upon a pool of shared connections.
+
+use {
+    aquarius::{syndicate::Syndicate, worker::Report},
+    aquarius_test_server::TestServer,
+    futures::StreamExt,
+    std::num::NonZeroU32,
+    tap::Tap,
+    tracing::info,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 64)]
+async fn can_share_connections() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    const COUNT: u32 = 32;
+    const CONNECTIONS: u32 = 2;
+
+    let server = TestServer::start().await?;
+    info!("test server is running");
+
+    let syndicate = Syndicate::local(server.port)
+        .total(Some(COUNT))
+        .rps(Some(64))
+        .connections(NonZeroU32::new(CONNECTIONS))
+        .start()?;
+    info!("workers are running");
+
+    let reports = syndicate.collect::<Vec<Report>>().await;
+    info!("collected reports");
+
+    assert_eq!(reports.len(), COUNT as usize);
+    assert!(
+        reports.iter().all(|r| r.success),
+        "all requests should succeed"
+    );
+    assert_eq!(server.reqs_received(), COUNT);
+    assert!(
+        server.conns_accepted() <= CONNECTIONS,
+        "requests should share connections, but {} were accepted",
+        server.conns_accepted()
+    );
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
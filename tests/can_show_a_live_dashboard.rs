@@ -0,0 +1,41 @@
+//! run the `aquarius` binary with a live dashboard, while writing a summary to stdout.
+
+use {
+    aquarius_test_server::TestServer,
+    std::process::{Command, Stdio},
+    tap::Tap,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn live_dashboards_do_not_mix_into_summaries() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    const COUNT: u64 = 8;
+
+    // NB: stdout is piped, so it is not a terminal, and lines are logged in place of a dashboard.
+    let server = TestServer::start().await?;
+    let port = server.port;
+    let output = tokio::task::spawn_blocking(move || {
+        Command::new(env!("CARGO_BIN_EXE_aquarius"))
+            .args(["--total", &COUNT.to_string(), "--rate", "64"])
+            .args(["--live", "--output", "json"])
+            .arg(format!("[::1]:{port}"))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+    })
+    .await??;
+    assert!(output.status.success());
+
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(summary["total"], COUNT);
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(
+        stderr.contains(&format!("{COUNT} requests (100%)")),
+        "dashboard should be logged to stderr: {stderr}"
+    );
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
@@ -0,0 +1,74 @@
+//! spawn a long-running load-test, and stop it before it has finished.
+
+use {
+    aquarius::{
+        syndicate::Syndicate,
+        worker::{Failure, Report},
+    },
+    aquarius_test_server::{Latency, TestServer},
+    futures::StreamExt,
+    std::time::{Duration, Instant},
+    tap::Tap,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn stopped_syndicates_report_finished_workers() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::start().await?;
+    let syndicate = Syndicate::local(server.port)
+        .duration(Some(Duration::from_secs(60)))
+        .rps(Some(64))
+        .start()?;
+
+    let stop = syndicate.stopper();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        stop.stop();
+    });
+
+    let start = Instant::now();
+    let reports = syndicate.collect::<Vec<Report>>().await;
+    assert!(
+        start.elapsed() < Duration::from_secs(5),
+        "syndicate should stop"
+    );
+    assert!(!reports.is_empty(), "finished workers should be reported");
+    assert!(reports.iter().all(|report| report.success));
+    assert_eq!(server.reqs_received(), reports.len() as u32);
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn stopped_syndicates_cancel_slow_workers() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::builder()
+        .latency(Latency::Fixed(Duration::from_secs(60)))
+        .start()
+        .await?;
+    let syndicate = Syndicate::local(server.port)
+        .duration(Some(Duration::from_secs(60)))
+        .rps(Some(64))
+        .grace(Some(Duration::from_millis(100)))
+        .start()?;
+
+    let stop = syndicate.stopper();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        stop.stop();
+    });
+
+    let reports = syndicate.collect::<Vec<Report>>().await;
+    assert!(!reports.is_empty(), "cancelled workers should be reported");
+    for report in reports {
+        assert_eq!(report.failure, Some(Failure::Cancelled));
+    }
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
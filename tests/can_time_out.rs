@@ -0,0 +1,147 @@
+//! spawn workers against servers that never respond, and observe them time out.
+
+use {
+    aquarius::{
+        cli,
+        syndicate::Syndicate,
+        tls::Connector,
+        worker::{Failure, Phase, Report, Target, Timeouts, Worker},
+    },
+    aquarius_test_server::{Latency, TestServer},
+    futures::StreamExt,
+    std::time::Duration,
+    tap::Tap,
+    tokio::net::TcpListener,
+};
+
+const TIMEOUT: Duration = Duration::from_millis(50);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn slow_responses_time_out() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    const COUNT: u32 = 4;
+
+    let server = TestServer::builder()
+        .latency(Latency::Fixed(Duration::from_secs(60)))
+        .start()
+        .await?;
+
+    let reports = Syndicate::local(server.port)
+        .total(Some(COUNT))
+        .rps(Some(64))
+        .timeouts(Timeouts {
+            request: Some(TIMEOUT),
+            ..Default::default()
+        })
+        .start()?
+        .collect::<Vec<Report>>()
+        .await;
+
+    assert_eq!(reports.len(), COUNT as usize);
+    for report in reports {
+        assert!(!report.success, "slow responses should not succeed");
+        assert_eq!(report.failure, Some(Failure::Timeout(Phase::Request)));
+        assert!(report.duration >= TIMEOUT, "{:?}", report.duration);
+        assert!(report.phases.get(Phase::Request) >= Some(TIMEOUT));
+    }
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn silent_servers_time_out_during_handshake() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    // accept connections, but never write anything to them.
+    let listener = TcpListener::bind("[::1]:0").await?;
+    let port = listener.local_addr()?.port();
+    let silent = tokio::spawn(async move {
+        let mut conns = vec![];
+        while let Ok((conn, _)) = listener.accept().await {
+            conns.push(conn);
+        }
+    });
+
+    let tls = Connector::new(&cli::Tls::default())?;
+    let report = Worker::run(
+        Target::local(port).tls(Some(tls)).timeouts(Timeouts {
+            handshake: Some(TIMEOUT),
+            ..Default::default()
+        }),
+        Default::default(),
+    )
+    .await;
+    assert_eq!(report.failure, Some(Failure::Timeout(Phase::Tls)));
+    assert!(report.phases.get(Phase::Connect).is_some(), "tcp connected");
+
+    silent.abort();
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn total_timeouts_bound_the_whole_worker() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::builder()
+        .latency(Latency::Fixed(Duration::from_secs(60)))
+        .start()
+        .await?;
+
+    let report = Worker::run(
+        Target::local(server.port).timeouts(Timeouts {
+            request: Some(Duration::from_secs(30)),
+            total: Some(TIMEOUT),
+            ..Default::default()
+        }),
+        Default::default(),
+    )
+    .await;
+    assert_eq!(report.failure, Some(Failure::TotalTimeout));
+    assert!(report.duration >= TIMEOUT, "{:?}", report.duration);
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn total_timeouts_are_reported_distinctly() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    // accept connections, but never write anything to them.
+    let listener = TcpListener::bind("[::1]:0").await?;
+    let port = listener.local_addr()?.port();
+    let silent = tokio::spawn(async move {
+        let mut conns = vec![];
+        while let Ok((conn, _)) = listener.accept().await {
+            conns.push(conn);
+        }
+    });
+
+    // the total timeout elapses during the tls handshake, before any phase times out.
+    let tls = Connector::new(&cli::Tls::default())?;
+    let report = Worker::run(
+        Target::local(port).tls(Some(tls)).timeouts(Timeouts {
+            connect: Some(Duration::from_secs(30)),
+            handshake: Some(Duration::from_secs(30)),
+            total: Some(TIMEOUT),
+            ..Default::default()
+        }),
+        Default::default(),
+    )
+    .await;
+    assert_eq!(report.failure, Some(Failure::TotalTimeout));
+    assert!(
+        report.phases.get(Phase::Tls) > Some(Duration::ZERO),
+        "tls was interrupted"
+    );
+    assert_eq!(report.phases.get(Phase::Request), None);
+
+    silent.abort();
+    Ok(()).tap(|_| drop(guard))
+}
@@ -0,0 +1,61 @@
+//! spawn a test server, and observe the time that workers spend in each phase.
+
+use {
+    aquarius::worker::{Phase, Pool, Target, Worker},
+    aquarius_test_server::TestServer,
+    std::{num::NonZeroU32, sync::Arc},
+    tap::Tap,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn worker_times_each_phase() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::start().await?;
+    let report = Worker::run_local(server.port).await;
+    assert!(report.success, "request should succeed");
+
+    // a cleartext connection does not perform a tls handshake.
+    let phases = report
+        .phases
+        .iter()
+        .map(|(phase, _)| phase)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        phases,
+        vec![
+            Phase::Dns,
+            Phase::Connect,
+            Phase::Handshake,
+            Phase::Request,
+            Phase::Body
+        ]
+    );
+    let total: std::time::Duration = report.phases.iter().map(|(_, d)| d).sum();
+    assert!(report.duration >= total);
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn pooled_workers_skip_connecting() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::start().await?;
+    let pool = Pool::new(Target::local(server.port), NonZeroU32::MIN);
+    let request = Arc::default();
+
+    // the first worker opens the pooled connection, the second reuses it.
+    let first = Worker::run_pooled(pool.clone(), Arc::clone(&request)).await;
+    let second = Worker::run_pooled(pool, request).await;
+    assert!(first.phases.get(Phase::Handshake).is_some());
+    assert!(second.phases.get(Phase::Dns).is_none());
+    assert!(second.phases.get(Phase::Handshake).is_none());
+    assert!(second.phases.get(Phase::Request).is_some());
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
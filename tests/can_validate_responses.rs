@@ -0,0 +1,129 @@
+//! spawn a test server, and check its responses against validation rules.
+
+use {
+    aquarius::{
+        syndicate::Syndicate,
+        worker::{BodyRule, Failure, Report, Sha256, Template, Validation, Violation},
+    },
+    aquarius_test_server::TestServer,
+    futures::StreamExt,
+    http::{uri::PathAndQuery, Method, StatusCode},
+    hyper::body::Bytes,
+    tap::{Pipe, Tap},
+};
+
+const COUNT: u32 = 4;
+
+/// sends requests with the given template, returning the reports.
+async fn send(port: u16, request: Template) -> Result<Vec<Report>, aquarius::error::Error> {
+    Syndicate::local(port)
+        .total(Some(COUNT))
+        .rps(Some(64))
+        .request(request)
+        .start()?
+        .collect::<Vec<Report>>()
+        .await
+        .tap(|reports| assert_eq!(reports.len(), COUNT as usize))
+        .pipe(Ok)
+}
+
+/// returns a template that posts the given body, which the test server echoes back.
+fn echo(body: &'static str) -> Template {
+    Template::new(Method::POST, PathAndQuery::from_static("/")).body(Bytes::from(body))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn valid_responses_succeed() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::start().await?;
+    const BODY: &str = r#"{"status": "ok"}"#;
+    let validation = Validation {
+        statuses: vec!["200".parse()?],
+        body: vec![
+            BodyRule::Json("/status=ok".parse()?),
+            BodyRule::Regex("ok".parse()?),
+        ],
+        max_body_size: Some(BODY.len()),
+        sha256: Some(Sha256::digest(BODY.as_bytes())),
+        ..Default::default()
+    };
+
+    for report in send(server.port, echo(BODY).validation(validation)).await? {
+        assert!(report.success, "response should be valid");
+        assert_eq!(report.failure, None);
+    }
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn error_pages_are_invalid() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    // the server responds quickly with `200 OK`, but the body describes an error.
+    let server = TestServer::start().await?;
+    let validation = Validation {
+        body: vec![BodyRule::Json("/status=ok".parse()?)],
+        ..Default::default()
+    };
+
+    let request = echo("<html>internal server error</html>").validation(validation);
+    for report in send(server.port, request).await? {
+        assert!(!report.success, "error pages should not succeed");
+        assert_eq!(report.status, Some(StatusCode::OK));
+        assert_eq!(report.failure, Some(Failure::Invalid(Violation::Body)));
+    }
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn unexpected_statuses_are_invalid() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::builder().error_rate(100.0).start().await?;
+    let validation = Validation {
+        statuses: vec!["2xx".parse()?],
+        ..Default::default()
+    };
+
+    let request = Template::default().validation(validation);
+    for report in send(server.port, request).await? {
+        assert!(!report.success, "errors should not succeed");
+        assert_eq!(report.status, Some(StatusCode::SERVICE_UNAVAILABLE));
+        assert_eq!(report.failure, Some(Failure::Invalid(Violation::Status)));
+    }
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn large_bodies_are_invalid() -> Result<(), aquarius::error::Error> {
+    let guard = aquarius_test_subscriber::set_default();
+    aquarius_test_timeout::spawn();
+
+    let server = TestServer::builder()
+        .body_size(Some(64 * 1024))
+        .start()
+        .await?;
+    let validation = Validation {
+        max_body_size: Some(1024),
+        ..Default::default()
+    };
+
+    let request = Template::default().validation(validation);
+    for report in send(server.port, request).await? {
+        assert!(!report.success, "large bodies should not succeed");
+        assert_eq!(report.failure, Some(Failure::Invalid(Violation::BodySize)));
+    }
+
+    server.finish().await?;
+    Ok(()).tap(|_| drop(guard))
+}